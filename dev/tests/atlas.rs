@@ -0,0 +1,187 @@
+use once_cell::sync::Lazy;
+use rusttype::{atlas::*, *};
+
+static DEJA_VU_MONO: Lazy<Font<'static>> = Lazy::new(|| {
+    Font::try_from_bytes(include_bytes!("../fonts/dejavu/DejaVuSansMono.ttf") as &[u8]).unwrap()
+});
+static OPEN_SANS_ITALIC: Lazy<Font<'static>> = Lazy::new(|| {
+    Font::try_from_bytes(include_bytes!("../fonts/opensans/OpenSans-Italic.ttf") as &[u8]).unwrap()
+});
+
+fn builder() -> AtlasBuilder<'static> {
+    AtlasBuilder::new()
+        .font(DEJA_VU_MONO.clone())
+        .font(OPEN_SANS_ITALIC.clone())
+        .chars(' '..='~')
+        .sizes([12.0, 24.0, 12.0])
+        .width(256)
+}
+
+#[test]
+fn atlas_glyphs_match_draw() {
+    let atlas = builder().build().unwrap();
+    let fonts = [&*DEJA_VU_MONO, &*OPEN_SANS_ITALIC];
+    assert_eq!(atlas.faces.len(), 4);
+    assert_eq!(atlas.glyphs.len(), 2 * 2 * 95);
+    assert_eq!(atlas.pixels.len(), (atlas.width * atlas.height) as usize);
+
+    for g in &atlas.glyphs {
+        let font = fonts[g.font_index];
+        let scale = Scale::uniform(g.size);
+        let glyph = font.glyph(g.character).scaled(scale);
+        assert_eq!(g.glyph_id, glyph.id().0);
+        assert_eq!(g.advance, glyph.h_metrics().advance_width);
+
+        let glyph = glyph.positioned(point(0.0, 0.0));
+        let Some(bb) = glyph.pixel_bounding_box() else {
+            assert_eq!(g.tex_size(), [0, 0], "{g:?}");
+            continue;
+        };
+        assert_eq!(g.offset, [bb.min.x, bb.min.y]);
+        assert_eq!(g.tex_size(), [bb.width() as u32, bb.height() as u32]);
+        assert_eq!(g.uv[0], g.tex_min[0] as f32 / atlas.width as f32, "{g:?}");
+        glyph.draw(|x, y, v| {
+            let i = (g.tex_min[1] + y) * atlas.width + g.tex_min[0] + x;
+            assert_eq!(atlas.pixels[i as usize], (v * 255.0 + 0.5) as u8);
+        });
+    }
+
+    // glyph rects are padded & don't overlap
+    let rects: Vec<_> = atlas
+        .glyphs
+        .iter()
+        .filter(|g| g.tex_size() != [0, 0])
+        .map(|g| (g.tex_min, g.tex_max))
+        .collect();
+    for (i, &(a_min, a_max)) in rects.iter().enumerate() {
+        assert!(a_min[0] >= 1 && a_min[1] >= 1);
+        assert!(a_max[0] < atlas.width && a_max[1] < atlas.height);
+        for &(b_min, b_max) in &rects[i + 1..] {
+            let same = a_min == b_min && a_max == b_max;
+            let apart = a_max[0] < b_min[0]
+                || b_max[0] < a_min[0]
+                || a_max[1] < b_min[1]
+                || b_max[1] < a_min[1];
+            assert!(
+                same || apart,
+                "{a_min:?}..{a_max:?} overlaps {b_min:?}..{b_max:?}"
+            );
+        }
+    }
+
+    let face = atlas.face(1, 24.0).unwrap();
+    assert_eq!(
+        face.ascent,
+        OPEN_SANS_ITALIC.v_metrics(Scale::uniform(24.0)).ascent
+    );
+    assert!(atlas.glyph(0, 'a', 13.0).is_none());
+}
+
+#[test]
+fn atlas_options() {
+    // missing chars share the .notdef texture
+    let atlas = AtlasBuilder::new()
+        .font(DEJA_VU_MONO.clone())
+        .chars(['\u{e000}', '\u{e001}'])
+        .sizes([20.0])
+        .power_of_two(true)
+        .build()
+        .unwrap();
+    assert_eq!(atlas.glyphs[0].tex_min, atlas.glyphs[1].tex_min);
+    assert!(atlas.height.is_power_of_two());
+
+    assert_eq!(builder().width(8).build(), Err(AtlasError::GlyphTooWide));
+
+    // padding overflowing the width or the height is an error, not a panic
+    assert_eq!(
+        builder().padding(u32::MAX).build(),
+        Err(AtlasError::GlyphTooWide)
+    );
+    let tall = AtlasBuilder::new()
+        .font(DEJA_VU_MONO.clone())
+        .chars(['a', 'b'])
+        .sizes([20.0])
+        .width(u32::MAX)
+        .padding((u32::MAX - 40) / 2);
+    assert_eq!(tall.build(), Err(AtlasError::GlyphTooWide));
+}
+
+#[test]
+fn atlas_kernings() {
+    assert!(builder().build().unwrap().kernings.is_empty());
+
+    let atlas = builder().kerning(true).build().unwrap();
+    let scale = Scale::uniform(24.0);
+    let av = atlas
+        .kernings
+        .iter()
+        .find(|k| k.font_index == 1 && k.size == 24.0 && (k.first, k.second) == ('A', 'V'))
+        .unwrap();
+    assert_eq!(av.amount, OPEN_SANS_ITALIC.pair_kerning(scale, 'A', 'V'));
+    assert!(atlas.kernings.iter().all(|k| k.amount != 0.0));
+    // monospace
+    assert!(atlas.kernings.iter().all(|k| k.font_index == 1));
+}
+
+#[test]
+fn bmfont_export() {
+    let atlas = builder().kerning(true).build().unwrap();
+    let text = atlas
+        .to_bmfont(
+            1,
+            24.0,
+            BmFontFormat::Text,
+            "Open \"Sans\"",
+            "open_sans.png",
+        )
+        .unwrap();
+    let lines: Vec<_> = text.lines().collect();
+    assert_eq!(lines[0].split(' ').nth(1), Some("face=\"Open"));
+    assert!(lines[0].contains("size=24 "));
+    let face = atlas.face(1, 24.0).unwrap();
+    assert_eq!(
+        lines[1],
+        format!(
+            "common lineHeight={} base={} scaleW={} scaleH={} pages=1 packed=0",
+            (face.ascent - face.descent + face.line_gap).round(),
+            face.ascent.round(),
+            atlas.width,
+            atlas.height
+        )
+    );
+    assert_eq!(lines[2], "page id=0 file=\"open_sans.png\"");
+    assert_eq!(lines[3], "chars count=95");
+
+    let a = atlas.glyph(1, 'a', 24.0).unwrap();
+    let expected_a = format!(
+        "char id=97 x={} y={} width={} height={} xoffset={} yoffset={} xadvance={} page=0 chnl=15",
+        a.tex_min[0],
+        a.tex_min[1],
+        a.tex_size()[0],
+        a.tex_size()[1],
+        a.offset[0],
+        face.ascent.round() as i32 + a.offset[1],
+        a.advance.round(),
+    );
+    assert!(lines.contains(&expected_a.as_str()), "{expected_a}");
+
+    let kernings = lines
+        .iter()
+        .position(|l| l.starts_with("kernings count="))
+        .unwrap();
+    let count: usize = lines[kernings]["kernings count=".len()..].parse().unwrap();
+    assert!(count > 0);
+    assert_eq!(lines.len(), kernings + 1 + count);
+    assert!(lines.contains(&"kerning first=65 second=86 amount=-1"));
+
+    let xml = atlas
+        .to_bmfont(1, 24.0, BmFontFormat::Xml, "Open \"Sans\"", "a&b.png")
+        .unwrap();
+    assert!(xml.contains("<info face=\"Open &quot;Sans&quot;\" size=\"24\""));
+    assert!(xml.contains("<page id=\"0\" file=\"a&amp;b.png\"/>"));
+    assert!(xml.contains("<char id=\"97\" "));
+    assert!(xml.trim_end().ends_with("</font>"));
+    assert_eq!(xml.matches("<char ").count(), 95);
+
+    assert_eq!(atlas.to_bmfont(2, 24.0, BmFontFormat::Text, "", ""), None);
+}
@@ -0,0 +1,93 @@
+use once_cell::sync::Lazy;
+use rusttype::*;
+
+static WQY_MICRO_HEI: Lazy<Font<'static>> = Lazy::new(|| {
+    Font::try_from_bytes(include_bytes!("../fonts/wqy-microhei/WenQuanYiMicroHei.ttf") as &[u8])
+        .unwrap()
+});
+static DEJA_VU_MONO: Lazy<Font<'static>> = Lazy::new(|| {
+    Font::try_from_bytes(include_bytes!("../fonts/dejavu/DejaVuSansMono.ttf") as &[u8]).unwrap()
+});
+
+#[test]
+fn draw_row_blits_cached_cells() {
+    let mut renderer = CellRenderer::new(DEJA_VU_MONO.clone(), Scale::uniform(14.0));
+    let (cell_width, cell_height) = renderer.cell_size();
+    let (cell_width, cell_height) = (cell_width as usize, cell_height as usize);
+
+    // 3 columns wide, so the 4th cell is clipped
+    let stride = 3 * cell_width;
+    let mut buffer = vec![7; stride * 2 * cell_height];
+    renderer.draw_row(
+        "abab".chars().map(|c| (c, CellStyle::NONE)),
+        &mut buffer,
+        stride,
+        1,
+    );
+    assert_eq!(renderer.cached_len(), 2);
+
+    // first row untouched
+    assert!(buffer[..stride * cell_height].iter().all(|&v| v == 7));
+
+    let b = renderer.cell('b', CellStyle::NONE).to_vec();
+    for y in 0..cell_height {
+        let start = (cell_height + y) * stride + cell_width;
+        assert_eq!(
+            &buffer[start..start + cell_width],
+            &b[y * cell_width..(y + 1) * cell_width]
+        );
+    }
+}
+
+#[test]
+fn underline_style() {
+    let mut renderer = CellRenderer::new(DEJA_VU_MONO.clone(), Scale::uniform(14.0));
+    let width = renderer.cell_size().0 as usize;
+
+    let plain = renderer.cell(' ', CellStyle::NONE).to_vec();
+    let underlined = renderer.cell(' ', CellStyle::UNDERLINE).to_vec();
+    assert!(plain.iter().all(|&v| v == 0));
+    let full_rows = underlined
+        .chunks(width)
+        .filter(|row| row.iter().all(|&v| v == 255))
+        .count();
+    assert!(full_rows >= 1);
+
+    let both = renderer.cell(' ', CellStyle::UNDERLINE | CellStyle::STRIKETHROUGH);
+    assert!(both.iter().filter(|&&v| v == 255).count() > full_rows * width);
+    assert_eq!(renderer.cached_len(), 3);
+}
+
+#[test]
+fn draw_text_row_cell_widths() {
+    let mut renderer = CellRenderer::new(WQY_MICRO_HEI.clone(), Scale::uniform(14.0));
+    let (cell_width, cell_height) = renderer.cell_size();
+    let (cell_width, cell_height) = (cell_width as usize, cell_height as usize);
+    let stride = 5 * cell_width;
+    let mut row = |text: &str| -> Vec<u8> {
+        let mut buffer = vec![0; stride * cell_height];
+        let chars = text.chars().map(|c| (c, CellStyle::NONE));
+        renderer.draw_text_row(chars, &mut buffer, stride, 0);
+        buffer
+    };
+    let columns = |buffer: &[u8]| -> Vec<bool> {
+        (0..5)
+            .map(|column| {
+                (0..cell_height).any(|y| {
+                    let start = y * stride + column * cell_width;
+                    buffer[start..start + cell_width].iter().any(|&v| v > 0)
+                })
+            })
+            .collect()
+    };
+
+    // the wide char spans columns 1 & 2, "b" follows in column 3
+    let wide = row("a\u{4e16}b");
+    assert_eq!(columns(&wide), [true, true, true, true, false]);
+    // zero width chars are skipped
+    assert_eq!(wide, row("a\u{301}\u{4e16}b"));
+    let mut narrow = vec![0; stride * cell_height];
+    let cells = "a\u{4e16}b".chars().map(|c| (c, CellStyle::NONE));
+    renderer.draw_row(cells, &mut narrow, stride, 0);
+    assert_eq!(columns(&narrow), [true, true, true, false, false]);
+}
@@ -0,0 +1,104 @@
+use rusttype::ffi::*;
+use rusttype::{point, Font, Scale};
+
+const FONT_DATA: &[u8] = include_bytes!("../fonts/dejavu/DejaVuSansMono.ttf");
+
+#[test]
+fn rasterize_matches_draw() {
+    let font = Font::try_from_bytes(FONT_DATA).unwrap();
+    let expected_glyph = font
+        .glyph('g')
+        .scaled(Scale::uniform(30.0))
+        .positioned(point(0.5, 0.0));
+    let expected_bb = expected_glyph.pixel_bounding_box().unwrap();
+
+    unsafe {
+        let ffi_font = rusttype_font_from_bytes(FONT_DATA.as_ptr(), FONT_DATA.len(), 0);
+        assert!(!ffi_font.is_null());
+        let glyph = rusttype_font_glyph_id(ffi_font, 'g' as u32);
+        assert_eq!(glyph, expected_glyph.id().0);
+
+        let mut bounds = RusttypeRect::default();
+        assert!(rusttype_glyph_pixel_bounds(
+            ffi_font,
+            glyph,
+            30.0,
+            0.5,
+            0.0,
+            &mut bounds
+        ));
+        assert_eq!(
+            (bounds.min_x, bounds.min_y, bounds.max_x, bounds.max_y),
+            (
+                expected_bb.min.x,
+                expected_bb.min.y,
+                expected_bb.max.x,
+                expected_bb.max.y
+            )
+        );
+
+        let (width, height) = (expected_bb.width() as u32, expected_bb.height() as u32);
+        let stride = width as usize + 3;
+        let mut pixels = vec![0; stride * height as usize];
+        assert!(rusttype_glyph_rasterize(
+            ffi_font,
+            glyph,
+            30.0,
+            0.5,
+            0.0,
+            pixels.as_mut_ptr(),
+            width,
+            height,
+            stride
+        ));
+        expected_glyph.draw(|x, y, v| {
+            assert_eq!(
+                pixels[y as usize * stride + x as usize],
+                (v * 255.0).round() as u8
+            );
+        });
+
+        // invalid buffer sizes are rejected without writing
+        let mut rasterize = |width, height, stride| {
+            rusttype_glyph_rasterize(
+                ffi_font,
+                glyph,
+                30.0,
+                0.5,
+                0.0,
+                pixels.as_mut_ptr(),
+                width,
+                height,
+                stride,
+            )
+        };
+        assert!(!rasterize(width, height, width as usize - 1));
+        assert!(!rasterize(width, height, usize::MAX / 2));
+
+        let space = rusttype_font_glyph_id(ffi_font, ' ' as u32);
+        assert!(!rusttype_glyph_pixel_bounds(
+            ffi_font,
+            space,
+            30.0,
+            0.0,
+            0.0,
+            &mut bounds
+        ));
+
+        let mut h = RusttypeHMetrics::default();
+        assert!(rusttype_glyph_h_metrics(ffi_font, space, 30.0, &mut h));
+        assert!(h.advance_width > 0.0);
+        assert!(!rusttype_glyph_h_metrics(ffi_font, u16::MAX, 30.0, &mut h));
+
+        rusttype_font_free(ffi_font);
+    }
+}
+
+#[test]
+fn invalid_font_data() {
+    unsafe {
+        assert!(rusttype_font_from_bytes(b"nope".as_ptr(), 4, 0).is_null());
+        assert!(rusttype_font_from_bytes(std::ptr::null(), 0, 0).is_null());
+        rusttype_font_free(std::ptr::null_mut());
+    }
+}
@@ -0,0 +1,1032 @@
+use once_cell::sync::Lazy;
+use rusttype::*;
+use std::collections::HashSet;
+
+static DEJA_VU_MONO: Lazy<Font<'static>> = Lazy::new(|| {
+    Font::try_from_bytes(include_bytes!("../fonts/dejavu/DejaVuSansMono.ttf") as &[u8]).unwrap()
+});
+static ROBOTO_REGULAR: Lazy<Font<'static>> = Lazy::new(|| {
+    Font::try_from_bytes(include_bytes!("../fonts/Roboto-Regular.ttf") as &[u8]).unwrap()
+});
+
+#[test]
+fn glyph_key_equality() {
+    let font = &*DEJA_VU_MONO;
+    let a = font.glyph('a').scaled(Scale::uniform(20.0));
+
+    assert_eq!(
+        a.key(),
+        font.clone().glyph('a').scaled(Scale::uniform(20.0)).key()
+    );
+    assert_ne!(a.key(), font.glyph('b').scaled(Scale::uniform(20.0)).key());
+    assert_ne!(a.key(), font.glyph('a').scaled(Scale::uniform(21.0)).key());
+    assert_ne!(
+        a.key(),
+        ROBOTO_REGULAR.glyph('a').scaled(Scale::uniform(20.0)).key()
+    );
+
+    let p = a.clone().positioned(point(0.0, 5.5));
+    assert_ne!(a.key(), p.key());
+    assert_eq!(p, a.clone().positioned(point(-0.0, 5.5)));
+    assert_ne!(p, a.positioned(point(0.0, 5.25)));
+    assert_eq!(p.key().position(), Some(point(0.0, 5.5)));
+}
+
+#[test]
+fn positioned_glyph_hash_dedup() {
+    let glyphs: HashSet<_> = DEJA_VU_MONO
+        .layout("abab", Scale::uniform(16.0), point(0.0, 0.0))
+        .chain(DEJA_VU_MONO.layout("abab", Scale::uniform(16.0), point(0.0, 0.0)))
+        .collect();
+    assert_eq!(glyphs.len(), 4);
+}
+
+#[test]
+fn pixel_rounding_modes() {
+    let glyph = DEJA_VU_MONO.glyph('a').scaled(Scale::uniform(20.3));
+    let exact = glyph.exact_bounding_box().unwrap();
+    let at = point(0.0, 0.0);
+    let bounds = |rounding| {
+        glyph
+            .clone()
+            .positioned_with_rounding(at, rounding)
+            .pixel_bounding_box()
+            .unwrap()
+    };
+
+    let conservative = bounds(PixelRounding::Conservative);
+    assert_eq!(
+        Some(conservative),
+        glyph.clone().positioned(at).pixel_bounding_box()
+    );
+    assert_eq!(conservative.min.x, exact.min.x.floor() as i32);
+    assert_eq!(conservative.max.y, exact.max.y.ceil() as i32);
+
+    let floor = bounds(PixelRounding::Floor);
+    assert_eq!(floor.min, conservative.min);
+    assert_eq!(floor.max.x, exact.max.x.floor() as i32);
+
+    let round = bounds(PixelRounding::Round);
+    assert_eq!(round.min.y, exact.min.y.round() as i32);
+    assert_eq!(round.max.x, exact.max.x.round() as i32);
+
+    let mut moved = glyph.positioned_with_rounding(at, PixelRounding::Round);
+    moved.set_position(point(3.0, -2.0));
+    assert_eq!(moved.rounding(), PixelRounding::Round);
+    assert_eq!(
+        moved.pixel_bounding_box().unwrap().min,
+        round.min + vector(3, -2)
+    );
+}
+
+#[test]
+fn pixel_extents_bound_all_positions() {
+    for c in "aWg|.".chars() {
+        let glyph = ROBOTO_REGULAR.glyph(c).scaled(Scale::uniform(17.3));
+        let extents = glyph.pixel_extents().unwrap();
+        let (mut max_width, mut max_height) = (0, 0);
+        for step in 0..20 {
+            let offset = step as f32 / 20.0;
+            let bb = glyph
+                .clone()
+                .positioned(point(offset, offset))
+                .pixel_bounding_box()
+                .unwrap();
+            max_width = max_width.max(bb.width() as u32);
+            max_height = max_height.max(bb.height() as u32);
+        }
+        assert!(max_width <= extents.x && max_height <= extents.y);
+        assert!(extents.x - max_width <= 1 && extents.y - max_height <= 1);
+    }
+    assert_eq!(
+        ROBOTO_REGULAR
+            .glyph(' ')
+            .scaled(Scale::uniform(17.3))
+            .pixel_extents(),
+        None
+    );
+}
+
+#[test]
+fn ab_glyph_conversions() {
+    use ab_glyph::{Font as _, ScaleFont as _};
+
+    let ab_font = ROBOTO_REGULAR.to_ab_glyph().unwrap();
+    for c in "Qug7".chars() {
+        let id = ROBOTO_REGULAR.glyph(c).id();
+        assert_eq!(ab_glyph::GlyphId::from(id), ab_font.glyph_id(c));
+        assert_eq!(GlyphId::from(ab_font.glyph_id(c)), id);
+
+        let scale = Scale::uniform(24.0);
+        let advance = ROBOTO_REGULAR
+            .glyph(c)
+            .scaled(scale)
+            .h_metrics()
+            .advance_width;
+        let ab_advance = ab_font
+            .as_scaled(ab_glyph::PxScale::from(scale))
+            .h_advance(id.into());
+        assert!(
+            (advance - ab_advance).abs() < 1e-4,
+            "{advance} != {ab_advance}"
+        );
+    }
+
+    let rect = Rect {
+        min: point(1.5, -2.0),
+        max: point(3.0, 4.25),
+    };
+    assert_eq!(Rect::from(ab_glyph::Rect::from(rect)), rect);
+}
+
+#[test]
+fn lyon_positioned_path() {
+    let glyph = DEJA_VU_MONO.glyph('A').scaled(Scale::uniform(30.0));
+    let events: Vec<_> = glyph.path_events().collect();
+    assert!(!events.is_empty());
+
+    let positioned = glyph.clone().positioned(point(100.0, 50.0));
+    let path = positioned.lyon_path().unwrap();
+    for (event, moved) in events.iter().zip(path.iter()) {
+        let (from, to) = (event.to(), moved.to());
+        assert!((to.x - from.x - 100.0).abs() < 1e-3 && (to.y - from.y - 50.0).abs() < 1e-3);
+    }
+
+    assert!(DEJA_VU_MONO
+        .glyph(' ')
+        .scaled(Scale::uniform(30.0))
+        .lyon_path()
+        .is_none());
+}
+
+#[test]
+fn extreme_positions_do_not_overflow() {
+    let glyph = DEJA_VU_MONO.glyph('W').scaled(Scale::uniform(40.0));
+    for &p in &[
+        point(2.0e9, -2.0e9),
+        point(-3.0e9, 3.0e9),
+        point(f32::MAX, f32::MIN),
+    ] {
+        let mut positioned = glyph.clone().positioned(p);
+        let bb = positioned.pixel_bounding_box().unwrap();
+        assert!(bb.min.x <= bb.max.x && bb.min.y <= bb.max.y);
+        positioned.set_position(point(p.x * 0.5, p.y * 0.5));
+        positioned.set_position(point(p.x + 1.0, p.y + 1.0));
+    }
+}
+
+#[test]
+fn tiny_skia_fill_matches_draw() {
+    let glyph = ROBOTO_REGULAR
+        .glyph('R')
+        .scaled(Scale::uniform(40.0))
+        .positioned(point(10.3, 40.7));
+    let bb = glyph.pixel_bounding_box().unwrap();
+
+    let mut pixmap = tiny_skia::Pixmap::new(64, 64).unwrap();
+    let mut paint = tiny_skia::Paint::default();
+    paint.set_color_rgba8(255, 255, 255, 255);
+    glyph.fill_tiny_skia(&mut pixmap.as_mut(), &paint, None);
+
+    let mut total_diff = 0.0;
+    glyph.draw(|x, y, v| {
+        let pixel = pixmap
+            .pixel(bb.min.x as u32 + x, bb.min.y as u32 + y)
+            .unwrap();
+        total_diff += (pixel.alpha() as f32 / 255.0 - v).abs();
+    });
+    let area = (bb.width() * bb.height()) as f32;
+    assert!(
+        total_diff / area < 0.02,
+        "mean coverage diff {}",
+        total_diff / area
+    );
+}
+
+#[test]
+fn unscaled_glyph_equality() {
+    let glyphs: HashSet<_> = "hello".chars().map(|c| DEJA_VU_MONO.glyph(c)).collect();
+    assert_eq!(glyphs.len(), 4);
+    assert!(glyphs.contains(&DEJA_VU_MONO.clone().glyph('l')));
+    assert!(!glyphs.contains(&ROBOTO_REGULAR.glyph('l')));
+}
+
+#[test]
+fn hatch_lines_inside_glyph() {
+    let glyph = ROBOTO_REGULAR.glyph('B').scaled(Scale::uniform(60.0));
+    let positioned = glyph.clone().positioned(point(0.0, 0.0));
+    let bb = positioned.pixel_bounding_box().unwrap();
+    let width = bb.width() as usize;
+    let mut coverage = vec![0.0; width * bb.height() as usize];
+    positioned.draw(|x, y, v| coverage[y as usize * width + x as usize] = v);
+
+    for angle in [0.0, 30_f32.to_radians(), 90_f32.to_radians()] {
+        let lines = glyph.hatch_lines(1.5, angle);
+        assert!(lines.len() > 10);
+        for (a, b) in lines {
+            let mid = point((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+            let x = (mid.x.floor() as i32 - bb.min.x) as usize;
+            let y = (mid.y.floor() as i32 - bb.min.y) as usize;
+            assert!(coverage[y * width + x] > 0.0, "{mid:?} outside glyph");
+        }
+    }
+    assert!(glyph.hatch_lines(0.0, 0.0).is_empty());
+    assert!(glyph.hatch_lines(f32::NAN, 0.0).is_empty());
+    assert!(glyph.hatch_lines(f32::INFINITY, 0.0).is_empty());
+    assert!(glyph.hatch_lines(1.5, f32::NAN).is_empty());
+
+    // line count is bounded for tiny spacings
+    let lines = glyph.hatch_lines(1e-6, 0.0);
+    assert!(!lines.is_empty() && lines.len() <= 4 << 16);
+}
+
+#[test]
+fn scanline_crossings_match_coverage() {
+    let glyph = ROBOTO_REGULAR.glyph('m').scaled(Scale::uniform(50.0));
+    let positioned = glyph.clone().positioned(point(0.0, 0.0));
+    let bb = positioned.pixel_bounding_box().unwrap();
+    let width = bb.width() as usize;
+    let mut coverage = vec![0.0; width * bb.height() as usize];
+    positioned.draw(|x, y, v| coverage[y as usize * width + x as usize] = v);
+
+    // sample through the middle of a pixel row
+    let row = bb.height() as usize / 2;
+    let y = bb.min.y as f32 + row as f32 + 0.5;
+    let crossings = glyph.scanline_crossings(y);
+    assert_eq!(crossings.len(), 3, "{crossings:?}");
+    for x in 0..width {
+        let px = bb.min.x as f32 + x as f32 + 0.5;
+        let inside = crossings.iter().any(|&(a, b)| a <= px && px < b);
+        let v = coverage[row * width + x];
+        if inside {
+            assert!(v > 0.4, "x={x} v={v}");
+        } else {
+            assert!(v < 0.6, "x={x} v={v}");
+        }
+    }
+    assert!(glyph.scanline_crossings(1000.0).is_empty());
+}
+
+#[test]
+fn arabic_joining_forms() {
+    let font = &*DEJA_VU_MONO;
+    let ids = |chars: &[char]| -> Vec<_> { chars.iter().map(|&c| font.glyph(c).id()).collect() };
+
+    // beh, beh with a transparent fatha, alef, beh
+    let glyphs = font.arabic_glyphs("\u{628}\u{628}\u{64e}\u{627} \u{628}");
+    // the font has no `isol` feature, so the isolated beh is unchanged
+    let expected = ids(&[
+        '\u{fe91}', '\u{fe92}', '\u{64e}', '\u{fe8e}', ' ', '\u{628}',
+    ]);
+    assert_eq!(glyphs, expected);
+
+    // lam-alef ligature
+    assert_eq!(font.arabic_glyphs("\u{644}\u{627}"), ids(&['\u{fefb}']));
+    assert_eq!(
+        font.arabic_glyphs("\u{628}\u{644}\u{627}"),
+        ids(&['\u{fe91}', '\u{fefc}'])
+    );
+
+    // a zero width non-joiner breaks joining, a joiner doesn't
+    assert_eq!(
+        font.arabic_glyphs("\u{628}\u{200c}\u{628}")[0],
+        font.glyph('\u{628}').id()
+    );
+    assert_eq!(
+        font.arabic_glyphs("\u{628}\u{200d}")[0],
+        font.glyph('\u{fe91}').id()
+    );
+
+    // other scripts are unaffected
+    assert_eq!(font.arabic_glyphs("ab"), ids(&['a', 'b']));
+}
+
+#[test]
+fn coverage_ignores_default_ignorable() {
+    let report = DEJA_VU_MONO.coverage("a\u{200d}b\u{fe0f}\u{2060}\n");
+    assert!(report.is_complete(), "{report:?}");
+    assert_eq!(report.checked(), 2);
+}
+
+#[test]
+fn coverage_buffer_box_filter() {
+    #[rustfmt::skip]
+    let buffer = CoverageBuffer::from_raw(3, 3, vec![
+        255, 255, 0,
+        0,   0,   0,
+        255, 255, 100,
+    ])
+    .unwrap();
+
+    let half = buffer.downsample();
+    assert_eq!((half.width(), half.height()), (2, 2));
+    // full 2x2 block, odd edge blocks average only existing pixels
+    assert_eq!(half.data(), [128, 0, 255, 100]);
+
+    let pyramid = buffer.pyramid();
+    assert_eq!(pyramid.len(), 3);
+    assert_eq!(pyramid[2].data(), [121]);
+
+    assert!(CoverageBuffer::from_raw(2, 2, vec![0; 3]).is_none());
+}
+
+#[test]
+fn font_api_matches_glyph_api() {
+    let font = &*ROBOTO_REGULAR;
+    let api: &dyn FontApi = font;
+    let scale = Scale { x: 30.0, y: 24.0 };
+    let position = point(10.3, 20.7);
+
+    let glyph = font.glyph('g').scaled(scale);
+    let id = api.glyph_id('g');
+    assert_eq!(id, glyph.id());
+    assert_eq!(api.glyph_h_metrics(id, scale), glyph.h_metrics());
+    assert_eq!(
+        api.glyph_bounding_box(id, scale),
+        glyph.exact_bounding_box()
+    );
+    assert_eq!(
+        api.pair_kerning(scale, api.glyph_id('A'), api.glyph_id('V')),
+        font.pair_kerning(scale, 'A', 'V')
+    );
+
+    let glyph = glyph.positioned(position);
+    assert_eq!(
+        api.pixel_bounding_box(id, scale, position),
+        glyph.pixel_bounding_box()
+    );
+    let mut expected = Vec::new();
+    glyph.draw(|x, y, v| expected.push((x, y, v)));
+    let mut drawn = Vec::new();
+    api.draw(id, scale, position, &mut |x, y, v| drawn.push((x, y, v)));
+    assert!(!drawn.is_empty());
+    assert_eq!(drawn, expected);
+}
+
+/// A font with a single square glyph, 500 units wide, for every char.
+struct SquareFont;
+
+impl FontApi for SquareFont {
+    fn units_per_em(&self) -> u16 {
+        1000
+    }
+    fn glyph_count(&self) -> usize {
+        1
+    }
+    fn glyph_id(&self, _: char) -> GlyphId {
+        GlyphId(0)
+    }
+    fn v_metrics_unscaled(&self) -> VMetrics {
+        VMetrics {
+            ascent: 800.0,
+            descent: -200.0,
+            line_gap: 0.0,
+        }
+    }
+    fn glyph_h_metrics_unscaled(&self, _: GlyphId) -> HMetrics {
+        HMetrics {
+            advance_width: 600.0,
+            left_side_bearing: 100.0,
+        }
+    }
+    fn pair_kerning_unscaled(&self, _: GlyphId, _: GlyphId) -> f32 {
+        -100.0
+    }
+    fn glyph_bounding_box_unscaled(&self, _: GlyphId) -> Option<Rect<f32>> {
+        Some(Rect {
+            min: point(100.0, 0.0),
+            max: point(600.0, 500.0),
+        })
+    }
+    fn build_outline_unscaled(&self, _: GlyphId, builder: &mut dyn OutlineBuilder) -> bool {
+        builder.move_to(100.0, 0.0);
+        builder.line_to(600.0, 0.0);
+        builder.line_to(600.0, 500.0);
+        builder.line_to(100.0, 500.0);
+        builder.close();
+        true
+    }
+}
+
+#[test]
+fn font_api_mock_font() {
+    let font = SquareFont;
+    let scale = Scale::uniform(10.0);
+    let id = font.glyph_id('x');
+
+    assert_eq!(font.glyph_h_metrics(id, scale).advance_width, 6.0);
+    assert_eq!(font.pair_kerning(scale, id, id), -1.0);
+    assert_eq!(font.v_metrics(scale).ascent, 8.0);
+
+    let bb = font.pixel_bounding_box(id, scale, point(0.0, 8.0)).unwrap();
+    assert_eq!((bb.min, bb.max), (point(1, 3), point(6, 8)));
+    let mut coverage = Vec::new();
+    font.draw(id, scale, point(0.0, 8.0), &mut |_, _, v| coverage.push(v));
+    assert_eq!(coverage.len(), 25);
+    assert!(
+        coverage.iter().all(|v| (v - 1.0).abs() < 1e-5),
+        "{coverage:?}"
+    );
+}
+
+#[test]
+fn synthetic_font_from_recorded_outline() {
+    let scale = Scale::uniform(24.0);
+    let real_id = DEJA_VU_MONO.glyph('o').id();
+    let mut outline = Outline::new();
+    assert!(DEJA_VU_MONO.build_outline_unscaled(real_id, &mut outline));
+    let h_metrics = DEJA_VU_MONO.glyph_h_metrics_unscaled(real_id);
+
+    let font = Font::synthetic([('o', outline, h_metrics)])
+        .unwrap()
+        .with_v_metrics(DEJA_VU_MONO.v_metrics_unscaled());
+    let id = font.glyph_id('o');
+    assert_eq!(id, GlyphId(1));
+    assert_eq!(font.glyph_id('x'), GlyphId(0));
+    assert_eq!(font.glyph_count(), 2);
+    assert_eq!(font.glyph_bounding_box(GlyphId(0), scale), None);
+
+    // draws the same as the real glyph
+    let real = DEJA_VU_MONO
+        .glyph('o')
+        .scaled(scale)
+        .positioned(point(3.5, 20.0));
+    assert_eq!(
+        font.pixel_bounding_box(id, scale, point(3.5, 20.0)),
+        real.pixel_bounding_box()
+    );
+    let mut expected = Vec::new();
+    real.draw(|_, _, v| expected.push(v));
+    let mut drawn = Vec::new();
+    font.draw(id, scale, point(3.5, 20.0), &mut |_, _, v| drawn.push(v));
+    assert_eq!(drawn, expected);
+
+    // out of range ids fall back to ".notdef"
+    assert_eq!(font.glyph_bounding_box(GlyphId(9), scale), None);
+    assert_eq!(
+        font.glyph_h_metrics_unscaled(GlyphId(9)),
+        font.glyph_h_metrics_unscaled(GlyphId(0))
+    );
+    assert!(!font.build_outline_unscaled(GlyphId(9), &mut Outline::new()));
+}
+
+#[test]
+fn synthetic_font_glyph_limit() {
+    let h_metrics = HMetrics {
+        advance_width: 0.0,
+        left_side_bearing: 0.0,
+    };
+    let glyphs = |n| (0..n).map(move |_| ('a', Outline::new(), h_metrics));
+    assert_eq!(Font::synthetic(glyphs(65535)).unwrap().glyph_count(), 65536);
+    assert_eq!(
+        Font::synthetic(glyphs(65536)),
+        Err(SyntheticFontError::TooManyGlyphs)
+    );
+}
+
+#[test]
+fn glyph_ids_batch_mapping() {
+    let font = &*ROBOTO_REGULAR;
+    let text = "Batch \u{10ffff} mapping";
+    let mut ids = vec![GlyphId(7)];
+    font.glyph_ids(text, &mut ids);
+
+    assert_eq!(ids[0], GlyphId(7));
+    let expected: Vec<_> = text.chars().map(|c| font.glyph(c).id()).collect();
+    assert_eq!(ids[1..], expected);
+    assert_eq!(ids[7], GlyphId(0));
+}
+
+#[test]
+fn glyph_ref_matches_owned_glyph() {
+    let font = &*ROBOTO_REGULAR;
+    let scale = Scale { x: 22.0, y: 18.0 };
+    let position = point(4.6, 15.2);
+
+    for c in ['g', 'W', ' '] {
+        let owned = font.glyph(c).scaled(scale);
+        let borrowed = font.glyph_ref(c).scaled(scale);
+        assert_eq!(borrowed.id(), owned.id());
+        assert_eq!(borrowed.h_metrics(), owned.h_metrics());
+        assert_eq!(borrowed.exact_bounding_box(), owned.exact_bounding_box());
+        assert_eq!(borrowed.to_scaled(), owned);
+
+        let owned = owned.positioned_with_rounding(position, PixelRounding::Round);
+        let borrowed = borrowed.positioned_with_rounding(position, PixelRounding::Round);
+        assert_eq!(borrowed.pixel_bounding_box(), owned.pixel_bounding_box());
+        assert_eq!(borrowed.to_positioned(), owned);
+
+        let mut expected = Vec::new();
+        owned.draw(|x, y, v| expected.push((x, y, v)));
+        let mut drawn = Vec::new();
+        borrowed.draw(|x, y, v| drawn.push((x, y, v)));
+        assert_eq!(drawn, expected);
+    }
+}
+
+#[test]
+fn stem_darkening_thickens_glyphs() {
+    let font = &*DEJA_VU_MONO;
+    let scale = Scale::uniform(10.0);
+    assert_eq!(stem_darkening(Scale::uniform(14.0)), 0.0);
+    assert!(stem_darkening(scale) > 0.0);
+
+    for c in ['e', 'o', 'l', 'W'] {
+        let glyph = font.glyph(c).scaled(scale).positioned(point(1.3, 8.6));
+        let mut plain = 0.0;
+        glyph.draw(|_, _, v| plain += v);
+
+        let mut undarkened = 0.0;
+        glyph.draw_darkened(0.0, |_, _, v| undarkened += v);
+        assert!((plain - undarkened).abs() < 1e-3, "{plain} != {undarkened}");
+        assert_eq!(glyph.darkened_bounding_box(0.0), glyph.pixel_bounding_box());
+
+        let darkening = stem_darkening(scale);
+        let bb = glyph.darkened_bounding_box(darkening).unwrap();
+        let mut darkened = 0.0;
+        glyph.draw_darkened(darkening, |x, y, v| {
+            assert!(x < bb.width() as u32 && y < bb.height() as u32);
+            darkened += v;
+        });
+        assert!(darkened > plain, "{c}: {darkened} <= {plain}");
+    }
+}
+
+#[test]
+fn superscript_subscript_metrics() {
+    let font = &*DEJA_VU_MONO;
+    let scale = Scale { x: 40.0, y: 20.0 };
+    let px_per_unit = font.scale_for_pixel_height(scale.y);
+
+    // OS/2 sizes 1331x1433, offsets sub 286 & super 983, 2048 units per em
+    let sup = font.superscript_metrics(scale).unwrap();
+    assert_eq!(sup.scale.x, 40.0 * 1331.0 / 2048.0);
+    assert_eq!(sup.scale.y, 20.0 * 1433.0 / 2048.0);
+    assert_eq!(sup.offset, vector(0.0, -983.0 * px_per_unit));
+
+    let sub = font.subscript_metrics(scale).unwrap();
+    assert_eq!(sub.scale, sup.scale);
+    assert_eq!(sub.offset, vector(0.0, 286.0 * px_per_unit));
+}
+
+#[test]
+fn supersampled_draw_matches_analytic() {
+    let font = &*DEJA_VU_MONO;
+    let supersampled = DrawOptions {
+        quality: DrawQuality::Supersampled,
+    };
+    for c in ['e', 'o', 'l', 'W', '@'] {
+        let glyph = font
+            .glyph(c)
+            .scaled(Scale::uniform(24.0))
+            .positioned(point(1.3, 18.6));
+        let bb = glyph.pixel_bounding_box().unwrap();
+
+        let mut analytic = vec![0.0; bb.width() as usize * bb.height() as usize];
+        glyph.draw(|x, y, v| analytic[(y * bb.width() as u32 + x) as usize] = v);
+        let mut default = vec![0.0; analytic.len()];
+        glyph.draw_with_options(DrawOptions::default(), |x, y, v| {
+            default[(y * bb.width() as u32 + x) as usize] = v
+        });
+        assert_eq!(default, analytic);
+
+        let mut sampled = vec![0.0; analytic.len()];
+        glyph.draw_with_options(supersampled, |x, y, v| {
+            assert!((0.0..=1.0).contains(&v));
+            sampled[(y * bb.width() as u32 + x) as usize] = v;
+        });
+        let max_diff = analytic
+            .iter()
+            .zip(&sampled)
+            .map(|(a, s)| (a - s).abs())
+            .fold(0.0, f32::max);
+        assert!(max_diff < 0.3, "{c}: {max_diff}");
+        let total = |v: &[f32]| v.iter().sum::<f32>();
+        let (a, s) = (total(&analytic), total(&sampled));
+        assert!((a - s).abs() < a * 0.05, "{c}: {a} != {s}");
+    }
+}
+
+#[test]
+fn cmap_subtable_override() {
+    let font = &*DEJA_VU_MONO;
+    let subtables = font.cmap_subtables();
+    let platforms: Vec<_> = subtables
+        .iter()
+        .map(|s| (s.platform_id, s.encoding_id, s.format, s.is_unicode))
+        .collect();
+    assert_eq!(
+        platforms,
+        [
+            (0, 3, 4, true),
+            (0, 10, 12, true),
+            (1, 0, 6, false),
+            (3, 1, 4, true),
+            (3, 10, 12, true),
+        ]
+    );
+    assert!(font.with_cmap_subtable(subtables.len() as u16).is_none());
+
+    // Mac Roman maps byte 0x8A to 'ä'
+    let mac_roman = font.with_cmap_subtable(2).unwrap();
+    assert_eq!(mac_roman.glyph('A').id(), font.glyph('A').id());
+    assert_eq!(mac_roman.glyph('\u{8a}').id(), font.glyph('ä').id());
+    // & byte 0xE4, 'ä' in Unicode, to '‰'
+    assert_eq!(mac_roman.glyph('ä').id(), font.glyph('‰').id());
+    assert_eq!(mac_roman.glyph_count(), font.glyph_count());
+    assert_eq!(mac_roman.cmap_subtables().len(), 1);
+
+    // an encoding record offset overflowing the new cmap is rejected
+    let data: &[u8] = include_bytes!("../fonts/dejavu/DejaVuSansMono.ttf");
+    let cmap_record = (0..u16::from_be_bytes([data[4], data[5]]) as usize)
+        .map(|i| 12 + 16 * i)
+        .find(|&at| &data[at..at + 4] == b"cmap")
+        .unwrap();
+    let cmap = u32::from_be_bytes(data[cmap_record + 8..cmap_record + 12].try_into().unwrap());
+    let mut bad_offset = data.to_vec();
+    let at = cmap as usize + 4 + 8 * 2 + 4;
+    bad_offset[at..at + 4].copy_from_slice(&(u32::MAX - 4).to_be_bytes());
+    let font = Font::try_from_bytes(&bad_offset).unwrap();
+    assert!(font.with_cmap_subtable(2).is_none());
+}
+
+#[test]
+fn strict_font_parsing_errors() {
+    let data: &[u8] = include_bytes!("../fonts/dejavu/DejaVuSansMono.ttf");
+    assert!(Font::try_from_bytes_strict(data).is_ok());
+    assert_eq!(
+        Font::try_from_bytes_strict(&[0; 64]).unwrap_err(),
+        FontError::UnknownMagic
+    );
+
+    // table records follow the 12 byte header, 16 bytes each
+    let record = |tag: &[u8]| {
+        (0..u16::from_be_bytes([data[4], data[5]]) as usize)
+            .map(|i| 12 + 16 * i)
+            .find(|&at| &data[at..at + 4] == tag)
+            .unwrap()
+    };
+    let offset = |at: usize| u32::from_be_bytes(data[at + 8..at + 12].try_into().unwrap()) as usize;
+
+    // cmap claiming too many subtables is malformed
+    let mut bad_cmap = data.to_vec();
+    let cmap = offset(record(b"cmap"));
+    bad_cmap[cmap + 2..cmap + 4].copy_from_slice(&[0xff, 0xff]);
+    assert_eq!(
+        Font::try_from_bytes_strict(&bad_cmap).unwrap_err(),
+        FontError::MalformedTable("cmap")
+    );
+    assert_eq!(
+        Font::try_from_bytes_lenient(&bad_cmap).unwrap_err(),
+        FontError::MalformedTable("cmap")
+    );
+    let font = Font::from_bytes(&bad_cmap).unwrap();
+    assert_eq!(font.glyph('a').id(), GlyphId(0));
+
+    // renaming the record removes the table
+    let mut no_cmap = data.to_vec();
+    let at = record(b"cmap");
+    no_cmap[at..at + 4].copy_from_slice(b"cmaq");
+    assert_eq!(
+        Font::try_from_bytes_strict(&no_cmap).unwrap_err(),
+        FontError::MissingTable("cmap")
+    );
+    assert_eq!(
+        Font::try_from_bytes_lenient(&no_cmap).unwrap_err(),
+        FontError::MissingTable("cmap")
+    );
+    assert!(Font::from_bytes(&no_cmap).is_ok());
+
+    // optional tables may be missing or malformed in lenient mode
+    let mut no_gpos = data.to_vec();
+    let at = record(b"GPOS");
+    no_gpos[at..at + 4].copy_from_slice(b"GPOT");
+    assert!(Font::try_from_bytes_strict(&no_gpos).is_ok());
+    let lenient = Font::try_from_bytes_lenient(&no_gpos).unwrap();
+    assert!(lenient.tables().gpos.is_none());
+
+    let mut bad_gpos = data.to_vec();
+    let gpos = offset(record(b"GPOS"));
+    bad_gpos[gpos..gpos + 2].copy_from_slice(&[0xff, 0xff]);
+    assert_eq!(
+        Font::try_from_bytes_strict(&bad_gpos).unwrap_err(),
+        FontError::MalformedTable("GPOS")
+    );
+    let lenient = Font::try_from_bytes_lenient(&bad_gpos).unwrap();
+    assert_eq!(
+        lenient.glyph('a').id(),
+        Font::from_bytes(data).unwrap().glyph('a').id()
+    );
+
+    // head is required in either mode
+    let mut no_head = data.to_vec();
+    let at = record(b"head");
+    no_head[at..at + 4].copy_from_slice(b"heaa");
+    assert_eq!(
+        Font::try_from_bytes_lenient(&no_head).unwrap_err(),
+        FontError::MissingTable("head")
+    );
+    let mut short_head = data.to_vec();
+    short_head[at + 12..at + 16].copy_from_slice(&10_u32.to_be_bytes());
+    assert_eq!(
+        Font::try_from_bytes_strict(&short_head).unwrap_err(),
+        FontError::MalformedTable("head")
+    );
+    // owned & borrowed data report the same errors
+    for corrupt in [&no_head, &short_head] {
+        assert_eq!(
+            Font::from_vec(corrupt.clone()).unwrap_err(),
+            Font::from_bytes(corrupt).unwrap_err(),
+        );
+    }
+    assert_eq!(
+        Font::from_vec(short_head).unwrap_err(),
+        FontError::MalformedTable("head")
+    );
+}
+
+#[test]
+fn font_constructor_errors() {
+    let data: &[u8] = include_bytes!("../fonts/dejavu/DejaVuSansMono.ttf");
+    assert!(Font::from_vec(data.to_vec()).is_ok());
+    assert_eq!(
+        Font::from_vec(b"not a font".to_vec()).unwrap_err(),
+        FontError::UnknownMagic
+    );
+    assert_eq!(
+        Font::from_vec_and_index(data.to_vec(), 1).unwrap_err(),
+        FontError::FaceIndexOutOfBounds
+    );
+    assert_eq!(
+        Font::from_bytes(&data[..8]).unwrap_err(),
+        FontError::MalformedFont
+    );
+    assert!(Font::try_from_vec(data[..8].to_vec()).is_none());
+    assert_eq!(
+        FontError::MissingTable("cmap").to_string(),
+        "Missing `cmap` table"
+    );
+}
+
+#[test]
+fn try_glyph_checks_glyph_ids() {
+    let font = &*DEJA_VU_MONO;
+    let count = font.glyph_count() as u16;
+    assert_eq!(
+        font.try_glyph('a').map(|g| g.id()),
+        Some(font.glyph('a').id())
+    );
+    assert_eq!(
+        font.try_glyph(GlyphId(count - 1)).map(|g| g.id()),
+        Some(GlyphId(count - 1))
+    );
+    assert!(font.try_glyph(GlyphId(count)).is_none());
+    assert!(font.try_glyph(GlyphId(u16::MAX)).is_none());
+
+    let result = std::panic::catch_unwind(|| font.glyph(GlyphId(count)));
+    assert!(result.is_err());
+}
+
+#[test]
+fn missing_h_metrics_default_to_zero() {
+    let data: &[u8] = include_bytes!("../fonts/dejavu/DejaVuSansMono.ttf");
+    // rename the hmtx table record, 16 byte records follow the 12 byte header
+    let mut no_hmtx = data.to_vec();
+    let at = (0..u16::from_be_bytes([data[4], data[5]]) as usize)
+        .map(|i| 12 + 16 * i)
+        .find(|&at| &data[at..at + 4] == b"hmtx")
+        .unwrap();
+    no_hmtx[at..at + 4].copy_from_slice(b"hmtw");
+    let font = Font::from_bytes(&no_hmtx).unwrap();
+
+    let glyph = font.glyph('a').scaled(Scale::uniform(20.0));
+    assert_eq!(glyph.try_h_metrics(), None);
+    assert_eq!(glyph.h_metrics().advance_width, 0.0);
+    assert_eq!(glyph.h_metrics().left_side_bearing, 0.0);
+
+    // layout doesn't panic, glyphs are drawn without advancing
+    let glyphs: Vec<_> = font
+        .layout("abc", Scale::uniform(20.0), point(0.0, 20.0))
+        .collect();
+    assert_eq!(glyphs.len(), 3);
+    assert!(glyphs.iter().all(|g| g.position() == point(0.0, 20.0)));
+}
+
+#[test]
+fn raw_table_access() {
+    let data: &[u8] = include_bytes!("../fonts/dejavu/DejaVuSansMono.ttf");
+    let font = &*DEJA_VU_MONO;
+
+    // matches the table directory record, 16 bytes each after the header
+    let at = (0..u16::from_be_bytes([data[4], data[5]]) as usize)
+        .map(|i| 12 + 16 * i)
+        .find(|&at| &data[at..at + 4] == b"head")
+        .unwrap();
+    let offset = u32::from_be_bytes(data[at + 8..at + 12].try_into().unwrap()) as usize;
+    let length = u32::from_be_bytes(data[at + 12..at + 16].try_into().unwrap()) as usize;
+    assert_eq!(
+        font.raw_table(Tag::from_bytes(b"head")),
+        Some(&data[offset..offset + length])
+    );
+    assert_eq!(font.raw_table(Tag::from_bytes(b"meta")), None);
+
+    let tables = font.tables();
+    assert_eq!(tables.head.units_per_em, font.units_per_em());
+    assert!(tables.glyf.is_some());
+    assert!(tables.cff.is_none());
+}
+
+#[test]
+fn glyph_names() {
+    let font = &*DEJA_VU_MONO;
+    assert_eq!(font.glyph_name(GlyphId(0)), Some(".notdef"));
+    let a = font.glyph('a').id();
+    assert_eq!(font.glyph_name(a), Some("a"));
+    assert_eq!(font.glyph_by_name("a").unwrap().id(), a);
+    assert!(font.glyph_by_name("not a glyph name").is_none());
+
+    // names from the CFF charset
+    let otf = Font::try_from_bytes(include_bytes!("../fonts/Exo2-Light.otf") as &[u8]).unwrap();
+    assert!(otf.tables().cff.is_some());
+    let b = otf.glyph('b').id();
+    assert_eq!(otf.glyph_name(b), Some("b"));
+    assert_eq!(otf.glyph_by_name("b").unwrap().id(), b);
+}
+
+#[test]
+fn outline_rasterizer_matches_draw() {
+    let glyph = DEJA_VU_MONO
+        .glyph('&')
+        .scaled(Scale::uniform(24.0))
+        .positioned(point(3.3, 20.6));
+    let bb = glyph.pixel_bounding_box().unwrap();
+    let (width, height) = (bb.width() as usize, bb.height() as usize);
+
+    let mut drawn = vec![0.0; width * height];
+    glyph.draw(|x, y, v| drawn[y as usize * width + x as usize] = v);
+
+    let mut rasterizer = OutlineRasterizer::new(width, height);
+    assert!(glyph.build_outline(&mut rasterizer));
+    assert_eq!(rasterizer.dimensions(), (width, height));
+    let mut rasterized = vec![0.0; width * height];
+    rasterizer.for_each_pixel(|x, y, v| rasterized[y as usize * width + x as usize] = v);
+    assert_eq!(drawn, rasterized);
+
+    // an unclosed contour is closed by the next move_to
+    let mut closed = OutlineRasterizer::new(4, 4);
+    let mut unclosed = OutlineRasterizer::new(4, 4);
+    for (rasterizer, close) in [(&mut closed, true), (&mut unclosed, false)] {
+        rasterizer.move_to(0.0, 0.0);
+        rasterizer.line_to(2.0, 0.0);
+        rasterizer.line_to(2.0, 2.0);
+        if close {
+            rasterizer.close();
+        }
+        rasterizer.move_to(2.0, 2.0);
+        rasterizer.line_to(4.0, 2.0);
+        rasterizer.line_to(4.0, 4.0);
+        rasterizer.close();
+    }
+    let pixels = |r: &OutlineRasterizer| {
+        let mut pixels = Vec::new();
+        r.for_each_pixel(|_, _, v| pixels.push(v));
+        pixels
+    };
+    assert_eq!(pixels(&closed), pixels(&unclosed));
+    assert!(pixels(&closed).iter().any(|&v| v > 0.0));
+
+    // overlapping contours of the same winding cover pixels at most fully
+    let mut overlapping = OutlineRasterizer::new(4, 4);
+    for _ in 0..2 {
+        overlapping.move_to(0.0, 0.0);
+        overlapping.line_to(4.0, 0.0);
+        overlapping.line_to(4.0, 4.0);
+        overlapping.line_to(0.0, 4.0);
+        overlapping.close();
+    }
+    assert!(pixels(&overlapping).iter().all(|&v| v == 1.0));
+}
+
+#[test]
+fn simplified_outline_within_tolerance() {
+    let glyph = DEJA_VU_MONO.glyph('&').scaled(Scale::uniform(60.0));
+    let fine = glyph.simplified_contours(0.01);
+    let coarse = glyph.simplified_contours(1.0);
+    let vertices = |contours: &[Vec<Point<f32>>]| contours.iter().map(Vec::len).sum::<usize>();
+    assert_eq!(fine.len(), coarse.len());
+    assert!(vertices(&coarse) * 3 < vertices(&fine));
+
+    // every point of the fine outline is near the coarse polygons
+    let segment_distance = |p: Point<f32>, a: Point<f32>, b: Point<f32>| {
+        let (ab, ap) = (b - a, p - a);
+        let t = ((ap.x * ab.x + ap.y * ab.y) / (ab.x * ab.x + ab.y * ab.y)).clamp(0.0, 1.0);
+        ((ap.x - t * ab.x).powi(2) + (ap.y - t * ab.y).powi(2)).sqrt()
+    };
+    for p in fine.iter().flatten() {
+        let distance = coarse
+            .iter()
+            .flat_map(|c| c.iter().zip(c.iter().cycle().skip(1)))
+            .map(|(&a, &b)| segment_distance(*p, a, b))
+            .fold(f32::INFINITY, f32::min);
+        assert!(
+            distance <= 1.0,
+            "{p:?} is {distance} from the simplified outline"
+        );
+    }
+
+    // simplified drawing covers about the same area
+    let positioned = glyph.clone().positioned(point(0.3, 50.0));
+    let mut exact = 0.0;
+    positioned.draw(|_, _, v| exact += v);
+    let mut simplified = 0.0;
+    positioned.draw_simplified(0.5, |_, _, v| simplified += v);
+    assert!(
+        (exact - simplified).abs() < exact * 0.05,
+        "{exact} vs {simplified}"
+    );
+
+    let mut recorded = Outline::new();
+    assert!(glyph.build_simplified_outline(1.0, &mut recorded));
+    assert!(recorded.segments().iter().all(|s| matches!(
+        s,
+        OutlineSegment::MoveTo(_) | OutlineSegment::LineTo(_) | OutlineSegment::Close
+    )));
+    assert!(!DEJA_VU_MONO
+        .glyph(' ')
+        .scaled(Scale::uniform(60.0))
+        .build_simplified_outline(1.0, &mut recorded));
+}
+
+#[test]
+fn unscaled_glyph_metrics() {
+    let font = &*ROBOTO_REGULAR;
+    let scale = Scale { x: 30.0, y: 20.0 };
+    let v_factor = font.scale_for_pixel_height(scale.y);
+    let h_factor = v_factor * scale.x / scale.y;
+    for c in ['A', 'g', 'j', ' '] {
+        let glyph = font.glyph(c);
+        let scaled = glyph.clone().scaled(scale);
+
+        let unscaled = glyph.h_metrics_unscaled();
+        let metrics = scaled.h_metrics();
+        assert_eq!(unscaled.advance_width * h_factor, metrics.advance_width);
+        assert_eq!(
+            unscaled.left_side_bearing * h_factor,
+            metrics.left_side_bearing
+        );
+
+        let bb = glyph.bounding_box_unscaled();
+        let exact = scaled.exact_bounding_box();
+        assert_eq!(bb.is_some(), exact.is_some());
+        if let (Some(bb), Some(exact)) = (bb, exact) {
+            assert_eq!(bb.min.x * h_factor, exact.min.x);
+            assert_eq!(bb.max.x * h_factor, exact.max.x);
+            assert_eq!(-bb.max.y * v_factor, exact.min.y);
+            assert_eq!(-bb.min.y * v_factor, exact.max.y);
+        }
+    }
+    let metrics = font.glyph_ref('A').h_metrics_unscaled();
+    assert_eq!(metrics, font.glyph('A').h_metrics_unscaled());
+}
+
+#[test]
+fn cached_outlines_match_uncached() {
+    let font = &*ROBOTO_REGULAR;
+    // small capacity so outlines are evicted & reparsed
+    for capacity in [0, 3, 256] {
+        let mut caches = FontCaches::with_outline_capacity(capacity);
+        for size in [9.0, 17.5, 40.0] {
+            for glyph in font.layout(
+                "Hello, cached world!",
+                Scale::uniform(size),
+                point(0.3, size),
+            ) {
+                let mut cached = Outline::new();
+                let mut outline = Outline::new();
+                assert_eq!(
+                    glyph.build_outline_cached(&mut caches, &mut cached),
+                    glyph.build_outline(&mut outline)
+                );
+                assert_eq!(cached, outline);
+
+                let mut pixels = Vec::new();
+                glyph.draw(|x, y, v| pixels.push((x, y, v)));
+                let mut cached_pixels = Vec::new();
+                glyph.draw_cached(&mut caches, |x, y, v| cached_pixels.push((x, y, v)));
+                assert_eq!(cached_pixels, pixels);
+            }
+        }
+    }
+
+    // caches are cleared for another font
+    let mut caches = FontCaches::new();
+    let glyph = |font: &Font<'static>| font.glyph('a').scaled(Scale::uniform(20.0));
+    let mut outline = Outline::new();
+    glyph(font).build_outline_cached(&mut caches, &mut outline);
+    let mut other = Outline::new();
+    let mut expected = Outline::new();
+    glyph(&DEJA_VU_MONO).build_outline_cached(&mut caches, &mut other);
+    glyph(&DEJA_VU_MONO).build_outline(&mut expected);
+    assert_eq!(other, expected);
+    assert_ne!(other, outline);
+}
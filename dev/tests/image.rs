@@ -0,0 +1,78 @@
+use image::{Rgba, RgbaImage};
+use once_cell::sync::Lazy;
+use rusttype::*;
+
+static OPEN_SANS_ITALIC: Lazy<Font<'static>> = Lazy::new(|| {
+    Font::try_from_bytes(include_bytes!("../fonts/opensans/OpenSans-Italic.ttf") as &[u8]).unwrap()
+});
+static DEJA_VU_MONO: Lazy<Font<'static>> = Lazy::new(|| {
+    Font::try_from_bytes(include_bytes!("../fonts/dejavu/DejaVuSansMono.ttf") as &[u8]).unwrap()
+});
+
+#[test]
+fn render_text_fits_image() {
+    let scale = Scale::uniform(30.0);
+    let image = render_text_to_image(&DEJA_VU_MONO, "Tofu", scale, Rgba([10, 20, 30, 255]));
+    let gray = render_text_to_gray_image(&DEJA_VU_MONO, "Tofu", scale);
+    assert_eq!(image.dimensions(), gray.dimensions());
+
+    for (rgba, luma) in image.pixels().zip(gray.pixels()) {
+        assert_eq!(rgba[3], luma[0]);
+        if rgba[3] > 0 {
+            assert_eq!(&rgba.0[..3], &[10, 20, 30]);
+        }
+    }
+    assert!(gray.pixels().any(|p| p[0] == 255));
+}
+
+#[test]
+fn render_text_negative_side_bearing() {
+    let font = &*OPEN_SANS_ITALIC;
+    let scale = Scale::uniform(40.0);
+    assert!(font.glyph('j').scaled(scale).h_metrics().left_side_bearing < 0.0);
+
+    let gray = render_text_to_gray_image(font, "jag", scale);
+    let (width, height) = gray.dimensions();
+    let column_inked = |x| (0..height).any(|y| gray.get_pixel(x, y)[0] > 0);
+    assert!(column_inked(0), "left overhang clipped");
+    assert!(column_inked(width - 1));
+
+    // equals drawing at an origin shifted right by the overhang
+    let ink: u32 = gray.pixels().map(|p| u32::from(p[0])).sum();
+    let mut wide = RgbaImage::new(width + 20, height);
+    let ascent = font.v_metrics(scale).ascent;
+    draw_text_onto_image(
+        &mut wide,
+        font,
+        "jag",
+        scale,
+        point(20.0, ascent),
+        Rgba([0, 0, 0, 255]),
+    );
+    let wide_ink: u32 = wide.pixels().map(|p| u32::from(p[3])).sum();
+    assert_eq!(ink, wide_ink);
+}
+
+#[test]
+fn draw_text_clips_at_borders() {
+    let mut image = RgbaImage::from_pixel(20, 20, Rgba([255, 255, 255, 255]));
+    // glyphs hanging off every edge & entirely outside the image
+    for &position in &[
+        point(-10.0, 10.0),
+        point(10.0, 5.0),
+        point(15.0, 30.0),
+        point(-500.0, -500.0),
+        point(500.0, 500.0),
+    ] {
+        draw_text_onto_image(
+            &mut image,
+            &DEJA_VU_MONO,
+            "W@",
+            Scale::uniform(24.0),
+            position,
+            Rgba([0, 0, 0, 255]),
+        );
+    }
+    assert!(image.pixels().all(|p| p[3] == 255));
+    assert!(image.pixels().any(|p| p[0] == 0));
+}
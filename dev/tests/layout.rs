@@ -0,0 +1,841 @@
+use once_cell::sync::Lazy;
+use rusttype::*;
+use std::hash::Hasher;
+
+static OPEN_SANS_ITALIC: Lazy<Font<'static>> = Lazy::new(|| {
+    Font::try_from_bytes(include_bytes!("../fonts/opensans/OpenSans-Italic.ttf") as &[u8]).unwrap()
+});
+static DEJAVU_MONO: Lazy<Font<'static>> = Lazy::new(|| {
+    Font::try_from_bytes(include_bytes!("../fonts/dejavu/DejaVuSansMono.ttf") as &[u8]).unwrap()
+});
+
+#[test]
+fn measure_advance_and_ink_width() {
+    let font = &*OPEN_SANS_ITALIC;
+    let scale = Scale::uniform(40.0);
+
+    let glyphs: Vec<_> = font.layout("Waf", scale, point(0.0, 0.0)).collect();
+    let last = glyphs.last().unwrap();
+    let advance = last.position().x + last.unpositioned().h_metrics().advance_width;
+    assert_relative_eq(
+        font.measure_width("Waf", scale, WidthMetric::Advance),
+        advance,
+    );
+
+    // italic 'f' overhangs its advance
+    let ink = font.measure_width("Waf", scale, WidthMetric::Ink);
+    let first_ink = glyphs[0].unpositioned().exact_bounding_box().unwrap().min.x;
+    let last_ink = last.position().x + last.unpositioned().exact_bounding_box().unwrap().max.x;
+    assert_relative_eq(ink, last_ink - first_ink);
+    assert!(last_ink > advance);
+
+    assert_eq!(font.measure_width("", scale, WidthMetric::Advance), 0.0);
+    assert_eq!(font.measure_width("  ", scale, WidthMetric::Ink), 0.0);
+}
+
+fn assert_relative_eq(a: f32, b: f32) {
+    assert!((a - b).abs() <= 1e-4 * a.abs().max(b.abs()), "{a} != {b}");
+}
+
+#[test]
+fn layout_digest_golden() {
+    let glyphs: Vec<_> = OPEN_SANS_ITALIC
+        .layout("Golden layout", Scale::uniform(25.0), point(100.0, 25.0))
+        .collect();
+    assert_eq!(layout_digest(&glyphs), 5772386621908131420);
+
+    // platform dependent integers hash as their 64-bit little-endian values
+    let digest = |write: &dyn Fn(&mut GlyphHasher)| {
+        let mut hasher = GlyphHasher::new();
+        write(&mut hasher);
+        hasher.finish()
+    };
+    assert_eq!(
+        digest(&|h| h.write_usize(0x0102_0304)),
+        digest(&|h| h.write(&[4, 3, 2, 1, 0, 0, 0, 0]))
+    );
+    assert_eq!(
+        digest(&|h| h.write_isize(-1)),
+        digest(&|h| h.write(&[0xff; 8]))
+    );
+    assert_eq!(
+        digest(&|h| h.write_u32(0x0102_0304)),
+        digest(&|h| h.write(&[4, 3, 2, 1]))
+    );
+}
+
+#[test]
+fn run_cache_lru() {
+    let font = &*OPEN_SANS_ITALIC;
+    let scale = Scale::uniform(18.0);
+    let mut runs = RunCache::new(2);
+
+    let layout = |runs: &mut RunCache<'static>, text| {
+        let start = point(3.25, 40.5);
+        let glyphs: Vec<_> = runs.layout(font, text, scale, start).collect();
+        assert_eq!(glyphs, font.layout(text, scale, start).collect::<Vec<_>>());
+    };
+    layout(&mut runs, "first");
+    layout(&mut runs, "second");
+    layout(&mut runs, "first");
+    // evicts "second", the least recently used
+    layout(&mut runs, "third");
+    assert_eq!(runs.len(), 2);
+
+    // different scales are different runs
+    let _ = runs.layout(font, "first", Scale::uniform(19.0), point(0.0, 0.0));
+    assert_eq!(runs.len(), 2);
+    runs.clear();
+    assert!(runs.is_empty());
+}
+
+#[test]
+fn grapheme_combining_mark_over_base() {
+    let font = &*OPEN_SANS_ITALIC;
+    let scale = Scale::uniform(40.0);
+    let glyphs: Vec<_> = font
+        .layout("e\u{301}e", scale, point(0.0, 40.0))
+        .graphemes()
+        .collect();
+    assert_eq!(glyphs.len(), 3);
+
+    let e = glyphs[0].pixel_bounding_box().unwrap();
+    let mark = glyphs[1].pixel_bounding_box().unwrap();
+    assert!(
+        mark.min.x < e.max.x && e.min.x < mark.max.x,
+        "{mark:?} not over {e:?}"
+    );
+
+    // the mark takes no space
+    let plain: Vec<_> = font.layout("ee", scale, point(0.0, 40.0)).collect();
+    assert_eq!(glyphs[2].position(), plain[1].position());
+}
+
+#[test]
+fn on_glyph_caret_spans() {
+    let font = &*OPEN_SANS_ITALIC;
+    let scale = Scale::uniform(30.0);
+    let text = "AVa b";
+    let start = point(5.0, 30.0);
+
+    let mut infos = Vec::new();
+    let glyphs: Vec<_> = font
+        .layout(text, scale, start)
+        .on_glyph(|info| {
+            infos.push((
+                info.cluster,
+                info.glyph.clone(),
+                info.caret_start,
+                info.caret_end,
+            ))
+        })
+        .collect();
+    assert_eq!(glyphs, font.layout(text, scale, start).collect::<Vec<_>>());
+    assert_eq!(infos.len(), glyphs.len());
+
+    for (index, (cluster, glyph, caret_start, caret_end)) in infos.iter().enumerate() {
+        assert_eq!(cluster.glyph_index, index);
+        assert_eq!(glyph, &glyphs[index]);
+        // caret starts after kerning, at the glyph origin
+        assert_relative_eq(*caret_start, glyph.position().x);
+        let advance = glyph.unpositioned().h_metrics().advance_width;
+        assert_relative_eq(*caret_end, caret_start + advance);
+    }
+    // spans are contiguous, except for kerning
+    assert_eq!(infos[3].3, infos[4].2);
+    assert!(infos[1].2 < infos[0].3, "AV not kerned");
+}
+
+#[test]
+fn gpos_mark_attachment() {
+    let font = &*DEJAVU_MONO;
+    let scale = Scale::uniform(40.0);
+    let offset = font.mark_attachment(scale, 'A', '\u{301}').unwrap();
+    // capital accents are raised
+    assert!(offset.y < 0.0, "{offset:?}");
+    assert_eq!(font.mark_attachment(scale, 'A', 'B'), None);
+
+    let glyphs: Vec<_> = font
+        .layout("A\u{301}", scale, point(0.0, 40.0))
+        .graphemes()
+        .collect();
+    assert_eq!(glyphs[1].position(), glyphs[0].position() + offset);
+    let a = glyphs[0].pixel_bounding_box().unwrap();
+    let acute = glyphs[1].pixel_bounding_box().unwrap();
+    assert!(acute.max.y <= a.min.y, "{acute:?} not above {a:?}");
+}
+
+#[test]
+fn rustybuzz_shape_arabic() {
+    let font = &*DEJAVU_MONO;
+    let scale = Scale::uniform(30.0);
+    let start = point(10.0, 30.0);
+    let id = |c| font.glyph(c).id();
+
+    // beh beh, shaped right-to-left into final & initial forms
+    let glyphs = font.shape("\u{628}\u{628}", &[], None, None, scale, start);
+    let ids: Vec<_> = glyphs.iter().map(|g| g.id()).collect();
+    assert_eq!(ids, [id('\u{fe90}'), id('\u{fe91}')]);
+    assert_eq!(glyphs[0].position(), start);
+    let advance = glyphs[0].unpositioned().h_metrics().advance_width;
+    assert!((glyphs[1].position().x - (start.x + advance)).abs() < 1e-3);
+
+    // lam-alef ligature
+    let glyphs = font.shape("\u{644}\u{627}", &[], None, None, scale, start);
+    assert_eq!(glyphs.len(), 1);
+    assert_eq!(glyphs[0].id(), id('\u{fefb}'));
+}
+
+#[test]
+fn bidi_mixed_direction_layout() {
+    let font = &*DEJAVU_MONO;
+    let text = "ab \u{628}\u{644}\u{627} cd";
+
+    let runs = bidi::runs(text);
+    let run_texts: Vec<_> = runs.iter().map(|r| &text[r.text_range.clone()]).collect();
+    assert_eq!(run_texts, ["ab ", "\u{628}\u{644}\u{627}", " cd"]);
+    assert_eq!(runs[1].direction, bidi::Direction::RightToLeft);
+    assert_eq!(runs[1].script, bidi::Script::Arabic);
+
+    let scale = Scale::uniform(20.0);
+    let glyphs = font.layout_bidi(text, scale, point(0.0, 20.0));
+    let ids: Vec<_> = glyphs.iter().map(|g| g.id()).collect();
+    // final lam-alef is drawn left of the initial beh
+    let expected: Vec<_> = "ab \u{fefc}\u{fe91} cd"
+        .chars()
+        .map(|c| font.glyph(c).id())
+        .collect();
+    assert_eq!(ids, expected);
+
+    // all LTR text lays out like `Font::layout`
+    let latin = font.layout_bidi("Hello", scale, point(0.0, 20.0));
+    assert!(latin
+        .into_iter()
+        .eq(font.layout("Hello", scale, point(0.0, 20.0))));
+}
+
+#[test]
+fn bidi_rtl_kerning_in_logical_order() {
+    let font = &*OPEN_SANS_ITALIC;
+    let scale = Scale::uniform(40.0);
+    let (t, o) = (font.glyph('T').id(), font.glyph('o').id());
+    let kerning = font.pair_kerning(scale, t, o);
+    assert_ne!(kerning, font.pair_kerning(scale, o, t));
+
+    // forced right-to-left, "To" is drawn as "oT" still kerned as "To"
+    let glyphs = font.layout_bidi("\u{202e}To\u{202c}", scale, point(0.0, 0.0));
+    let drawn_o = glyphs.iter().position(|g| g.id() == o).unwrap();
+    let (drawn_o, drawn_t) = (&glyphs[drawn_o], &glyphs[drawn_o + 1]);
+    assert_eq!(drawn_t.id(), t);
+    assert_relative_eq(
+        drawn_t.position().x - drawn_o.position().x,
+        drawn_o.unpositioned().h_metrics().advance_width + kerning,
+    );
+}
+
+#[test]
+fn layout_options_control_chars() {
+    let font = &*DEJAVU_MONO;
+    let scale = Scale::uniform(20.0);
+    let start = point(5.0, 20.0);
+    let space = font.glyph(' ').scaled(scale).h_metrics().advance_width;
+    let options = LayoutOptions::new()
+        .with_tab_width(Some(TabWidth::Spaces(4.0)))
+        .with_newline(NewlineMode::Break)
+        .with_skip_control(true);
+    let layout = |text| -> Vec<_> {
+        font.layout(text, scale, start)
+            .with_options(options)
+            .collect()
+    };
+
+    // tab stops are relative to the start
+    let glyphs = layout("ab\tc\t\td");
+    assert_eq!(glyphs.len(), 4);
+    assert_relative_eq(glyphs[2].position().x, start.x + 4.0 * space);
+    assert_relative_eq(glyphs[3].position().x, start.x + 12.0 * space);
+
+    // default ignorable format chars are skipped too
+    assert_eq!(layout("a\u{200d}b"), layout("ab"));
+
+    let glyphs = layout("ab\r\ncd");
+    let v_metrics = font.v_metrics(scale);
+    let line_height = v_metrics.ascent - v_metrics.descent + v_metrics.line_gap;
+    let ids: Vec<_> = glyphs.iter().map(|g| g.id()).collect();
+    assert_eq!(ids, ['a', 'b', 'c', 'd'].map(|c| font.glyph(c).id()));
+    assert_eq!(glyphs[2].position(), point(start.x, start.y + line_height));
+    assert_eq!(
+        glyphs[1].position(),
+        font.layout("ab", scale, start).nth(1).unwrap().position()
+    );
+
+    // defaults match plain layout
+    let plain: Vec<_> = font.layout("a\tb\n", scale, start).collect();
+    let defaults: Vec<_> = font
+        .layout("a\tb\n", scale, start)
+        .with_options(LayoutOptions::default())
+        .collect();
+    assert_eq!(plain.len(), 4);
+    assert_eq!(plain, defaults);
+
+    let ignored: Vec<_> = font
+        .layout("a\nb", scale, start)
+        .with_options(LayoutOptions::new().with_newline(NewlineMode::Ignore))
+        .collect();
+    assert_eq!(ignored, font.layout("ab", scale, start).collect::<Vec<_>>());
+
+    // rejected newlines end layout
+    let mut rejected = font
+        .layout("ab\ncd", scale, start)
+        .with_options(LayoutOptions::new().with_newline(NewlineMode::Error));
+    let glyphs: Vec<_> = rejected.by_ref().collect();
+    assert_eq!(glyphs, font.layout("ab", scale, start).collect::<Vec<_>>());
+    assert!(rejected.stopped_at_newline());
+    assert_eq!(rejected.next(), None);
+    let mut single = font
+        .layout("ab", scale, start)
+        .with_options(LayoutOptions::new().with_newline(NewlineMode::Error));
+    assert_eq!(single.by_ref().count(), 2);
+    assert!(!single.stopped_at_newline());
+}
+
+#[test]
+fn layout_letter_and_word_spacing() {
+    let font = &*OPEN_SANS_ITALIC;
+    let scale = Scale::uniform(30.0);
+    let start = point(0.0, 30.0);
+    let text = "AV a";
+    let plain: Vec<_> = font.layout(text, scale, start).collect();
+    let spaced: Vec<_> = font
+        .layout(text, scale, start)
+        .with_options(
+            LayoutOptions::new()
+                .with_letter_spacing(2.0)
+                .with_word_spacing(5.0),
+        )
+        .collect();
+
+    // kerning is still applied
+    let plain_x: Vec<_> = plain.iter().map(|g| g.position().x).collect();
+    let spaced_x: Vec<_> = spaced.iter().map(|g| g.position().x).collect();
+    let extra: Vec<_> = plain_x.iter().zip(&spaced_x).map(|(p, s)| s - p).collect();
+    for (extra, expected) in extra.into_iter().zip([0.0, 2.0, 4.0, 11.0]) {
+        assert_relative_eq(extra, expected);
+    }
+
+    // no-break spaces are word separators
+    let nbsp: Vec<_> = font
+        .layout("a\u{a0}a", scale, start)
+        .with_options(LayoutOptions::new().with_word_spacing(5.0))
+        .collect();
+    let plain: Vec<_> = font.layout("a\u{a0}a", scale, start).collect();
+    assert_relative_eq(nbsp[2].position().x - plain[2].position().x, 5.0);
+}
+
+#[test]
+fn layout_truncated_fits_max_width() {
+    let font = &*OPEN_SANS_ITALIC;
+    let scale = Scale::uniform(30.0);
+    let start = point(5.0, 30.0);
+    let width = |glyphs: &[PositionedGlyph<'_>]| {
+        glyphs.last().map_or(0.0, |g| {
+            g.position().x - start.x + g.unpositioned().h_metrics().advance_width
+        })
+    };
+
+    let full = font.measure_width("WAVE", scale, WidthMetric::Advance);
+    let (glyphs, chars) = font.layout_truncated("WAVE", scale, start, full, "...");
+    assert_eq!(chars, 4);
+    assert!(glyphs.into_iter().eq(font.layout("WAVE", scale, start)));
+
+    for max_width in [0.4 * full, 0.7 * full, 0.95 * full] {
+        let (glyphs, chars) = font.layout_truncated("WAVE", scale, start, max_width, "...");
+        assert!(chars < 4);
+        assert_eq!(glyphs.len(), chars + 3);
+        assert!(width(&glyphs) <= max_width);
+        // one more char would not fit
+        let (longer, _) = font.layout_truncated("WAVE", scale, start, 1000.0, "");
+        let next_end = width(&longer[..=chars])
+            + font.pair_kerning(scale, longer[chars].id(), '.')
+            + font.measure_width("...", scale, WidthMetric::Advance);
+        assert!(next_end > max_width);
+    }
+
+    let (glyphs, chars) = font.layout_truncated("WAVE", scale, start, 1.0, "...");
+    assert!(glyphs.is_empty());
+    assert_eq!(chars, 0);
+}
+
+#[test]
+fn nine_slice_around_text() {
+    let font = &*OPEN_SANS_ITALIC;
+    let scale = Scale::uniform(20.0);
+    let start = point(12.0, 40.0);
+    let padding = Padding {
+        left: 6.0,
+        top: 2.0,
+        right: 8.0,
+        bottom: 3.0,
+    };
+    let v_metrics = font.v_metrics(scale);
+    let width = font.measure_width("Badge", scale, WidthMetric::Advance);
+
+    let badge = font.nine_slice("Badge", scale, start, padding, 4.0);
+    assert_eq!(badge.baseline, 40.0);
+    assert_relative_eq(badge.rect.min.x, 6.0);
+    assert_relative_eq(badge.rect.max.x, 20.0 + width);
+    assert_relative_eq(badge.rect.min.y, 38.0 - v_metrics.ascent);
+    assert_relative_eq(badge.rect.max.y, 43.0 - v_metrics.descent);
+    assert_eq!(badge.insets, Padding::uniform(4.0));
+
+    let slices = badge.slices();
+    assert_eq!(slices[0].min, badge.rect.min);
+    assert_eq!(slices[8].max, badge.rect.max);
+    assert_eq!((slices[0].width(), slices[0].height()), (4.0, 4.0));
+    assert_relative_eq(slices[4].width(), badge.rect.width() - 8.0);
+    let area: f32 = slices.iter().map(|s| s.width() * s.height()).sum();
+    assert_relative_eq(area, badge.rect.width() * badge.rect.height());
+
+    // other text on the same baseline has the same vertical extent
+    let other = font.nine_slice("xx", scale, start, padding, 100.0);
+    assert_eq!(
+        (other.rect.min.y, other.rect.max.y),
+        (badge.rect.min.y, badge.rect.max.y)
+    );
+    assert_eq!(other.insets.top, other.rect.height() / 2.0);
+    assert_eq!(other.slices()[4].height(), 0.0);
+}
+
+#[test]
+fn layout_advances_with_spacing() {
+    let font = &*OPEN_SANS_ITALIC;
+    let scale = Scale::uniform(30.0);
+    let start = point(5.0, 30.0);
+    let options = LayoutOptions::new()
+        .with_letter_spacing(1.5)
+        .with_word_spacing(4.0);
+    let layout = || font.layout("AVa b", scale, start).with_options(options);
+
+    let advances: Vec<_> = layout().advances().collect();
+    let glyphs: Vec<_> = layout().collect();
+    assert!(advances.iter().map(|(g, _)| g).eq(&glyphs));
+
+    let mut caret = start.x;
+    let mut last = None;
+    for (glyph, advance) in &advances {
+        let kerning = last.map_or(0.0, |last| font.pair_kerning(scale, last, glyph.id()));
+        assert_eq!(advance.kerning, kerning);
+        caret += advance.kerning;
+        assert_relative_eq(glyph.position().x, caret);
+        caret += advance.advance;
+        last = Some(glyph.id());
+    }
+    assert!(advances[1].1.kerning < 0.0, "AV not kerned");
+    let h_advance = |i: usize| glyphs[i].unpositioned().h_metrics().advance_width;
+    assert_relative_eq(advances[2].1.advance, h_advance(2) + 1.5);
+    assert_relative_eq(advances[3].1.advance, h_advance(3) + 1.5 + 4.0);
+
+    // on_glyph caret spans account for spacing
+    let mut spans = Vec::new();
+    layout()
+        .on_glyph(|info| spans.push((info.caret_start, info.caret_end, info.advance)))
+        .for_each(drop);
+    for ((caret_start, caret_end, advance), (glyph, expected)) in spans.iter().zip(&advances) {
+        assert_relative_eq(*caret_start, glyph.position().x);
+        assert_relative_eq(*caret_end, caret_start + expected.advance);
+        assert_eq!(advance, expected);
+    }
+
+    // zero advance marks get no spacing & an empty caret span
+    let accent = font.glyph('\u{301}');
+    assert_eq!(accent.scaled(scale).h_metrics().advance_width, 0.0);
+    let mut spans = Vec::new();
+    let glyphs: Vec<_> = font
+        .layout("e\u{301}e", scale, start)
+        .with_options(options)
+        .on_glyph(|info| spans.push((info.caret_start, info.caret_end, info.advance)))
+        .collect();
+    assert_eq!(spans[1].2.advance, 0.0);
+    assert_relative_eq(spans[1].0, spans[1].1);
+    assert_relative_eq(spans[1].0, spans[0].1);
+    assert_relative_eq(glyphs[2].position().x, spans[2].0);
+}
+
+#[test]
+fn cluster_char_index_stable_across_relayout() {
+    let font = &*DEJAVU_MONO;
+    let clusters = |text| -> Vec<_> {
+        font.layout(text, Scale::uniform(16.0), point(0.0, 0.0))
+            .clusters()
+            .map(|(cluster, _)| cluster)
+            .collect()
+    };
+    let short = clusters("añb");
+    let long = clusters("añb €c");
+
+    assert_eq!(long[..short.len()], short);
+    let char_indices: Vec<_> = long.iter().map(|c| c.char_index).collect();
+    assert_eq!(char_indices, [0, 1, 2, 3, 4, 5]);
+    let text_starts: Vec<_> = long.iter().map(|c| c.text_start).collect();
+    assert_eq!(text_starts, [0, 1, 3, 4, 5, 8]);
+    assert_eq!(long[4], GlyphCluster::new(4, 4, 5..8));
+}
+
+#[test]
+fn clusters_exclude_skipped_option_chars() {
+    let font = &*DEJAVU_MONO;
+    let options = LayoutOptions::new()
+        .with_tab_width(Some(TabWidth::Spaces(4.0)))
+        .with_newline(NewlineMode::Break);
+    let text = "a\tb\nc d";
+    let layout = || {
+        font.layout(text, Scale::uniform(16.0), point(0.0, 16.0))
+            .with_options(options)
+    };
+
+    let clusters: Vec<_> = layout().clusters().map(|(cluster, _)| cluster).collect();
+    assert_eq!(
+        clusters,
+        [
+            GlyphCluster::new(0, 0, 0..1),
+            GlyphCluster::new(1, 2, 2..3),
+            GlyphCluster::new(2, 4, 4..5),
+            GlyphCluster::new(3, 5, 5..6),
+            GlyphCluster::new(4, 6, 6..7),
+        ]
+    );
+
+    let mut spaces = Vec::new();
+    layout()
+        .on_glyph(|info| {
+            if &text[info.cluster.text_range()] == " " {
+                spaces.push(info.cluster.glyph_index);
+            }
+        })
+        .for_each(drop);
+    assert_eq!(spaces, [3]);
+}
+
+#[test]
+fn layout_utf16_invalid_surrogates() {
+    let font = &*DEJAVU_MONO;
+    let (scale, start) = (Scale::uniform(16.0), point(0.0, 16.0));
+    let ids =
+        |glyphs: Vec<PositionedGlyph<'_>>| -> Vec<_> { glyphs.iter().map(|g| g.id()).collect() };
+    let expected = |text: &str| ids(font.layout(text, scale, start).collect());
+
+    // valid text matches `layout`
+    let valid: Vec<u16> = "añ\u{1F600}b".encode_utf16().collect();
+    let glyphs = font.layout_utf16(&valid, scale, start, InvalidCharPolicy::Replace);
+    assert_eq!(ids(glyphs), expected("añ\u{1F600}b"));
+
+    // lone low surrogate, then a high surrogate without its pair
+    let invalid = [0x61, 0xDC00, 0x62, 0xD83D, 0x63];
+    let layout = |policy| ids(font.layout_utf16(&invalid, scale, start, policy));
+    assert_eq!(
+        layout(InvalidCharPolicy::Replace),
+        expected("a\u{FFFD}b\u{FFFD}c")
+    );
+    assert_eq!(
+        layout(InvalidCharPolicy::ReplaceWith('?')),
+        expected("a?b?c")
+    );
+    assert_eq!(layout(InvalidCharPolicy::Skip), expected("abc"));
+
+    assert_eq!(
+        InvalidCharPolicy::Replace.decode_utf32(&[0x61, 0xD800, 0x11_0000, 0x1F600]),
+        "a\u{FFFD}\u{FFFD}\u{1F600}"
+    );
+}
+
+#[test]
+fn italic_caret_slope() {
+    let italic = &*OPEN_SANS_ITALIC;
+    assert_eq!(italic.italic_angle(), Some(-12.0));
+    let slope = italic.caret_slope();
+    assert_eq!((slope.rise, slope.run, slope.offset), (2048, 435, 0));
+    // the caret slope matches the italic angle
+    assert!((slope.slant().atan().to_degrees() - 12.0).abs() < 0.1);
+
+    let scale = Scale::uniform(30.0);
+    let caret = point(10.0, 40.0);
+    let v_metrics = italic.v_metrics(scale);
+    let (bottom, top) = italic.caret_line(scale, caret);
+    assert_relative_eq(bottom.y, caret.y - v_metrics.descent);
+    assert_relative_eq(top.y, caret.y - v_metrics.ascent);
+    assert_relative_eq(top.x - caret.x, slope.slant() * v_metrics.ascent);
+    assert_relative_eq(bottom.x - caret.x, slope.slant() * v_metrics.descent);
+
+    let upright = &*DEJAVU_MONO;
+    assert_eq!(upright.italic_angle(), Some(0.0));
+    assert_eq!(upright.caret_slope().slant(), 0.0);
+    let (bottom, top) = upright.caret_line(scale, caret);
+    assert_eq!((bottom.x, top.x), (caret.x, caret.x));
+}
+
+#[test]
+fn em_height_scale_conversions() {
+    let font = &*OPEN_SANS_ITALIC;
+    assert_eq!(Scale::from_pt(12.0, 96.0), Scale::uniform(16.0));
+    assert_eq!(Scale::from_pt(10.0, 72.0), Scale::uniform(10.0));
+
+    let scale = font.scale_for_em_height(16.0);
+    assert_relative_eq(font.px_per_em(scale), 16.0);
+    // the ascent to descent height is larger than the em
+    assert!(scale.y > 16.0);
+
+    // an em sized glyph advance is `px` at the em scale
+    let units_per_em = f32::from(font.units_per_em());
+    let unscaled_advance =
+        font.h_metrics_unscaled().advance_widths[font.glyph('M').id().0 as usize];
+    let advance = font.glyph('M').scaled(scale).h_metrics().advance_width;
+    assert_relative_eq(advance, 16.0 * f32::from(unscaled_advance) / units_per_em);
+
+    // ascent to descent scales are unchanged
+    let v_metrics = font.v_metrics(Scale::uniform(20.0));
+    assert_relative_eq(v_metrics.ascent - v_metrics.descent, 20.0);
+}
+
+#[test]
+fn size_semantics() {
+    let font = &*OPEN_SANS_ITALIC;
+    let size = Scale { x: 20.0, y: 16.0 };
+    let start = point(0.0, 20.0);
+    assert_eq!(font.resolve_scale(size, SizeSemantics::AscentDescent), size);
+    assert_eq!(SizeSemantics::default(), SizeSemantics::AscentDescent);
+
+    let em = font.resolve_scale(size, SizeSemantics::EmSquare);
+    assert_relative_eq(font.px_per_em(em), 16.0);
+    assert_relative_eq(em.x / em.y, size.x / size.y);
+
+    let positions =
+        |glyphs: LayoutIter<'_, '_, '_>| -> Vec<_> { glyphs.map(|g| g.position()).collect() };
+    assert_eq!(
+        positions(font.layout_with_semantics("Ava", size, SizeSemantics::EmSquare, start)),
+        positions(font.layout("Ava", em, start))
+    );
+    assert_eq!(
+        font.v_metrics_with_semantics(size, SizeSemantics::EmSquare),
+        font.v_metrics(em)
+    );
+    let glyph = font
+        .glyph('A')
+        .scaled_with_semantics(size, SizeSemantics::EmSquare);
+    assert_eq!(glyph.scale(), em);
+    let glyph = font
+        .glyph('A')
+        .scaled_with_semantics(size, SizeSemantics::AscentDescent);
+    assert_eq!(glyph.scale(), size);
+}
+
+#[test]
+fn canvas_text_metrics() {
+    let font = &*OPEN_SANS_ITALIC;
+    let scale = font.scale_for_em_height(20.0);
+    let metrics = font.measure_text("fly", scale);
+
+    assert_relative_eq(
+        metrics.width,
+        font.measure_width("fly", scale, WidthMetric::Advance),
+    );
+    assert_relative_eq(
+        metrics.actual_bounding_box_left + metrics.actual_bounding_box_right,
+        font.measure_width("fly", scale, WidthMetric::Ink),
+    );
+    // italic "f" descends & extends left of the start
+    assert!(metrics.actual_bounding_box_left > 0.0);
+    assert!(metrics.actual_bounding_box_descent > 0.0);
+    let f_top = font
+        .glyph('f')
+        .scaled(scale)
+        .exact_bounding_box()
+        .unwrap()
+        .min
+        .y;
+    assert_relative_eq(metrics.actual_bounding_box_ascent, -f_top);
+
+    let v_metrics = font.v_metrics(scale);
+    assert_relative_eq(metrics.font_bounding_box_ascent, v_metrics.ascent);
+    assert_relative_eq(metrics.font_bounding_box_descent, -v_metrics.descent);
+    assert_relative_eq(metrics.em_height_ascent + metrics.em_height_descent, 20.0);
+    assert_relative_eq(
+        metrics.em_height_ascent / metrics.em_height_descent,
+        v_metrics.ascent / -v_metrics.descent,
+    );
+
+    let blank = font.measure_text(" ", scale);
+    assert!(blank.width > 0.0);
+    assert_eq!(blank.actual_bounding_box_right, 0.0);
+    assert_eq!(blank.actual_bounding_box_ascent, 0.0);
+}
+
+#[test]
+fn kern_table_matches_pair_kerning() {
+    let font = &*OPEN_SANS_ITALIC;
+    let kern_table = font.kern_table();
+    let scale = Scale::uniform(24.0);
+    let ids: Vec<_> = (0..font.glyph_count() as u16)
+        .step_by(3)
+        .map(GlyphId)
+        .collect();
+    let mut kerned = 0;
+    for &first in &ids {
+        for &second in &ids {
+            let expected = font.pair_kerning(scale, first, second);
+            assert_eq!(kern_table.pair_kerning(scale, first, second), expected);
+            kerned += (expected != 0.0) as usize;
+        }
+    }
+    assert!(kerned > 0);
+    assert_ne!(kern_table.pair_kerning(scale, 'A', 'V'), 0.0);
+    assert_eq!(
+        kern_table.pair_kerning_unscaled(GlyphId(u16::MAX), GlyphId(0)),
+        0
+    );
+
+    let text = "AVAVA Tea, Yo. LT fi";
+    let start = point(0.0, 24.0);
+    let plain: Vec<_> = font.layout(text, scale, start).collect();
+    let with_table: Vec<_> = font
+        .layout(text, scale, start)
+        .with_kern_table(&kern_table)
+        .collect();
+    assert_eq!(plain, with_table);
+
+    // a table of another font is ignored
+    let other = DEJAVU_MONO.kern_table();
+    let with_other: Vec<_> = font
+        .layout(text, scale, start)
+        .with_kern_table(&other)
+        .collect();
+    assert_eq!(plain, with_other);
+}
+
+#[test]
+fn kerning_pairs_match_pair_kerning() {
+    let font = &*OPEN_SANS_ITALIC;
+    let pairs: Vec<_> = font.kerning_pairs().collect();
+    assert!(!pairs.is_empty());
+    assert!(pairs
+        .windows(2)
+        .all(|w| (w[0].0, w[0].1) < (w[1].0, w[1].1)));
+    assert!(pairs.iter().all(|&(_, _, kerning)| kerning != 0));
+
+    // kern table values, with GPOS kerning for other pairs
+    let kern_table = font.kern_table();
+    for &(first, second, kerning) in &pairs {
+        let kern = kern_table.pair_kerning_unscaled(first, second);
+        assert!(kern == kerning || kern == 0);
+    }
+    // pairs not listed have no kerning
+    let listed: std::collections::HashSet<_> = pairs.iter().map(|&(a, b, _)| (a, b)).collect();
+    let ids: Vec<_> = (0..font.glyph_count() as u16)
+        .step_by(5)
+        .map(GlyphId)
+        .collect();
+    for &first in &ids {
+        for &second in &ids {
+            if !listed.contains(&(first, second)) {
+                assert_eq!(font.pair_kerning(Scale::uniform(20.0), first, second), 0.0);
+            }
+        }
+    }
+
+    assert_eq!(DEJAVU_MONO.kerning_pairs().count(), 0);
+}
+
+#[test]
+fn kerning_pairs_match_gpos_shaping() {
+    // GPOS kerning only, no kern table
+    let font =
+        Font::try_from_bytes(include_bytes!("../fonts/Roboto-Regular.ttf") as &[u8]).unwrap();
+    let pairs: std::collections::HashMap<_, _> = font
+        .kerning_pairs()
+        .map(|(first, second, kerning)| ((first, second), kerning))
+        .collect();
+    assert!(!pairs.is_empty());
+
+    let scale = Scale::uniform(100.0);
+    let units = font.scale_for_pixel_height(scale.y);
+    let chars = "AVTWYLPFoaey.,-'";
+    let shaper = font.shaper().unwrap();
+    let mut kerned = 0;
+    for first in chars.chars() {
+        for second in chars.chars() {
+            let text: String = [first, second].iter().collect();
+            let glyphs = shaper.shape(&text, &[], None, None, scale, point(0.0, 0.0));
+            let advance = glyphs[0].unpositioned().h_metrics().advance_width;
+            let shaped = glyphs[1].position().x - glyphs[0].position().x - advance;
+
+            let ids = (font.glyph(first).id(), font.glyph(second).id());
+            let kerning = pairs.get(&ids).copied().unwrap_or(0);
+            assert!(
+                (shaped - f32::from(kerning) * units).abs() < 1e-3,
+                "{text:?} shaped {shaped} listed {kerning}"
+            );
+            kerned += usize::from(kerning != 0);
+        }
+    }
+    assert!(kerned > 20);
+}
+
+#[test]
+fn layout_shared_across_threads() {
+    let font = OPEN_SANS_ITALIC.clone();
+    let text = "Hello World!";
+    let expected: Vec<_> = font
+        .layout(text, Scale::uniform(20.0), point(0.0, 20.0))
+        .map(|g| g.position())
+        .collect();
+
+    let glyphs = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let font = font.clone();
+                scope.spawn(move || {
+                    font.layout(text, Scale::uniform(20.0), point(0.0, 20.0))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|w| w.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    for glyphs in glyphs {
+        // glyphs are sent back from the worker threads
+        let positions: Vec<_> = glyphs.iter().map(|g| g.position()).collect();
+        assert_eq!(positions, expected);
+    }
+}
+
+#[test]
+fn layout_ids_match_layout() {
+    let font = &*OPEN_SANS_ITALIC;
+    let scale = Scale::uniform(22.0);
+    let text = "AVAST ye\tWaves!\nfi";
+    let options = LayoutOptions::new()
+        .with_tab_width(Some(TabWidth::Spaces(4.0)))
+        .with_newline(NewlineMode::Break)
+        .with_letter_spacing(1.5);
+    let glyphs: Vec<_> = font
+        .layout(text, scale, point(3.0, 30.0))
+        .with_options(options)
+        .collect();
+    let placed: Vec<_> = font
+        .layout(text, scale, point(3.0, 30.0))
+        .with_options(options)
+        .ids()
+        .collect();
+
+    assert_eq!(placed.len(), glyphs.len());
+    for (p, g) in placed.iter().zip(&glyphs) {
+        assert_eq!(p.id, g.id());
+        assert_eq!(p.position, g.position());
+        assert_eq!(p.scale, g.scale());
+    }
+
+    let plain: Vec<_> = font.layout_ids("Hi", scale, point(0.0, 0.0)).collect();
+    assert_eq!(plain[1].id, font.glyph('i').id());
+}
@@ -0,0 +1,92 @@
+//! Conversions between rusttype & [ab_glyph](https://docs.rs/ab_glyph) types.
+use crate::{Font, GlyphId, Point, Rect, Scale};
+
+impl From<ab_glyph::GlyphId> for GlyphId {
+    #[inline]
+    fn from(id: ab_glyph::GlyphId) -> Self {
+        Self(id.0)
+    }
+}
+impl From<GlyphId> for ab_glyph::GlyphId {
+    #[inline]
+    fn from(id: GlyphId) -> Self {
+        Self(id.0)
+    }
+}
+
+impl From<ab_glyph::Point> for Point<f32> {
+    #[inline]
+    fn from(p: ab_glyph::Point) -> Self {
+        Self { x: p.x, y: p.y }
+    }
+}
+impl From<Point<f32>> for ab_glyph::Point {
+    #[inline]
+    fn from(p: Point<f32>) -> Self {
+        Self { x: p.x, y: p.y }
+    }
+}
+
+impl From<ab_glyph::Rect> for Rect<f32> {
+    #[inline]
+    fn from(r: ab_glyph::Rect) -> Self {
+        Self {
+            min: r.min.into(),
+            max: r.max.into(),
+        }
+    }
+}
+impl From<Rect<f32>> for ab_glyph::Rect {
+    #[inline]
+    fn from(r: Rect<f32>) -> Self {
+        Self {
+            min: r.min.into(),
+            max: r.max.into(),
+        }
+    }
+}
+
+/// Note: ab_glyph scale is the height of a glyph in pixels, as with rusttype,
+/// so values are carried over unchanged.
+impl From<ab_glyph::PxScale> for Scale {
+    #[inline]
+    fn from(s: ab_glyph::PxScale) -> Self {
+        Self { x: s.x, y: s.y }
+    }
+}
+impl From<Scale> for ab_glyph::PxScale {
+    #[inline]
+    fn from(s: Scale) -> Self {
+        Self { x: s.x, y: s.y }
+    }
+}
+
+impl<'font> Font<'font> {
+    /// Returns an [`ab_glyph::FontRef`] borrowing this font's data.
+    ///
+    /// The font data is shared, not copied. ab_glyph does its own, relatively
+    /// cheap, pre-parsing of the font tables so this should not be called
+    /// per-glyph.
+    ///
+    /// Returns an error if ab_glyph rejects font data rusttype's parser
+    /// accepted.
+    ///
+    /// Requires the `ab_glyph` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::Font;
+    /// use ab_glyph::Font as _;
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// let font = Font::try_from_bytes(font_data).unwrap();
+    /// let ab_font = font.to_ab_glyph().unwrap();
+    ///
+    /// let id = font.glyph('a').id();
+    /// assert_eq!(ab_font.glyph_id('a'), id.into());
+    /// ```
+    pub fn to_ab_glyph(&self) -> Result<ab_glyph::FontRef<'_>, ab_glyph::InvalidFont> {
+        let raw = self.inner().raw_face();
+        ab_glyph::FontRef::try_from_slice_and_index(raw.data, self.collection_index())
+    }
+}
@@ -0,0 +1,291 @@
+use crate::{char_class, Font, GlyphId};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use owned_ttf_parser::{gsub::SubstitutionSubtable, opentype_layout::LayoutTable, Tag};
+
+/// Unicode joining type, see The Unicode Standard section 9.2.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum JoiningType {
+    NonJoining,
+    Right,
+    Dual,
+    Causing,
+    Transparent,
+}
+
+impl JoiningType {
+    fn of(c: char) -> Self {
+        match c as u32 {
+            // tatweel & zero width joiner
+            0x0640 | 0x200D => Self::Causing,
+            // zero width non-joiner
+            0x200C => Self::NonJoining,
+            // marks & format chars
+            _ if !c.is_control() && char_class::is_zero_width(c) => Self::Transparent,
+            0x0622..=0x0625
+            | 0x0627
+            | 0x0629
+            | 0x062F..=0x0632
+            | 0x0648
+            | 0x0671..=0x0673
+            | 0x0675..=0x0677
+            | 0x0688..=0x0699
+            | 0x06C0
+            | 0x06C3..=0x06CB
+            | 0x06CD
+            | 0x06CF
+            | 0x06D2
+            | 0x06D3
+            | 0x06D5
+            | 0x06EE
+            | 0x06EF => Self::Right,
+            0x0620
+            | 0x0626
+            | 0x0628
+            | 0x062A..=0x062E
+            | 0x0633..=0x063F
+            | 0x0641..=0x0647
+            | 0x0649
+            | 0x064A
+            | 0x066E
+            | 0x066F
+            | 0x0678..=0x0687
+            | 0x069A..=0x06BF
+            | 0x06C1
+            | 0x06C2
+            | 0x06CC
+            | 0x06CE
+            | 0x06D0
+            | 0x06D1
+            | 0x06FA..=0x06FC
+            | 0x06FF => Self::Dual,
+            _ => Self::NonJoining,
+        }
+    }
+
+    /// Joins to the following char, in logical order.
+    #[inline]
+    fn joins_next(self) -> bool {
+        matches!(self, Self::Dual | Self::Causing)
+    }
+
+    /// Joins to the preceding char, in logical order.
+    #[inline]
+    fn joins_prev(self) -> bool {
+        matches!(self, Self::Right | Self::Dual | Self::Causing)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Form {
+    Isolated,
+    Initial,
+    Medial,
+    Final,
+}
+
+impl Form {
+    const ALL: [Self; 4] = [Self::Isolated, Self::Initial, Self::Medial, Self::Final];
+
+    fn feature(self) -> Tag {
+        Tag::from_bytes(match self {
+            Self::Isolated => b"isol",
+            Self::Initial => b"init",
+            Self::Medial => b"medi",
+            Self::Final => b"fina",
+        })
+    }
+}
+
+/// Returns the positional form of each char, `None` for chars without forms.
+fn joining_forms(chars: &[char]) -> Vec<Option<Form>> {
+    let mut forms = alloc::vec![None; chars.len()];
+    // previous char that isn't transparent
+    let mut prev: Option<(usize, JoiningType)> = None;
+    for (index, &c) in chars.iter().enumerate() {
+        let joining = JoiningType::of(c);
+        if joining == JoiningType::Transparent {
+            continue;
+        }
+        let joins_prev = joining.joins_prev() && prev.is_some_and(|(_, j)| j.joins_next());
+        if let (true, Some((prev_index, _))) = (joins_prev, prev) {
+            forms[prev_index] = match forms[prev_index] {
+                Some(Form::Isolated) => Some(Form::Initial),
+                Some(Form::Final) => Some(Form::Medial),
+                form => form,
+            };
+        }
+        if matches!(joining, JoiningType::Right | JoiningType::Dual) {
+            forms[index] = Some(if joins_prev {
+                Form::Final
+            } else {
+                Form::Isolated
+            });
+        }
+        prev = Some((index, joining));
+    }
+    forms
+}
+
+/// Returns the lookup indices, in lookup list order, of a feature of the
+/// `arab` script, or the default script, default language system.
+fn feature_lookups(gsub: &LayoutTable<'_>, feature: Tag) -> Vec<u16> {
+    let script = gsub
+        .scripts
+        .find(Tag::from_bytes(b"arab"))
+        .or_else(|| gsub.scripts.find(Tag::from_bytes(b"DFLT")));
+    let Some(language) = script.and_then(|s| s.default_language) else {
+        return Vec::new();
+    };
+    let mut lookups: Vec<_> = language
+        .feature_indices
+        .into_iter()
+        .filter_map(|index| gsub.features.get(index))
+        .filter(|f| f.tag == feature)
+        .flat_map(|f| f.lookup_indices)
+        .collect();
+    lookups.sort_unstable();
+    lookups.dedup();
+    lookups
+}
+
+fn substitute_single(
+    gsub: &LayoutTable<'_>,
+    lookups: &[u16],
+    mut glyph: owned_ttf_parser::GlyphId,
+) -> owned_ttf_parser::GlyphId {
+    use owned_ttf_parser::gsub::SingleSubstitution;
+
+    for lookup in lookups.iter().filter_map(|&index| gsub.lookups.get(index)) {
+        let substitute = lookup
+            .subtables
+            .into_iter::<SubstitutionSubtable>()
+            .find_map(|subtable| match subtable {
+                SubstitutionSubtable::Single(SingleSubstitution::Format1 { coverage, delta }) => {
+                    coverage.get(glyph)?;
+                    // spec'd as addition modulo 65536
+                    Some(owned_ttf_parser::GlyphId(
+                        glyph.0.wrapping_add(delta as u16),
+                    ))
+                }
+                SubstitutionSubtable::Single(SingleSubstitution::Format2 {
+                    coverage,
+                    substitutes,
+                }) => substitutes.get(coverage.get(glyph)?),
+                _ => None,
+            });
+        if let Some(substitute) = substitute {
+            glyph = substitute;
+        }
+    }
+    glyph
+}
+
+fn substitute_ligatures(
+    gsub: &LayoutTable<'_>,
+    lookups: &[u16],
+    glyphs: &mut Vec<owned_ttf_parser::GlyphId>,
+) {
+    for lookup in lookups.iter().filter_map(|&index| gsub.lookups.get(index)) {
+        let mut index = 0;
+        while index < glyphs.len() {
+            let ligature = lookup
+                .subtables
+                .into_iter::<SubstitutionSubtable>()
+                .find_map(|subtable| {
+                    let SubstitutionSubtable::Ligature(subtable) = subtable else {
+                        return None;
+                    };
+                    let set = subtable
+                        .ligature_sets
+                        .get(subtable.coverage.get(glyphs[index])?)?;
+                    let following = &glyphs[index + 1..];
+                    set.into_iter().find(|ligature| {
+                        usize::from(ligature.components.len()) <= following.len()
+                            && ligature
+                                .components
+                                .into_iter()
+                                .eq(following[..usize::from(ligature.components.len())]
+                                    .iter()
+                                    .copied())
+                    })
+                });
+            if let Some(ligature) = ligature {
+                let end = index + 1 + usize::from(ligature.components.len());
+                glyphs.splice(index..end, [ligature.glyph]);
+            }
+            index += 1;
+        }
+    }
+}
+
+impl Font<'_> {
+    /// Maps Arabic `text` to glyph ids, selecting the isolated, initial,
+    /// medial or final form of each letter according to the Unicode joining
+    /// algorithm.
+    ///
+    /// Forms are selected with the font's GSUB `isol`, `init`, `medi` & `fina`
+    /// single substitutions, then `rlig` ligatures, like lam-alef, are applied.
+    /// Chars of other scripts map to the same glyphs as `Font::glyph`.
+    ///
+    /// # Limitations
+    ///
+    /// Only single & ligature substitution lookups are applied. Multiple,
+    /// alternate, contextual & chaining contextual lookups are skipped, as
+    /// are other features like `calt` & `mark` positioning, so fonts relying
+    /// on them, eg for kashida or stacked ligatures, are only partially
+    /// shaped. Use `Font::shape`, with the `rustybuzz` feature, for complete
+    /// shaping.
+    ///
+    /// Glyphs are returned in logical order, reverse them to lay out
+    /// right-to-left.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::Font;
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// // beh beh beh
+    /// let glyphs = font.arabic_glyphs("\u{628}\u{628}\u{628}");
+    ///
+    /// // initial, medial & final presentation forms
+    /// let forms: Vec<_> = ['\u{fe91}', '\u{fe92}', '\u{fe90}']
+    ///     .iter()
+    ///     .map(|&c| font.glyph(c).id())
+    ///     .collect();
+    /// assert_eq!(glyphs, forms);
+    /// ```
+    pub fn arabic_glyphs(&self, text: &str) -> Vec<GlyphId> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut glyphs: Vec<_> = chars
+            .iter()
+            .map(|&c| self.char_glyph_id(c).into())
+            .collect();
+
+        if let Some(gsub) = self.inner().tables().gsub {
+            let forms = joining_forms(&chars);
+            for form in Form::ALL {
+                let lookups = feature_lookups(&gsub, form.feature());
+                for (glyph, _) in glyphs
+                    .iter_mut()
+                    .zip(&forms)
+                    .filter(|(_, f)| **f == Some(form))
+                {
+                    *glyph = substitute_single(&gsub, &lookups, *glyph);
+                }
+            }
+            let rlig = feature_lookups(&gsub, Tag::from_bytes(b"rlig"));
+            substitute_ligatures(&gsub, &rlig, &mut glyphs);
+        }
+        // substitutions of malformed fonts may be invalid
+        let glyph_count = self.glyph_count();
+        glyphs
+            .into_iter()
+            .map(|id| match GlyphId::from(id) {
+                id if (id.0 as usize) < glyph_count => id,
+                _ => GlyphId(0),
+            })
+            .collect()
+    }
+}
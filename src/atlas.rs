@@ -0,0 +1,424 @@
+//! Baking static glyph atlases, eg in build scripts or offline asset tools,
+//! for bitmap font pipelines.
+//!
+//! This module is optional, and not compiled by default. To use it enable the
+//! `atlas` feature in your Cargo.toml. With the `serde` feature the atlas
+//! types are serializable.
+//!
+//! Unlike `gpu_cache`, which maintains a dynamic texture at runtime, an
+//! `Atlas` is packed & rasterised once for a fixed set of fonts, chars &
+//! sizes, producing texture bytes along with per glyph metrics, texture
+//! coordinates & kerning to store alongside it. Metadata can be exported as
+//! BMFont `.fnt` descriptors with `Atlas::to_bmfont`.
+//!
+//! # Example
+//!
+//! ```
+//! # use rusttype::{atlas::AtlasBuilder, Font};
+//! # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+//! # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+//! let atlas = AtlasBuilder::new()
+//!     .font(font)
+//!     .chars(' '..='~')
+//!     .sizes([16.0, 32.0])
+//!     .width(256)
+//!     .build()
+//!     .unwrap();
+//!
+//! // coverage bytes of the texture, row major
+//! assert_eq!(atlas.pixels.len(), (atlas.width * atlas.height) as usize);
+//!
+//! let a = atlas.glyph(0, 'a', 16.0).unwrap();
+//! // draw a quad at `origin + a.offset` sized `a.tex_size()` using `a.uv`
+//! ```
+mod bmfont;
+
+pub use bmfont::BmFontFormat;
+
+use crate::{point, Font, GlyphId, Scale};
+use alloc::collections::BTreeMap;
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+/// Error building an `Atlas`, see `AtlasBuilder::build`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AtlasError {
+    /// A glyph, including padding, is wider than the atlas, or the packed
+    /// glyphs are too large to address in an atlas of its width.
+    GlyphTooWide,
+}
+
+impl fmt::Display for AtlasError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AtlasError::GlyphTooWide => "Glyph too wide for the atlas",
+        }
+        .fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AtlasError {}
+
+/// Builder for a static `Atlas`.
+///
+/// Every char is baked for every font & size. Glyphs are packed into rows,
+/// tallest first, in an atlas of fixed `width` & the height needed.
+#[derive(Clone, Debug)]
+pub struct AtlasBuilder<'font> {
+    fonts: Vec<Font<'font>>,
+    chars: Vec<char>,
+    sizes: Vec<f32>,
+    width: u32,
+    padding: u32,
+    power_of_two: bool,
+    kerning: bool,
+}
+
+impl Default for AtlasBuilder<'_> {
+    fn default() -> Self {
+        Self {
+            fonts: Vec::new(),
+            chars: Vec::new(),
+            sizes: Vec::new(),
+            width: 512,
+            padding: 1,
+            power_of_two: false,
+            kerning: false,
+        }
+    }
+}
+
+impl<'font> AtlasBuilder<'font> {
+    /// Returns a builder with no fonts, chars or sizes, a width of `512` &
+    /// padding of `1`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a font, identified in the atlas by its index in the order added.
+    pub fn font(mut self, font: Font<'font>) -> Self {
+        self.fonts.push(font);
+        self
+    }
+
+    /// Adds chars to bake. Duplicates are ignored.
+    pub fn chars(mut self, chars: impl IntoIterator<Item = char>) -> Self {
+        self.chars.extend(chars);
+        self
+    }
+
+    /// Adds pixel sizes to bake, as `Scale::uniform`. Duplicates are ignored.
+    pub fn sizes(mut self, sizes: impl IntoIterator<Item = f32>) -> Self {
+        self.sizes.extend(sizes);
+        self
+    }
+
+    /// Width of the atlas texture in pixels.
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Pixels of empty space kept around each glyph, avoiding bleeding of
+    /// neighbouring glyphs when sampling with bilinear filtering.
+    pub fn padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Rounds the atlas height up to a power of two, for APIs requiring
+    /// power of two textures. Set `width` to a power of two too.
+    pub fn power_of_two(mut self, power_of_two: bool) -> Self {
+        self.power_of_two = power_of_two;
+        self
+    }
+
+    /// Bakes kerning between the chars into `Atlas::kernings`, off by
+    /// default. Lists every kerned pair of each font, see
+    /// `Font::kerning_pairs`, which can be slow for fonts with large kerning
+    /// classes.
+    pub fn kerning(mut self, kerning: bool) -> Self {
+        self.kerning = kerning;
+        self
+    }
+
+    /// Packs & rasterises the atlas.
+    ///
+    /// Chars mapping to the same glyph, eg missing chars drawn as `.notdef`,
+    /// share texture space.
+    pub fn build(&self) -> Result<Atlas, AtlasError> {
+        let mut chars = self.chars.clone();
+        chars.sort_unstable();
+        chars.dedup();
+        let mut sizes = self.sizes.clone();
+        sizes.sort_unstable_by(f32::total_cmp);
+        sizes.dedup();
+
+        let mut glyphs = Vec::new();
+        let mut faces = Vec::new();
+        // distinct glyphs to rasterise, by font, size & glyph id
+        let mut rasters = BTreeMap::new();
+        for (font_index, font) in self.fonts.iter().enumerate() {
+            for &size in &sizes {
+                let scale = Scale::uniform(size);
+                let v_metrics = font.v_metrics(scale);
+                faces.push(AtlasFace {
+                    font_index,
+                    size,
+                    ascent: v_metrics.ascent,
+                    descent: v_metrics.descent,
+                    line_gap: v_metrics.line_gap,
+                });
+                for &character in &chars {
+                    let glyph = font.glyph(character).scaled(scale);
+                    let advance = glyph.h_metrics().advance_width;
+                    let glyph_id = glyph.id().0;
+                    let glyph = glyph.positioned(point(0.0, 0.0));
+                    let offset = glyph
+                        .pixel_bounding_box()
+                        .map_or([0, 0], |bb| [bb.min.x, bb.min.y]);
+                    rasters
+                        .entry((font_index, size.to_bits(), glyph_id))
+                        .or_insert(glyph);
+                    glyphs.push(AtlasGlyph {
+                        font_index,
+                        character,
+                        glyph_id,
+                        size,
+                        tex_min: [0, 0],
+                        tex_max: [0, 0],
+                        uv: [0.0; 4],
+                        offset,
+                        advance,
+                    });
+                }
+            }
+        }
+
+        // shelf pack, tallest first
+        let padding = self.padding;
+        let mut order: Vec<_> = rasters
+            .iter()
+            .filter_map(|(key, glyph)| Some((key, glyph, glyph.pixel_bounding_box()?)))
+            .collect();
+        order.sort_by_key(|(_, _, bb)| core::cmp::Reverse(bb.height()));
+        let mut placed = BTreeMap::new();
+        let (mut x, mut y, mut row_height) = (padding, padding, 0);
+        let too_wide = || AtlasError::GlyphTooWide;
+        for (key, glyph, bb) in order {
+            let (w, h) = (bb.width() as u32, bb.height() as u32);
+            let padded_w = padding
+                .checked_mul(2)
+                .and_then(|p| p.checked_add(w))
+                .filter(|&padded_w| padded_w <= self.width)
+                .ok_or_else(too_wide)?;
+            // x is at most the width after each glyph
+            if padded_w - padding > self.width - x {
+                x = padding;
+                y = y
+                    .checked_add(row_height)
+                    .and_then(|y| y.checked_add(padding))
+                    .ok_or_else(too_wide)?;
+                row_height = 0;
+            }
+            let max_y = y.checked_add(h).ok_or_else(too_wide)?;
+            placed.insert(*key, (glyph, [x, y], [x + w, max_y]));
+            x += w + padding;
+            row_height = row_height.max(h);
+        }
+        let mut height = y
+            .checked_add(row_height)
+            .and_then(|height| height.checked_add(padding))
+            .ok_or_else(too_wide)?;
+        if self.power_of_two {
+            height = height.checked_next_power_of_two().ok_or_else(too_wide)?;
+        }
+
+        let width = self.width;
+        let len = (width as usize)
+            .checked_mul(height as usize)
+            .ok_or_else(too_wide)?;
+        let mut pixels = vec![0; len];
+        for (glyph, min, _) in placed.values() {
+            glyph.draw(|gx, gy, v| {
+                let i = (min[1] + gy) as usize * width as usize + (min[0] + gx) as usize;
+                pixels[i] = (v * 255.0 + 0.5) as u8;
+            });
+        }
+
+        for g in &mut glyphs {
+            let key = (g.font_index, g.size.to_bits(), g.glyph_id);
+            if let Some((_, min, max)) = placed.get(&key) {
+                g.tex_min = *min;
+                g.tex_max = *max;
+                g.uv = [
+                    min[0] as f32 / width as f32,
+                    min[1] as f32 / height as f32,
+                    max[0] as f32 / width as f32,
+                    max[1] as f32 / height as f32,
+                ];
+            }
+        }
+
+        Ok(Atlas {
+            width,
+            height,
+            padding,
+            pixels,
+            faces,
+            kernings: if self.kerning {
+                self.kernings(&chars, &sizes)
+            } else {
+                Vec::new()
+            },
+            glyphs,
+        })
+    }
+
+    /// Returns the non-zero kerning between the baked chars, see
+    /// `Font::kerning_pairs`.
+    fn kernings(&self, chars: &[char], sizes: &[f32]) -> Vec<AtlasKerning> {
+        let mut kernings = Vec::new();
+        for (font_index, font) in self.fonts.iter().enumerate() {
+            let mut chars_of_glyph: BTreeMap<GlyphId, Vec<char>> = BTreeMap::new();
+            for &c in chars {
+                chars_of_glyph
+                    .entry(font.glyph(c).id())
+                    .or_default()
+                    .push(c);
+            }
+            for (first, second, kerning) in font.kerning_pairs() {
+                let (Some(firsts), Some(seconds)) =
+                    (chars_of_glyph.get(&first), chars_of_glyph.get(&second))
+                else {
+                    continue;
+                };
+                for &size in sizes {
+                    let amount = font.kerning_factor(Scale::uniform(size)) * f32::from(kerning);
+                    for &first in firsts {
+                        for &second in seconds {
+                            kernings.push(AtlasKerning {
+                                font_index,
+                                size,
+                                first,
+                                second,
+                                amount,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        kernings
+    }
+}
+
+/// A baked glyph atlas, see `AtlasBuilder`.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Atlas {
+    /// Texture width in pixels.
+    pub width: u32,
+    /// Texture height in pixels.
+    pub height: u32,
+    /// Empty pixels kept around each glyph, see `AtlasBuilder::padding`.
+    pub padding: u32,
+    /// Glyph coverage of each texture pixel, row major, `0` to `255`.
+    pub pixels: Vec<u8>,
+    /// Vertical metrics of each baked font & size.
+    pub faces: Vec<AtlasFace>,
+    /// Each baked char of each font & size.
+    pub glyphs: Vec<AtlasGlyph>,
+    /// Non-zero kerning between baked chars of each font & size, empty unless
+    /// enabled with `AtlasBuilder::kerning`.
+    pub kernings: Vec<AtlasKerning>,
+}
+
+impl Atlas {
+    /// Returns the glyph of `character` baked from the font at `font_index`
+    /// at pixel `size`.
+    pub fn glyph(&self, font_index: usize, character: char, size: f32) -> Option<&AtlasGlyph> {
+        self.glyphs
+            .iter()
+            .find(|g| g.font_index == font_index && g.character == character && g.size == size)
+    }
+
+    /// Returns the vertical metrics of the font at `font_index` at pixel
+    /// `size`.
+    pub fn face(&self, font_index: usize, size: f32) -> Option<&AtlasFace> {
+        self.faces
+            .iter()
+            .find(|f| f.font_index == font_index && f.size == size)
+    }
+}
+
+/// Vertical metrics of a font at a baked size, in pixels, see `VMetrics`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AtlasFace {
+    /// Index of the font in the order added to the builder.
+    pub font_index: usize,
+    /// Baked pixel size.
+    pub size: f32,
+    /// Baseline to top of the tallest glyphs, positive.
+    pub ascent: f32,
+    /// Baseline to bottom of the lowest glyphs, usually negative.
+    pub descent: f32,
+    /// Recommended gap between a line's descent & the next line's ascent.
+    pub line_gap: f32,
+}
+
+/// A glyph baked into an `Atlas`.
+///
+/// Glyphs without a shape, eg spaces, have an empty texture rect.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AtlasGlyph {
+    /// Index of the font in the order added to the builder.
+    pub font_index: usize,
+    /// The baked char.
+    pub character: char,
+    /// Id of the glyph in the font.
+    pub glyph_id: u16,
+    /// Pixel size the glyph was rasterised at.
+    pub size: f32,
+    /// Top left texture pixel of the glyph.
+    pub tex_min: [u32; 2],
+    /// Bottom right texture pixel of the glyph, exclusive.
+    pub tex_max: [u32; 2],
+    /// Normalised texture coordinates, `[min_x, min_y, max_x, max_y]`.
+    pub uv: [f32; 4],
+    /// Offset from the glyph origin on the baseline to the top left of the
+    /// glyph quad, in pixels with y increasing downwards.
+    pub offset: [i32; 2],
+    /// Horizontal advance to the next glyph origin, in pixels.
+    pub advance: f32,
+}
+
+impl AtlasGlyph {
+    /// Returns the width & height of the glyph quad in pixels.
+    pub fn tex_size(&self) -> [u32; 2] {
+        [
+            self.tex_max[0] - self.tex_min[0],
+            self.tex_max[1] - self.tex_min[1],
+        ]
+    }
+}
+
+/// Kerning between two chars baked into an `Atlas`, see `Font::pair_kerning`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AtlasKerning {
+    /// Index of the font in the order added to the builder.
+    pub font_index: usize,
+    /// Baked pixel size.
+    pub size: f32,
+    /// The preceding char.
+    pub first: char,
+    /// The following char.
+    pub second: char,
+    /// Caret adjustment between the chars in pixels, usually negative.
+    pub amount: f32,
+}
@@ -0,0 +1,227 @@
+//! Export of atlas metadata in the AngelCode BMFont `.fnt` formats.
+use super::{Atlas, AtlasFace, AtlasGlyph, AtlasKerning};
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+#[cfg(all(feature = "libm-math", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+
+/// A file format of BMFont `.fnt` descriptors, see `Atlas::to_bmfont`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BmFontFormat {
+    /// Lines of `tag key=value` pairs, the most widely supported format.
+    #[default]
+    Text,
+    /// An XML document with the same elements & attributes.
+    Xml,
+}
+
+impl Atlas {
+    /// Returns a BMFont `.fnt` descriptor of the glyphs baked from the font
+    /// at `font_index` at pixel `size`, for engines & tools importing
+    /// AngelCode bitmap fonts. `None` if the font & size weren't baked.
+    ///
+    /// The atlas is a single page, the texture saved as `page_file`, eg a png
+    /// of `pixels` relative to the descriptor. `face_name` is the font name
+    /// recorded in the descriptor.
+    ///
+    /// Metrics are rounded to whole pixels. Kerning is included as
+    /// `kerning` elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{atlas::{AtlasBuilder, BmFontFormat}, Font};
+    /// # let font_data: &[u8] = include_bytes!("../../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let atlas = AtlasBuilder::new()
+    ///     .font(font)
+    ///     .chars(' '..='~')
+    ///     .sizes([32.0])
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let fnt = atlas
+    ///     .to_bmfont(0, 32.0, BmFontFormat::Text, "DejaVu Sans Mono", "dejavu_0.png")
+    ///     .unwrap();
+    /// assert!(fnt.starts_with("info face=\"DejaVu Sans Mono\" size=32"));
+    /// // std::fs::write("dejavu.fnt", fnt)
+    /// ```
+    pub fn to_bmfont(
+        &self,
+        font_index: usize,
+        size: f32,
+        format: BmFontFormat,
+        face_name: &str,
+        page_file: &str,
+    ) -> Option<String> {
+        let face = self.face(font_index, size)?;
+        let glyphs: Vec<_> = self
+            .glyphs
+            .iter()
+            .filter(|g| g.font_index == font_index && g.size == size)
+            .collect();
+        let kernings: Vec<_> = self
+            .kernings
+            .iter()
+            .filter(|k| k.font_index == font_index && k.size == size && k.amount.round() != 0.0)
+            .collect();
+
+        let fnt = Descriptor {
+            atlas: self,
+            face,
+            glyphs: &glyphs,
+            kernings: &kernings,
+        };
+        let mut out = String::new();
+        match format {
+            BmFontFormat::Text => fnt.write_text(&mut out, face_name, page_file),
+            BmFontFormat::Xml => fnt.write_xml(&mut out, face_name, page_file),
+        }
+        .ok()?;
+        Some(out)
+    }
+}
+
+struct Descriptor<'a> {
+    atlas: &'a Atlas,
+    face: &'a AtlasFace,
+    glyphs: &'a [&'a AtlasGlyph],
+    kernings: &'a [&'a AtlasKerning],
+}
+
+impl Descriptor<'_> {
+    /// Returns the common line height & base, baseline from the line top.
+    fn line(&self) -> (i32, i32) {
+        let AtlasFace {
+            ascent,
+            descent,
+            line_gap,
+            ..
+        } = *self.face;
+        (
+            (ascent - descent + line_gap).round() as i32,
+            ascent.round() as i32,
+        )
+    }
+
+    /// Returns the `char` element attributes of a glyph, in order.
+    fn char_attributes(&self, g: &AtlasGlyph) -> [(&'static str, i64); 10] {
+        let (_, base) = self.line();
+        let [width, height] = g.tex_size();
+        [
+            ("id", i64::from(u32::from(g.character))),
+            ("x", i64::from(g.tex_min[0])),
+            ("y", i64::from(g.tex_min[1])),
+            ("width", i64::from(width)),
+            ("height", i64::from(height)),
+            ("xoffset", i64::from(g.offset[0])),
+            ("yoffset", i64::from(base + g.offset[1])),
+            ("xadvance", g.advance.round() as i64),
+            ("page", 0),
+            ("chnl", 15),
+        ]
+    }
+
+    fn write_text(&self, out: &mut String, face_name: &str, page_file: &str) -> core::fmt::Result {
+        let (line_height, base) = self.line();
+        // the text format has no escaping
+        let quoted = |s: &str| s.replace('"', "'");
+        writeln!(
+            out,
+            "info face=\"{}\" size={} bold=0 italic=0 charset=\"\" unicode=1 stretchH=100 \
+             smooth=1 aa=1 padding=0,0,0,0 spacing={p},{p}",
+            quoted(face_name),
+            self.face.size.round(),
+            p = self.atlas.padding,
+        )?;
+        writeln!(
+            out,
+            "common lineHeight={line_height} base={base} scaleW={} scaleH={} pages=1 packed=0",
+            self.atlas.width, self.atlas.height,
+        )?;
+        writeln!(out, "page id=0 file=\"{}\"", quoted(page_file))?;
+        writeln!(out, "chars count={}", self.glyphs.len())?;
+        for g in self.glyphs {
+            out.push_str("char");
+            for (key, value) in self.char_attributes(g) {
+                write!(out, " {key}={value}")?;
+            }
+            out.push('\n');
+        }
+        writeln!(out, "kernings count={}", self.kernings.len())?;
+        for k in self.kernings {
+            writeln!(
+                out,
+                "kerning first={} second={} amount={}",
+                u32::from(k.first),
+                u32::from(k.second),
+                k.amount.round(),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn write_xml(&self, out: &mut String, face_name: &str, page_file: &str) -> core::fmt::Result {
+        let (line_height, base) = self.line();
+        writeln!(out, "<?xml version=\"1.0\"?>")?;
+        writeln!(out, "<font>")?;
+        writeln!(
+            out,
+            "  <info face=\"{}\" size=\"{}\" bold=\"0\" italic=\"0\" charset=\"\" unicode=\"1\" \
+             stretchH=\"100\" smooth=\"1\" aa=\"1\" padding=\"0,0,0,0\" spacing=\"{p},{p}\"/>",
+            xml_escape(face_name),
+            self.face.size.round(),
+            p = self.atlas.padding,
+        )?;
+        writeln!(
+            out,
+            "  <common lineHeight=\"{line_height}\" base=\"{base}\" scaleW=\"{}\" scaleH=\"{}\" \
+             pages=\"1\" packed=\"0\"/>",
+            self.atlas.width, self.atlas.height,
+        )?;
+        writeln!(out, "  <pages>")?;
+        writeln!(
+            out,
+            "    <page id=\"0\" file=\"{}\"/>",
+            xml_escape(page_file)
+        )?;
+        writeln!(out, "  </pages>")?;
+        writeln!(out, "  <chars count=\"{}\">", self.glyphs.len())?;
+        for g in self.glyphs {
+            out.push_str("    <char");
+            for (key, value) in self.char_attributes(g) {
+                write!(out, " {key}=\"{value}\"")?;
+            }
+            out.push_str("/>\n");
+        }
+        writeln!(out, "  </chars>")?;
+        writeln!(out, "  <kernings count=\"{}\">", self.kernings.len())?;
+        for k in self.kernings {
+            writeln!(
+                out,
+                "    <kerning first=\"{}\" second=\"{}\" amount=\"{}\"/>",
+                u32::from(k.first),
+                u32::from(k.second),
+                k.amount.round(),
+            )?;
+        }
+        writeln!(out, "  </kernings>")?;
+        writeln!(out, "</font>")
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
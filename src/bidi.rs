@@ -0,0 +1,175 @@
+//! Splits text into runs of uniform direction & script, using the Unicode
+//! Bidirectional Algorithm ([UAX #9](https://unicode.org/reports/tr9/)), and
+//! lays out mixed left-to-right & right-to-left text in visual order.
+//!
+//! Requires the `bidi` feature.
+//!
+//! # Example
+//!
+//! ```
+//! # use rusttype::{bidi::{self, Direction}, point, Font, Scale};
+//! # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+//! # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+//! let text = "abc \u{5d0}\u{5d1}";
+//!
+//! let runs = bidi::runs(text);
+//! assert_eq!(runs[0].direction, Direction::LeftToRight);
+//! assert_eq!(&text[runs[1].text_range.clone()], "\u{5d0}\u{5d1}");
+//! assert_eq!(runs[1].direction, Direction::RightToLeft);
+//!
+//! // hebrew is drawn right-to-left
+//! let glyphs = font.layout_bidi(text, Scale::uniform(20.0), point(0.0, 20.0));
+//! assert_eq!(glyphs[4].id(), font.glyph('\u{5d1}').id());
+//! assert_eq!(glyphs[5].id(), font.glyph('\u{5d0}').id());
+//! ```
+use crate::{point, Font, GlyphId, Point, PositionedGlyph, Scale};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::ops::Range;
+use unicode_bidi::BidiInfo;
+use unicode_script::UnicodeScript;
+
+pub use unicode_script::Script;
+
+/// Horizontal text direction.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    LeftToRight,
+    RightToLeft,
+}
+
+/// A run of text with uniform direction & script.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BidiRun {
+    /// Byte range of the run in the source text.
+    pub text_range: Range<usize>,
+    pub direction: Direction,
+    /// The script of the run. Chars of the `Common` & `Inherited` scripts,
+    /// eg spaces, digits & combining marks, join the run they are in, so only
+    /// runs without any other script are `Common`.
+    pub script: Script,
+}
+
+/// Splits `text` into runs of uniform direction & script, in visual order,
+/// ie the order they are drawn left to right.
+///
+/// Each paragraph, see UAX #9, is reordered separately & paragraphs are
+/// returned in text order. The paragraph direction is taken from its first
+/// strong char.
+pub fn runs(text: &str) -> Vec<BidiRun> {
+    let info = BidiInfo::new(text, None);
+    let mut runs = Vec::new();
+    for paragraph in &info.paragraphs {
+        let (levels, level_runs) = info.visual_runs(paragraph, paragraph.range.clone());
+        for level_run in level_runs {
+            let direction = if levels[level_run.start].is_rtl() {
+                Direction::RightToLeft
+            } else {
+                Direction::LeftToRight
+            };
+            let start = runs.len();
+            split_scripts(text, level_run, direction, &mut runs);
+            if direction == Direction::RightToLeft {
+                runs[start..].reverse();
+            }
+        }
+    }
+    runs
+}
+
+/// Splits a level run into runs of uniform script, in logical order.
+fn split_scripts(text: &str, range: Range<usize>, direction: Direction, runs: &mut Vec<BidiRun>) {
+    let mut start = range.start;
+    let mut script = Script::Common;
+    for (index, c) in text[range.clone()].char_indices() {
+        let c_script = c.script();
+        if matches!(
+            c_script,
+            Script::Common | Script::Inherited | Script::Unknown
+        ) {
+            continue;
+        }
+        if script == Script::Common {
+            script = c_script;
+        } else if c_script != script {
+            let index = range.start + index;
+            runs.push(BidiRun {
+                text_range: start..index,
+                direction,
+                script,
+            });
+            start = index;
+            script = c_script;
+        }
+    }
+    runs.push(BidiRun {
+        text_range: start..range.end,
+        direction,
+        script,
+    });
+}
+
+impl<'font> Font<'font> {
+    /// Lays out a single line of mixed direction text from `start`, returning
+    /// glyphs in visual order, left to right.
+    ///
+    /// Text is split into runs with `bidi::runs`. Right-to-left runs are
+    /// reversed & mapped with `Font::arabic_glyphs`, so Arabic letters take
+    /// their joining forms. Kerning is applied between neighbouring glyphs of
+    /// each run, looking pairs up in logical order. Mirrored chars, like
+    /// brackets, are not mirrored.
+    ///
+    /// Requires the `bidi` feature.
+    pub fn layout_bidi(
+        &self,
+        text: &str,
+        scale: Scale,
+        start: Point<f32>,
+    ) -> Vec<PositionedGlyph<'font>> {
+        // glyph ids in visual order, with the kerning before each as drawn
+        let mut kerned: Vec<(GlyphId, f32)> = Vec::with_capacity(text.len());
+        for run in runs(text) {
+            let run_text = &text[run.text_range];
+            match run.direction {
+                Direction::LeftToRight => {
+                    let ids: Vec<_> = run_text.chars().map(|c| self.glyph(c).id()).collect();
+                    kerned.extend(ids.iter().enumerate().map(|(index, &id)| {
+                        let kerning = match index.checked_sub(1) {
+                            Some(prev) => self.pair_kerning(scale, ids[prev], id),
+                            None => 0.0,
+                        };
+                        (id, kerning)
+                    }));
+                }
+                Direction::RightToLeft => {
+                    // pairs are kerned in logical order, the logically next
+                    // glyph is drawn before
+                    let ids = self.arabic_glyphs(run_text);
+                    let run_start = kerned.len();
+                    kerned.extend(ids.iter().enumerate().map(|(index, &id)| {
+                        let kerning = match ids.get(index + 1) {
+                            Some(&next) => self.pair_kerning(scale, id, next),
+                            None => 0.0,
+                        };
+                        (id, kerning)
+                    }));
+                    kerned[run_start..].reverse();
+                }
+            }
+        }
+
+        let mut caret = 0.0;
+        kerned
+            .into_iter()
+            .map(|(id, kerning)| {
+                caret += kerning;
+                let glyph = self
+                    .glyph(id)
+                    .scaled(scale)
+                    .positioned(point(start.x + caret, start.y));
+                caret += glyph.unpositioned().h_metrics().advance_width;
+                glyph
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{Font, GlyphId, KernTable, Outline};
+use alloc::collections::BTreeMap;
+
+/// Default `FontCaches::outline_capacity`, outline caching is off without
+/// `std` to keep memory use explicit.
+const DEFAULT_OUTLINE_CAPACITY: usize = if cfg!(feature = "std") { 256 } else { 0 };
+
+/// Memoised lookups for a single `Font`.
+///
+/// `Font` itself holds no mutable state, so it stays cheap to clone and
+/// `Sync`. Callers wanting to memoise repeated work instead keep a
+/// `FontCaches` alongside a font and pass it to the `*_cached` methods, e.g.
+/// `Font::pair_kerning_cached` or `PositionedGlyph::draw_cached`. This keeps
+/// ownership, and any locking needed to share it between threads, under user
+/// control.
+///
+/// A `FontCaches` is dedicated to one font at a time. Using it with another
+/// font clears it first.
+///
+/// # Example
+///
+/// ```
+/// # use rusttype::{Font, FontCaches, Scale};
+/// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+/// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+/// let mut caches = FontCaches::new();
+/// let kern = font.pair_kerning_cached(&mut caches, Scale::uniform(20.0), 'A', 'V');
+/// assert_eq!(kern, font.pair_kerning(Scale::uniform(20.0), 'A', 'V'));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FontCaches<'font> {
+    /// The font the caches contents relate to. Holding a clone keeps the
+    /// font data address, used for identity, from being reused.
+    font: Option<Font<'font>>,
+    /// Kerning lookups, built on first use.
+    pub(crate) kern_table: Option<KernTable<'font>>,
+    /// Unscaled glyph outlines, `None` for glyphs without one, with the
+    /// `outline_tick` of their last use.
+    outlines: BTreeMap<GlyphId, (Option<Outline>, u64)>,
+    /// The glyphs of `outlines` by last use tick, oldest first.
+    outline_lru: BTreeMap<u64, GlyphId>,
+    outline_tick: u64,
+    outline_capacity: usize,
+}
+
+impl Default for FontCaches<'_> {
+    fn default() -> Self {
+        Self {
+            font: None,
+            kern_table: None,
+            outlines: BTreeMap::new(),
+            outline_lru: BTreeMap::new(),
+            outline_tick: 0,
+            outline_capacity: DEFAULT_OUTLINE_CAPACITY,
+        }
+    }
+}
+
+impl<'font> FontCaches<'font> {
+    /// Returns new empty caches.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns new empty caches keeping up to `capacity` glyph outlines, see
+    /// `set_outline_capacity`.
+    pub fn with_outline_capacity(capacity: usize) -> Self {
+        Self {
+            outline_capacity: capacity,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the number of parsed glyph outlines kept for the `*_cached`
+    /// outline & draw methods, eg `PositionedGlyph::draw_cached`, evicting
+    /// the least recently used first. `0` disables outline caching.
+    ///
+    /// Defaults to 256 with the `std` feature, otherwise 0.
+    pub fn set_outline_capacity(&mut self, capacity: usize) {
+        self.outline_capacity = capacity;
+        while self.outlines.len() > capacity {
+            self.evict_outline();
+        }
+    }
+
+    /// Returns the maximum number of glyph outlines kept, see
+    /// `set_outline_capacity`.
+    pub fn outline_capacity(&self) -> usize {
+        self.outline_capacity
+    }
+
+    /// Removes all memoised data.
+    pub fn clear(&mut self) {
+        self.font = None;
+        self.kern_table = None;
+        self.outlines.clear();
+        self.outline_lru.clear();
+    }
+
+    /// Returns these caches ready for use with `font`, clearing them if they
+    /// were last used with a different font.
+    pub(crate) fn for_font(&mut self, font: &Font<'font>) -> &mut Self {
+        let same_font = matches!(&self.font, Some(f) if f == font);
+        if !same_font {
+            self.clear();
+            self.font = Some(font.clone());
+        }
+        self
+    }
+
+    /// Returns the unscaled outline of glyph `id` of the font, parsing &
+    /// caching it if needed, `None` if the glyph has no outline. Requires a
+    /// non-zero `outline_capacity`.
+    pub(crate) fn outline(&mut self, font: &Font<'font>, id: GlyphId) -> Option<&Outline> {
+        debug_assert!(self.outline_capacity > 0);
+        let caches = self.for_font(font);
+        caches.outline_tick += 1;
+        let tick = caches.outline_tick;
+        if !caches.outlines.contains_key(&id) {
+            if caches.outlines.len() >= caches.outline_capacity {
+                caches.evict_outline();
+            }
+            let mut outline = Outline::new();
+            let outline = font
+                .inner()
+                .outline_glyph(id.into(), &mut outline)
+                .map(|_| outline);
+            caches.outlines.insert(id, (outline, tick));
+        }
+        let (outline, used) = caches.outlines.get_mut(&id).unwrap();
+        caches.outline_lru.remove(used);
+        caches.outline_lru.insert(tick, id);
+        *used = tick;
+        outline.as_ref()
+    }
+
+    /// Removes the least recently used outline.
+    fn evict_outline(&mut self) {
+        if let Some((_, id)) = self.outline_lru.pop_first() {
+            self.outlines.remove(&id);
+        }
+    }
+}
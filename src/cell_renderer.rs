@@ -0,0 +1,264 @@
+use crate::{char_class, point, Font, GlyphId, IntoGlyphId, Scale};
+use alloc::collections::BTreeMap;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::ops;
+
+#[cfg(all(feature = "libm-math", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+
+/// Style flags of a `CellRenderer` cell. Combine with `|`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CellStyle(u8);
+
+impl CellStyle {
+    /// No styling.
+    pub const NONE: Self = Self(0);
+    /// Draws a line at the font's underline position.
+    pub const UNDERLINE: Self = Self(1);
+    /// Draws a line at the font's strikeout position.
+    pub const STRIKETHROUGH: Self = Self(1 << 1);
+
+    /// Returns `true` if all flags of `other` are set in this style.
+    #[inline]
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl ops::BitOr for CellStyle {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Renders fixed size character cells for monospace, eg terminal, workloads.
+///
+/// Each distinct (glyph id, style) is rasterized once into a cell sized
+/// coverage bitmap & cached. Glyphs are drawn at the integer cell origin, so
+/// there are no subpixel variants, and kerning is not applied. Parts of glyphs
+/// overhanging the cell are clipped. Cell size is fixed per renderer, so cached
+/// cells are implicitly keyed by it too.
+///
+/// `draw_text_row` lays out chars by `char_class::cell_width`, drawing wide
+/// chars across two cells.
+///
+/// # Example
+///
+/// ```
+/// # use rusttype::{CellRenderer, CellStyle, Font, Scale};
+/// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+/// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+/// let mut renderer = CellRenderer::new(font, Scale::uniform(16.0));
+/// let (cell_width, cell_height) = renderer.cell_size();
+///
+/// // an 80x2 cell coverage buffer
+/// let stride = 80 * cell_width as usize;
+/// let mut buffer = vec![0; stride * 2 * cell_height as usize];
+///
+/// let row = "$ cargo test".chars().map(|c| (c, CellStyle::NONE));
+/// renderer.draw_row(row, &mut buffer, stride, 0);
+///
+/// let row = "ok".chars().map(|c| (c, CellStyle::UNDERLINE));
+/// renderer.draw_row(row, &mut buffer, stride, 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CellRenderer<'font> {
+    font: Font<'font>,
+    scale: Scale,
+    width: u32,
+    height: u32,
+    baseline: f32,
+    /// Cached cell bitmaps by glyph, style & width in cells.
+    cells: BTreeMap<(GlyphId, CellStyle, usize), Vec<u8>>,
+}
+
+impl<'font> CellRenderer<'font> {
+    /// Creates a renderer for `font` at `scale`. The cell width is the
+    /// advance width of `'M'`, the cell height is the font's ascent to
+    /// descent.
+    pub fn new(font: Font<'font>, scale: Scale) -> Self {
+        let width = font.glyph('M').scaled(scale).h_metrics().advance_width;
+        let v_metrics = font.v_metrics(scale);
+        let height = v_metrics.ascent - v_metrics.descent;
+        Self::with_cell_size(font, scale, width.ceil() as u32, height.ceil() as u32)
+    }
+
+    /// Creates a renderer for `font` at `scale` with the given cell size. The
+    /// baseline is positioned at the font's ascent.
+    pub fn with_cell_size(font: Font<'font>, scale: Scale, width: u32, height: u32) -> Self {
+        let baseline = font.v_metrics(scale).ascent.round();
+        Self {
+            font,
+            scale,
+            width,
+            height,
+            baseline,
+            cells: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the font.
+    #[inline]
+    pub fn font(&self) -> &Font<'font> {
+        &self.font
+    }
+
+    /// Returns the scale glyphs are rendered at.
+    #[inline]
+    pub fn scale(&self) -> Scale {
+        self.scale
+    }
+
+    /// Returns the cell `(width, height)` in pixels.
+    #[inline]
+    pub fn cell_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the number of cached cells.
+    #[inline]
+    pub fn cached_len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Removes all cached cells.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    /// Returns the coverage bitmap of a cell, row major with a stride of the
+    /// cell width, rendering & caching it if necessary.
+    pub fn cell<C: IntoGlyphId>(&mut self, id: C, style: CellStyle) -> &[u8] {
+        let id = id.into_glyph_id(&self.font);
+        self.cell_of_width(id, style, 1)
+    }
+
+    /// Returns the bitmap of a glyph drawn across `columns` cells, row major
+    /// with a stride of `columns` cell widths.
+    fn cell_of_width(&mut self, id: GlyphId, style: CellStyle, columns: usize) -> &[u8] {
+        let key = (id, style, columns);
+        if !self.cells.contains_key(&key) {
+            let cell = self.render_cell(id, style, columns);
+            self.cells.insert(key, cell);
+        }
+        &self.cells[&key]
+    }
+
+    /// Copies a row of cells into a coverage `buffer`, `stride` pixels wide,
+    /// overwriting its contents. The cells start at the left edge of the
+    /// buffer at text row `row`, ie `row * cell_height` pixels down.
+    ///
+    /// Cells outside the buffer are clipped.
+    pub fn draw_row<C, I>(&mut self, cells: I, buffer: &mut [u8], stride: usize, row: usize)
+    where
+        C: IntoGlyphId,
+        I: IntoIterator<Item = (C, CellStyle)>,
+    {
+        let cells = cells.into_iter().map(|(id, style)| (id, style, 1));
+        self.draw_cells(cells, buffer, stride, row);
+    }
+
+    /// Copies a row of chars into a coverage `buffer`, as `draw_row`, taking
+    /// up `char_class::cell_width` cells per char.
+    ///
+    /// Wide chars, eg CJK ideographs, are drawn across two cells. Zero width
+    /// chars, eg combining marks, are skipped as cells can't be composited.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{CellRenderer, CellStyle, Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let mut renderer = CellRenderer::new(font, Scale::uniform(16.0));
+    /// let (cell_width, cell_height) = renderer.cell_size();
+    /// let stride = 80 * cell_width as usize;
+    /// let mut buffer = vec![0; stride * cell_height as usize];
+    ///
+    /// // "世" takes columns 2 & 3, "!" column 4
+    /// let row = "a 世!".chars().map(|c| (c, CellStyle::NONE));
+    /// renderer.draw_text_row(row, &mut buffer, stride, 0);
+    /// ```
+    pub fn draw_text_row<I>(&mut self, chars: I, buffer: &mut [u8], stride: usize, row: usize)
+    where
+        I: IntoIterator<Item = (char, CellStyle)>,
+    {
+        let cells = chars
+            .into_iter()
+            .map(|(c, style)| (c, style, char_class::cell_width(c)))
+            .filter(|&(_, _, columns)| columns > 0);
+        self.draw_cells(cells, buffer, stride, row);
+    }
+
+    fn draw_cells<C, I>(&mut self, cells: I, buffer: &mut [u8], stride: usize, row: usize)
+    where
+        C: IntoGlyphId,
+        I: Iterator<Item = (C, CellStyle, usize)>,
+    {
+        let (width, height) = (self.width as usize, self.height as usize);
+        if stride == 0 || width == 0 {
+            return;
+        }
+        let buffer_height = buffer.len() / stride;
+        let y_start = row.saturating_mul(height);
+        let rows = height.min(buffer_height.saturating_sub(y_start));
+
+        let mut x = 0;
+        for (id, style, columns) in cells {
+            if x >= stride || rows == 0 {
+                break;
+            }
+            let cell_width = columns * width;
+            let copy_width = cell_width.min(stride - x);
+            let id = id.into_glyph_id(&self.font);
+            let cell = self.cell_of_width(id, style, columns);
+            for y in 0..rows {
+                let start = (y_start + y) * stride + x;
+                buffer[start..start + copy_width]
+                    .copy_from_slice(&cell[y * cell_width..y * cell_width + copy_width]);
+            }
+            x += cell_width;
+        }
+    }
+
+    fn render_cell(&self, id: GlyphId, style: CellStyle, columns: usize) -> Vec<u8> {
+        let (width, height) = (columns * self.width as usize, self.height as usize);
+        let mut cell = alloc::vec![0; width * height];
+
+        let glyph = self
+            .font
+            .glyph(id)
+            .scaled(self.scale)
+            .positioned(point(0.0, self.baseline));
+        if let Some(bb) = glyph.pixel_bounding_box() {
+            glyph.draw(|x, y, v| {
+                let x = bb.min.x + x as i32;
+                let y = bb.min.y + y as i32;
+                if x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height {
+                    cell[y as usize * width + x as usize] = (v * 255.0).round() as u8;
+                }
+            });
+        }
+
+        let font = self.font.inner();
+        let lines = [
+            (CellStyle::UNDERLINE, font.underline_metrics()),
+            (CellStyle::STRIKETHROUGH, font.strikeout_metrics()),
+        ];
+        let scale_y = self.font.scale_for_pixel_height(self.scale.y);
+        for (flag, metrics) in lines {
+            if let (true, Some(metrics)) = (style.contains(flag), metrics) {
+                let thickness = (metrics.thickness as f32 * scale_y).round().max(1.0) as usize;
+                let top = self.baseline - (metrics.position as f32 * scale_y).round();
+                let top = (top.max(0.0) as usize).min(height);
+                for y in top..(top + thickness).min(height) {
+                    cell[y * width..(y + 1) * width].fill(255);
+                }
+            }
+        }
+        cell
+    }
+}
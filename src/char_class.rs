@@ -0,0 +1,139 @@
+//! Character classification shared by the crate's text handling, exposed so
+//! user layout code can agree with it on edge case characters.
+//!
+//! Classifications are approximations using fixed code point ranges, covering
+//! commonly used scripts, rather than the full Unicode character database.
+//!
+//! # Example
+//!
+//! ```
+//! use rusttype::char_class::*;
+//!
+//! assert!(is_breakable(' '));
+//! assert!(!is_breakable('\u{a0}')); // no-break space
+//! assert!(is_word_separator('\u{a0}'));
+//!
+//! assert!(is_zero_width('\u{301}')); // combining acute accent
+//! assert!(is_default_ignorable('\u{200d}')); // zero width joiner
+//!
+//! assert_eq!(cell_width('a'), 1);
+//! assert_eq!(cell_width('世'), 2);
+//! assert_eq!(cell_width('\u{301}'), 0);
+//! ```
+
+/// Returns `true` for chars that are not expected to render & should be
+/// skipped if a font has no glyph for them. These are control chars & the
+/// Unicode `Default_Ignorable_Code_Point` format chars, like zero width
+/// joiners & variation selectors.
+///
+/// `Font::coverage` ignores these chars.
+pub fn is_default_ignorable(c: char) -> bool {
+    c.is_control()
+        || matches!(
+            c as u32,
+            0x00AD
+                | 0x034F
+                | 0x061C
+                | 0x115F..=0x1160
+                | 0x17B4..=0x17B5
+                | 0x180B..=0x180F
+                | 0x200B..=0x200F
+                | 0x202A..=0x202E
+                | 0x2060..=0x206F
+                | 0x3164
+                | 0xFE00..=0xFE0F
+                | 0xFEFF
+                | 0xFFA0
+                | 0xFFF0..=0xFFF8
+                | 0x1BCA0..=0x1BCA3
+                | 0x1D173..=0x1D17A
+                | 0xE0000..=0xE0FFF
+        )
+}
+
+/// Returns `true` for chars that take up no horizontal space of their own,
+/// ie default ignorable chars & combining marks.
+pub fn is_zero_width(c: char) -> bool {
+    is_default_ignorable(c)
+        || matches!(
+            c as u32,
+            0x0300..=0x036F
+                | 0x0483..=0x0489
+                | 0x0591..=0x05BD
+                | 0x05BF
+                | 0x05C1..=0x05C2
+                | 0x05C4..=0x05C5
+                | 0x05C7
+                | 0x0610..=0x061A
+                | 0x064B..=0x065F
+                | 0x0670
+                | 0x06D6..=0x06DC
+                | 0x06DF..=0x06E4
+                | 0x06E7..=0x06E8
+                | 0x06EA..=0x06ED
+                | 0x0E31
+                | 0x0E34..=0x0E3A
+                | 0x0E47..=0x0E4E
+                | 0x1AB0..=0x1AFF
+                | 0x1DC0..=0x1DFF
+                | 0x20D0..=0x20FF
+                | 0x302A..=0x302D
+                | 0x3099..=0x309A
+                | 0xFE20..=0xFE2F
+        )
+}
+
+/// Returns `true` for whitespace chars after which a line may be broken.
+///
+/// No-break spaces, like U+00A0, are whitespace but not breakable. The zero
+/// width space, U+200B, is breakable.
+pub fn is_breakable(c: char) -> bool {
+    match c {
+        '\u{a0}' | '\u{2007}' | '\u{202f}' => false,
+        '\u{200b}' => true,
+        c => c.is_whitespace(),
+    }
+}
+
+/// Returns `true` for spaces between words, which
+/// `LayoutOptions::word_spacing` is added after. These are whitespace chars,
+/// including no-break spaces, other than control chars like `'\n'`.
+pub fn is_word_separator(c: char) -> bool {
+    c.is_whitespace() && !is_zero_width(c)
+}
+
+/// Returns `true` for East Asian Wide & Fullwidth chars, which occupy two
+/// cells of a monospace grid.
+pub fn is_wide(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1100..=0x115F
+            | 0x2E80..=0x303E
+            | 0x3041..=0x33FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xA000..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x1F300..=0x1F64F
+            | 0x1F900..=0x1F9FF
+            | 0x20000..=0x2FFFD
+            | 0x30000..=0x3FFFD
+    )
+}
+
+/// Returns the number of monospace grid cells, eg `CellRenderer` cells, a
+/// char occupies: `0` for zero width chars, `2` for wide chars, otherwise
+/// `1`.
+pub fn cell_width(c: char) -> usize {
+    if is_zero_width(c) {
+        0
+    } else if is_wide(c) {
+        2
+    } else {
+        1
+    }
+}
@@ -0,0 +1,162 @@
+use crate::Font;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use owned_ttf_parser::{cmap::Format, PlatformId, Tag};
+
+const CMAP: Tag = Tag::from_bytes(b"cmap");
+
+/// A subtable of the font's `cmap` table, mapping chars to glyphs, see
+/// `Font::cmap_subtables`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CmapSubtable {
+    /// Index of the subtable in the `cmap` table.
+    pub index: u16,
+    /// Platform id, eg `0` Unicode, `1` Macintosh, `3` Windows.
+    pub platform_id: u16,
+    /// Platform specific encoding id, eg Windows `0` Symbol, `1` Unicode BMP.
+    pub encoding_id: u16,
+    /// Subtable format number, eg `4` or `12`.
+    pub format: u16,
+    /// Whether the subtable has a Unicode encoding, so may be used by
+    /// `Font::glyph`. The first Unicode subtable mapping a char is used.
+    pub is_unicode: bool,
+}
+
+impl Font<'_> {
+    /// Returns all subtables of the font's `cmap` table, in table order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::Font;
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// for subtable in font.cmap_subtables() {
+    ///     println!(
+    ///         "platform {} encoding {} format {}",
+    ///         subtable.platform_id, subtable.encoding_id, subtable.format
+    ///     );
+    /// }
+    /// # assert!(font.cmap_subtables().iter().any(|s| s.is_unicode));
+    /// ```
+    pub fn cmap_subtables(&self) -> Vec<CmapSubtable> {
+        let Some(cmap) = self.inner().tables().cmap else {
+            return Vec::new();
+        };
+        cmap.subtables
+            .into_iter()
+            .enumerate()
+            .map(|(index, subtable)| CmapSubtable {
+                index: index as u16,
+                platform_id: platform_id(subtable.platform_id),
+                encoding_id: subtable.encoding_id,
+                format: match subtable.format {
+                    Format::ByteEncodingTable(_) => 0,
+                    Format::HighByteMappingThroughTable(_) => 2,
+                    Format::SegmentMappingToDeltaValues(_) => 4,
+                    Format::TrimmedTableMapping(_) => 6,
+                    Format::MixedCoverage => 8,
+                    Format::TrimmedArray(_) => 10,
+                    Format::SegmentedCoverage(_) => 12,
+                    Format::ManyToOneRangeMappings(_) => 13,
+                    Format::UnicodeVariationSequences(_) => 14,
+                },
+                is_unicode: subtable.is_unicode(),
+            })
+            .collect()
+    }
+
+    /// Returns a copy of this font where `Font::glyph(char)`, & so layout,
+    /// maps chars using only the `cmap` subtable at `index`, see
+    /// `cmap_subtables`.
+    ///
+    /// Legacy fonts may have useful mappings only in non-Unicode subtables,
+    /// which are otherwise ignored, drawing every char as `.notdef`. Chars are
+    /// looked up by their code point as-is, so for non-Unicode subtables use
+    /// the code of the subtable's encoding, eg Mac Roman byte values. Windows
+    /// Symbol fonts usually map codes at `U+F020..=U+F0FF`.
+    ///
+    /// The font data is copied with its `cmap` table rewritten. Returns `None`
+    /// if there is no subtable at `index`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::Font;
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// // prefer the Macintosh Roman mapping
+    /// let mac_roman = font
+    ///     .cmap_subtables()
+    ///     .into_iter()
+    ///     .find(|s| s.platform_id == 1 && s.encoding_id == 0)
+    ///     .unwrap();
+    /// let font = font.with_cmap_subtable(mac_roman.index).unwrap();
+    /// ```
+    pub fn with_cmap_subtable(&self, index: u16) -> Option<Font<'static>> {
+        let raw = self.inner().raw_face();
+        let (record_index, record) = raw
+            .table_records
+            .into_iter()
+            .enumerate()
+            .find(|(_, r)| r.tag == CMAP)?;
+        let cmap_start = record.offset as usize;
+        let cmap = raw
+            .data
+            .get(cmap_start..cmap_start.checked_add(record.length as usize)?)?;
+
+        // encoding records follow the 4 byte header, 8 bytes each
+        let read_u16 =
+            |data: &[u8], at: usize| Some(u16::from_be_bytes([*data.get(at)?, *data.get(at + 1)?]));
+        let read_u32 = |data: &[u8], at: usize| {
+            Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+        };
+        if index >= read_u16(cmap, 2)? {
+            return None;
+        }
+        // a cmap with the single subtable, declared Unicode so it is used,
+        // followed by the original cmap data holding the subtable
+        const HEADER_LEN: u32 = 12;
+        let encoding_record = 4 + 8 * index as usize;
+        let subtable_offset = HEADER_LEN.checked_add(read_u32(cmap, encoding_record + 4)?)?;
+
+        let mut new_cmap = Vec::with_capacity(HEADER_LEN as usize + cmap.len());
+        new_cmap.extend_from_slice(&0_u16.to_be_bytes()); // version
+        new_cmap.extend_from_slice(&1_u16.to_be_bytes()); // numTables
+        new_cmap.extend_from_slice(&0_u16.to_be_bytes()); // platform Unicode
+        new_cmap.extend_from_slice(&4_u16.to_be_bytes()); // full repertoire
+        new_cmap.extend_from_slice(&subtable_offset.to_be_bytes());
+        new_cmap.extend_from_slice(cmap);
+
+        // append the new cmap, 4 byte aligned, & point the table record at it
+        let mut data = raw.data.to_vec();
+        data.resize(data.len().next_multiple_of(4), 0);
+        let new_offset = u32::try_from(data.len()).ok()?;
+        let new_length = u32::try_from(new_cmap.len()).ok()?;
+        data.extend_from_slice(&new_cmap);
+
+        let face_index = self.collection_index();
+        let directory = if raw.data.starts_with(b"ttcf") {
+            read_u32(raw.data, 12 + 4 * face_index as usize)? as usize
+        } else {
+            0
+        };
+        // table records follow the 12 byte table directory header, 16 bytes
+        // each: tag, checksum, offset, length
+        let record_at = directory + 12 + 16 * record_index;
+        data.get_mut(record_at + 8..record_at + 16)?
+            .copy_from_slice(&[new_offset.to_be_bytes(), new_length.to_be_bytes()].concat());
+
+        Font::try_from_vec_and_index(data, face_index)
+    }
+}
+
+fn platform_id(id: PlatformId) -> u16 {
+    match id {
+        PlatformId::Unicode => 0,
+        PlatformId::Macintosh => 1,
+        PlatformId::Iso => 2,
+        PlatformId::Windows => 3,
+        PlatformId::Custom => 4,
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{char_class, Font};
+use alloc::collections::BTreeMap;
+
+/// Reports the code points of a text that a font, or font fallback chain,
+/// cannot render and would instead draw as `.notdef`, often a box ("tofu").
+///
+/// Chars that are not expected to render, like `'\n'` or zero width joiners,
+/// are ignored, see `char_class::is_default_ignorable`.
+///
+/// # Example
+///
+/// ```
+/// # use rusttype::{CoverageReport, Font};
+/// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+/// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+/// let report = font.coverage("Hello 世界 世\n");
+///
+/// assert!(!report.is_complete());
+/// assert_eq!(report.checked(), 10);
+/// assert_eq!(report.missing().get(&'世'), Some(&2));
+/// assert_eq!(report.missing().get(&'界'), Some(&1));
+/// assert_eq!(report.missing_count(), 3);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageReport {
+    checked: usize,
+    missing: BTreeMap<char, usize>,
+}
+
+impl CoverageReport {
+    /// Checks `text` against a font fallback chain. A code point is covered if
+    /// any of the `fonts` has a glyph for it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{CoverageReport, Font};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// # let cjk_data: &[u8] = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+    /// # let cjk_font: Font<'static> = Font::try_from_bytes(cjk_data).unwrap();
+    /// let report = CoverageReport::new(&[font, cjk_font], "Hello 世界");
+    /// assert!(report.is_complete());
+    /// ```
+    pub fn new(fonts: &[Font<'_>], text: &str) -> Self {
+        let mut report = Self::default();
+        report.add(fonts, text);
+        report
+    }
+
+    /// Checks more text against a font fallback chain, accumulating results
+    /// into this report. Useful for checking a whole corpus a piece at a time.
+    pub fn add(&mut self, fonts: &[Font<'_>], text: &str) {
+        for c in text
+            .chars()
+            .filter(|&c| !char_class::is_default_ignorable(c))
+        {
+            self.checked += 1;
+            if !fonts.iter().any(|font| font.has_glyph(c)) {
+                *self.missing.entry(c).or_insert(0) += 1;
+            }
+        }
+    }
+
+    /// Returns missing code points with the number of times each occurred.
+    #[inline]
+    pub fn missing(&self) -> &BTreeMap<char, usize> {
+        &self.missing
+    }
+
+    /// Returns the number of occurrences of missing code points.
+    #[inline]
+    pub fn missing_count(&self) -> usize {
+        self.missing.values().sum()
+    }
+
+    /// Returns the number of code points checked.
+    #[inline]
+    pub fn checked(&self) -> usize {
+        self.checked
+    }
+
+    /// Returns `true` if all checked code points are covered.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+impl Font<'_> {
+    /// Returns `true` if this font has a glyph for the code point, ie it will
+    /// not render as `.notdef`.
+    #[inline]
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.inner().glyph_index(c).is_some()
+    }
+
+    /// Reports the code points of `text` this font will render as `.notdef`.
+    /// See `CoverageReport`.
+    pub fn coverage(&self, text: &str) -> CoverageReport {
+        CoverageReport::new(core::slice::from_ref(self), text)
+    }
+}
@@ -0,0 +1,148 @@
+use crate::PositionedGlyph;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+#[cfg(all(feature = "libm-math", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+
+/// An 8-bit coverage raster, eg of rendered glyphs or strings, that can be
+/// downsampled into a pyramid (mip chain) with box filtering.
+///
+/// Useful for zoomed out text, like code editor minimaps & document
+/// previews, where sampling a full size raster with nearest neighbour
+/// filtering loses thin strokes.
+///
+/// # Example
+///
+/// ```
+/// # use rusttype::{point, CoverageBuffer, Font, Scale};
+/// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+/// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+/// let mut buffer = CoverageBuffer::new(200, 24);
+/// for glyph in font.layout("fn main() {}", Scale::uniform(20.0), point(0.0, 18.0)) {
+///     buffer.draw(&glyph);
+/// }
+///
+/// let pyramid = buffer.pyramid();
+/// let sizes: Vec<_> = pyramid.iter().map(|b| (b.width(), b.height())).collect();
+/// assert_eq!(sizes[..3], [(200, 24), (100, 12), (50, 6)]);
+/// assert_eq!(sizes.last(), Some(&(1, 1)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CoverageBuffer {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl CoverageBuffer {
+    /// Creates a zeroed buffer.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            data: alloc::vec![0; width as usize * height as usize],
+        }
+    }
+
+    /// Creates a buffer from row major coverage `data`.
+    ///
+    /// Returns `None` if `data.len()` is not `width * height`.
+    pub fn from_raw(width: u32, height: u32, data: Vec<u8>) -> Option<Self> {
+        (data.len() == width as usize * height as usize).then_some(Self {
+            width,
+            height,
+            data,
+        })
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the row major coverage data.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Consumes the buffer, returning the row major coverage data.
+    #[inline]
+    pub fn into_raw(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Returns the coverage at `(x, y)`, `None` if out of bounds.
+    #[inline]
+    pub fn get(&self, x: u32, y: u32) -> Option<u8> {
+        if x < self.width && y < self.height {
+            Some(self.data[y as usize * self.width as usize + x as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Draws a glyph into the buffer, keeping the max coverage where glyphs
+    /// overlap. Parts outside the buffer are clipped.
+    pub fn draw(&mut self, glyph: &PositionedGlyph<'_>) {
+        let Some(bb) = glyph.pixel_bounding_box() else {
+            return;
+        };
+        let (width, height) = (self.width, self.height);
+        glyph.draw(|x, y, v| {
+            let x = bb.min.x.saturating_add(x as i32);
+            let y = bb.min.y.saturating_add(y as i32);
+            if x >= 0 && y >= 0 && (x as u32) < width && (y as u32) < height {
+                let pixel = &mut self.data[y as usize * width as usize + x as usize];
+                *pixel = (*pixel).max((v * 255.0).round() as u8);
+            }
+        });
+    }
+
+    /// Returns the next level of a pyramid, half the size rounded up, each
+    /// pixel the average of the 2x2 block of pixels it covers. At odd edges
+    /// blocks are partial & only existing pixels are averaged.
+    pub fn downsample(&self) -> Self {
+        let width = self.width.div_ceil(2);
+        let height = self.height.div_ceil(2);
+        let mut data = Vec::with_capacity(width as usize * height as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let (mut sum, mut count) = (0_u32, 0_u32);
+                for (sx, sy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    if let Some(v) = self.get(2 * x + sx, 2 * y + sy) {
+                        sum += u32::from(v);
+                        count += 1;
+                    }
+                }
+                data.push(((sum + count / 2) / count.max(1)) as u8);
+            }
+        }
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// Returns this buffer followed by successive `downsample` levels down to
+    /// 1x1. An empty buffer produces only itself.
+    pub fn pyramid(self) -> Vec<Self> {
+        let mut levels = alloc::vec![self];
+        loop {
+            let last = &levels[levels.len() - 1];
+            if last.width <= 1 && last.height <= 1 || last.data.is_empty() {
+                break;
+            }
+            let next = last.downsample();
+            levels.push(next);
+        }
+        levels
+    }
+}
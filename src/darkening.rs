@@ -0,0 +1,249 @@
+use crate::outliner::OutlineRasterizer;
+use crate::{point, vector, OutlineBuilder, Point, PositionedGlyph, Rect, Scale};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+#[cfg(all(feature = "libm-math", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+
+/// Returns a default stem darkening amount, in pixels, for glyphs at `scale`
+/// for use with `PositionedGlyph::draw_darkened`.
+///
+/// Darkening is strongest, `0.4` pixels, at 8 pixels & below, fading to none
+/// at 14 pixels & above where stems are thick enough to render solidly.
+pub fn stem_darkening(scale: Scale) -> f32 {
+    const FULL: f32 = 8.0;
+    const NONE: f32 = 14.0;
+    let t = ((NONE - scale.y) / (NONE - FULL)).clamp(0.0, 1.0);
+    0.4 * t
+}
+
+/// Limit on the distance points move at sharp corners, relative to the
+/// distance edges move.
+const MITER_LIMIT: f32 = 2.0;
+
+#[derive(Copy, Clone)]
+enum Segment {
+    Line,
+    Quad,
+    Curve,
+}
+
+/// Outline contours recorded as their points, including control points, to
+/// be shifted & replayed.
+#[derive(Default)]
+struct Contours {
+    points: Vec<Point<f32>>,
+    /// Start index of each contour in `points` & its segments
+    contours: Vec<(usize, Vec<Segment>)>,
+}
+
+impl Contours {
+    /// Moves every point outward from the ink by `distance`, along the
+    /// bisector of its adjacent edges, so edges move by `distance`.
+    fn embolden(&mut self, distance: f32) {
+        // the sign of the total area gives the side ink is on, the same side
+        // for outer contours & holes as they wind in opposite directions
+        let area: f32 = self
+            .contour_ranges()
+            .map(|(start, end)| {
+                let points = &self.points[start..end];
+                (0..points.len())
+                    .map(|i| {
+                        let (a, b) = (points[i], points[(i + 1) % points.len()]);
+                        a.x * b.y - b.x * a.y
+                    })
+                    .sum::<f32>()
+            })
+            .sum();
+        let side = if area > 0.0 { 1.0 } else { -1.0 };
+        let outward = |d: crate::Vector<f32>| vector(d.y, -d.x) * side;
+
+        let mut shifted = self.points.clone();
+        for (start, end) in self.contour_ranges() {
+            let points = &self.points[start..end];
+            let len = points.len();
+            for (i, &p) in points.iter().enumerate() {
+                // nearest distinct neighbours
+                let prev = (1..len)
+                    .map(|n| points[(i + len - n) % len])
+                    .find(|&q| q != p);
+                let next = (1..len).map(|n| points[(i + n) % len]).find(|&q| q != p);
+                let (Some(prev), Some(next)) = (prev, next) else {
+                    continue;
+                };
+                let n_in = outward(normalize(p - prev));
+                let n_out = outward(normalize(next - p));
+                let cos = 1.0 + n_in.x * n_out.x + n_in.y * n_out.y;
+                if cos < 0.0625 {
+                    // edges nearly reverse, leave spikes alone
+                    continue;
+                }
+                let mut miter = (n_in + n_out) * (1.0 / cos);
+                let length = (miter.x * miter.x + miter.y * miter.y).sqrt();
+                if length > MITER_LIMIT {
+                    miter = miter * (MITER_LIMIT / length);
+                }
+                shifted[start + i] = p + miter * distance;
+            }
+        }
+        self.points = shifted;
+    }
+
+    fn contour_ranges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.contours
+            .iter()
+            .enumerate()
+            .map(move |(i, (start, _))| {
+                let end = self
+                    .contours
+                    .get(i + 1)
+                    .map_or(self.points.len(), |(next, _)| *next);
+                (*start, end)
+            })
+    }
+
+    fn bounding_box(&self) -> Option<Rect<f32>> {
+        let first = *self.points.first()?;
+        Some(self.points.iter().fold(
+            Rect {
+                min: first,
+                max: first,
+            },
+            |bb, p| Rect {
+                min: point(bb.min.x.min(p.x), bb.min.y.min(p.y)),
+                max: point(bb.max.x.max(p.x), bb.max.y.max(p.y)),
+            },
+        ))
+    }
+
+    fn replay(&self, offset: crate::Vector<f32>, builder: &mut impl OutlineBuilder) {
+        for ((start, segments), (_, end)) in self.contours.iter().zip(self.contour_ranges()) {
+            let mut points = self.points[*start..end].iter().map(|&p| p - offset);
+            let Some(first) = points.next() else {
+                continue;
+            };
+            builder.move_to(first.x, first.y);
+            for segment in segments {
+                match segment {
+                    Segment::Line => {
+                        let p = points.next().unwrap();
+                        builder.line_to(p.x, p.y);
+                    }
+                    Segment::Quad => {
+                        let (c, p) = (points.next().unwrap(), points.next().unwrap());
+                        builder.quad_to(c.x, c.y, p.x, p.y);
+                    }
+                    Segment::Curve => {
+                        let c1 = points.next().unwrap();
+                        let c2 = points.next().unwrap();
+                        let p = points.next().unwrap();
+                        builder.curve_to(c1.x, c1.y, c2.x, c2.y, p.x, p.y);
+                    }
+                }
+            }
+            builder.close();
+        }
+    }
+
+    fn push(&mut self, segment: Segment, points: &[Point<f32>]) {
+        if let Some((_, segments)) = self.contours.last_mut() {
+            segments.push(segment);
+            self.points.extend_from_slice(points);
+        }
+    }
+}
+
+impl OutlineBuilder for Contours {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.contours.push((self.points.len(), Vec::new()));
+        self.points.push(point(x, y));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.push(Segment::Line, &[point(x, y)]);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.push(Segment::Quad, &[point(x1, y1), point(x, y)]);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.push(Segment::Curve, &[point(x1, y1), point(x2, y2), point(x, y)]);
+    }
+
+    fn close(&mut self) {}
+}
+
+#[inline]
+fn normalize(v: crate::Vector<f32>) -> crate::Vector<f32> {
+    let length = (v.x * v.x + v.y * v.y).sqrt();
+    v * (1.0 / length)
+}
+
+impl PositionedGlyph<'_> {
+    /// Returns the outline emboldened by `darkening / 2` on each side,
+    /// positioned in pixel space.
+    fn darkened_contours(&self, darkening: f32) -> Option<Contours> {
+        let mut contours = Contours::default();
+        if !self.unpositioned().build_outline(&mut contours) {
+            return None;
+        }
+        let position = self.position() - point(0.0, 0.0);
+        for p in &mut contours.points {
+            *p = *p + position;
+        }
+        contours.embolden(darkening / 2.0);
+        Some(contours)
+    }
+
+    /// The pixel bounding box of this glyph drawn with `draw_darkened`,
+    /// which may be larger than `pixel_bounding_box`.
+    pub fn darkened_bounding_box(&self, darkening: f32) -> Option<Rect<i32>> {
+        let bb = self.darkened_contours(darkening)?.bounding_box()?;
+        Some(Rect {
+            min: point(bb.min.x.floor() as i32, bb.min.y.floor() as i32),
+            max: point(bb.max.x.ceil() as i32, bb.max.y.ceil() as i32),
+        })
+    }
+
+    /// Rasterises this glyph with stem darkening, expanding the outline by
+    /// `darkening` pixels in total stem width before rasterising, like
+    /// FreeType's stem darkening. Small text rendered without hinting can
+    /// otherwise look washed out. `stem_darkening` provides a default amount
+    /// for a scale.
+    ///
+    /// As `draw`, but `x` & `y` are relative to the `min` of
+    /// `darkened_bounding_box`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{point, stem_darkening, Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let scale = Scale::uniform(10.0);
+    /// let glyph = font.glyph('e').scaled(scale).positioned(point(0.0, 8.0));
+    /// let darkening = stem_darkening(scale);
+    ///
+    /// let bb = glyph.darkened_bounding_box(darkening).unwrap();
+    /// glyph.draw_darkened(darkening, |x, y, v| {
+    ///     // draw pixel at (bb.min.x + x, bb.min.y + y)
+    /// });
+    /// ```
+    pub fn draw_darkened<O: FnMut(u32, u32, f32)>(&self, darkening: f32, o: O) {
+        let Some(contours) = self.darkened_contours(darkening) else {
+            return;
+        };
+        let Some(bb) = contours.bounding_box() else {
+            return;
+        };
+        let min = point(bb.min.x.floor(), bb.min.y.floor());
+        let width = (bb.max.x.ceil() - min.x) as usize;
+        let height = (bb.max.y.ceil() - min.y) as usize;
+
+        let mut rasterizer = OutlineRasterizer::new(width, height);
+        contours.replay(min - point(0.0, 0.0), &mut rasterizer);
+        rasterizer.rasterizer.for_each_pixel_2d(o);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::scanline::FlatOutline;
+use crate::PositionedGlyph;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+#[cfg(all(feature = "libm-math", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+
+/// Samples per pixel along each axis in `DrawQuality::Supersampled` mode.
+const SUPERSAMPLES: u32 = 4;
+
+/// Options for `PositionedGlyph::draw_with_options`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DrawOptions {
+    pub quality: DrawQuality,
+}
+
+/// Rasterisation mode, see `DrawOptions`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DrawQuality {
+    /// Analytic coverage, as `PositionedGlyph::draw`. Fast & exact for well
+    /// formed outlines, but overlapping or self-intersecting contours can
+    /// produce artefacts, eg contours of opposite winding cancelling out at
+    /// shared edges.
+    #[default]
+    Analytic,
+    /// Coverage sampled on a 4x4 grid per pixel with the non-zero winding
+    /// rule, then averaged. Slower & less smooth, coverage is a multiple of
+    /// 1/16, but robust to broken outlines, eg of untrusted fonts.
+    Supersampled,
+}
+
+impl PositionedGlyph<'_> {
+    /// Rasterises this glyph as `draw`, with the given `options`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{point, DrawOptions, DrawQuality, Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let glyph = font.glyph('a').scaled(Scale::uniform(20.0)).positioned(point(0.0, 16.0));
+    /// let options = DrawOptions {
+    ///     quality: DrawQuality::Supersampled,
+    /// };
+    /// glyph.draw_with_options(options, |x, y, v| {
+    ///     // draw pixel at (bb.min.x + x, bb.min.y + y)
+    /// });
+    /// ```
+    pub fn draw_with_options<O: FnMut(u32, u32, f32)>(&self, options: DrawOptions, o: O) {
+        match options.quality {
+            DrawQuality::Analytic => self.draw(o),
+            DrawQuality::Supersampled => self.draw_supersampled(o),
+        }
+    }
+
+    fn draw_supersampled<O: FnMut(u32, u32, f32)>(&self, mut o: O) {
+        let Some(bb) = self.pixel_bounding_box() else {
+            return;
+        };
+        let Some(outline) = FlatOutline::new(self.unpositioned()) else {
+            return;
+        };
+        let width = bb.saturating_width() as u32;
+        let height = bb.saturating_height() as u32;
+        // outline coordinates relative to the bounding box min
+        let offset_x = self.position().x - bb.min.x as f32;
+        let offset_y = self.position().y - bb.min.y as f32;
+
+        let ss = SUPERSAMPLES as f32;
+        let columns = width as f32 * ss;
+        let mut samples: Vec<u32> = alloc::vec![0; width as usize];
+        for y in 0..height {
+            samples.iter_mut().for_each(|s| *s = 0);
+            for sy in 0..SUPERSAMPLES {
+                let sample_y = y as f32 + (sy as f32 + 0.5) / ss - offset_y;
+                for (start, end) in outline.crossings(sample_y) {
+                    // sample columns with centres in [start, end)
+                    let first = ((start + offset_x) * ss - 0.5).ceil().clamp(0.0, columns);
+                    let last = ((end + offset_x) * ss - 0.5).ceil().clamp(0.0, columns);
+                    for column in first as u64..last as u64 {
+                        let x = (column / u64::from(SUPERSAMPLES)) as usize;
+                        // columns of huge glyphs may round past the last
+                        if let Some(sample) = samples.get_mut(x) {
+                            *sample += 1;
+                        }
+                    }
+                }
+            }
+            for (x, &count) in samples.iter().enumerate() {
+                o(x as u32, y, count as f32 / (ss * ss));
+            }
+        }
+    }
+}
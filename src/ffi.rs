@@ -0,0 +1,267 @@
+//! C API for loading fonts, reading metrics & rasterizing glyphs.
+//!
+//! Functions & types are prefixed `rusttype_`/`Rusttype` and are suitable for
+//! header generation with cbindgen. Fonts are opaque heap allocated handles
+//! that must be released with `rusttype_font_free`.
+//!
+//! Requires the `ffi` feature.
+//!
+//! # Example
+//!
+//! ```c
+//! RusttypeFont *font = rusttype_font_from_bytes(data, data_len, 0);
+//! uint16_t glyph = rusttype_font_glyph_id(font, 'a');
+//!
+//! RusttypeRect bounds;
+//! if (rusttype_glyph_pixel_bounds(font, glyph, 24.0f, 0.0f, 0.0f, &bounds)) {
+//!     uint32_t width = bounds.max_x - bounds.min_x;
+//!     uint32_t height = bounds.max_y - bounds.min_y;
+//!     uint8_t *pixels = calloc(width * height, 1);
+//!     rusttype_glyph_rasterize(font, glyph, 24.0f, 0.0f, 0.0f, pixels, width, height, width);
+//! }
+//! rusttype_font_free(font);
+//! ```
+use crate::{point, Font, GlyphId, PositionedGlyph, Scale};
+use std::{ptr, slice};
+
+/// An opaque loaded font.
+#[derive(Debug)]
+pub struct RusttypeFont(Font<'static>);
+
+/// Vertical font metrics, see `VMetrics`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RusttypeVMetrics {
+    pub ascent: f32,
+    pub descent: f32,
+    pub line_gap: f32,
+}
+
+/// Horizontal glyph metrics, see `HMetrics`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RusttypeHMetrics {
+    pub advance_width: f32,
+    pub left_side_bearing: f32,
+}
+
+/// A pixel rectangle, `min` inclusive, `max` exclusive.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RusttypeRect {
+    pub min_x: i32,
+    pub min_y: i32,
+    pub max_x: i32,
+    pub max_y: i32,
+}
+
+/// Loads a font, copying `len` bytes of font data from `data`. `index` selects
+/// the font of a collection, use `0` otherwise.
+///
+/// Returns null for invalid data.
+///
+/// # Safety
+///
+/// `data` must be valid for reads of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rusttype_font_from_bytes(
+    data: *const u8,
+    len: usize,
+    index: u32,
+) -> *mut RusttypeFont {
+    if data.is_null() {
+        return ptr::null_mut();
+    }
+    let data = slice::from_raw_parts(data, len).to_vec();
+    match Font::try_from_vec_and_index(data, index) {
+        Some(font) => Box::into_raw(Box::new(RusttypeFont(font))),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Releases a font. Null is ignored.
+///
+/// # Safety
+///
+/// `font` must be null or returned by `rusttype_font_from_bytes` & not
+/// already freed.
+#[no_mangle]
+pub unsafe extern "C" fn rusttype_font_free(font: *mut RusttypeFont) {
+    if !font.is_null() {
+        drop(Box::from_raw(font));
+    }
+}
+
+/// Returns the number of glyphs in the font.
+///
+/// # Safety
+///
+/// `font` must be a valid font handle.
+#[no_mangle]
+pub unsafe extern "C" fn rusttype_font_glyph_count(font: *const RusttypeFont) -> usize {
+    (*font).0.glyph_count()
+}
+
+/// Returns the glyph id for a Unicode code point, `0` (`.notdef`) if the font
+/// has no glyph for it or `codepoint` is not a valid `char`.
+///
+/// # Safety
+///
+/// `font` must be a valid font handle.
+#[no_mangle]
+pub unsafe extern "C" fn rusttype_font_glyph_id(font: *const RusttypeFont, codepoint: u32) -> u16 {
+    char::from_u32(codepoint).map_or(0, |c| (*font).0.glyph(c).id().0)
+}
+
+/// Writes the vertical metrics at pixel height `scale` to `out`.
+///
+/// # Safety
+///
+/// `font` must be a valid font handle & `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rusttype_font_v_metrics(
+    font: *const RusttypeFont,
+    scale: f32,
+    out: *mut RusttypeVMetrics,
+) {
+    let v = (*font).0.v_metrics(Scale::uniform(scale));
+    *out = RusttypeVMetrics {
+        ascent: v.ascent,
+        descent: v.descent,
+        line_gap: v.line_gap,
+    };
+}
+
+/// Returns the kerning adjustment between two glyphs at pixel height `scale`.
+///
+/// # Safety
+///
+/// `font` must be a valid font handle & glyph ids valid for it.
+#[no_mangle]
+pub unsafe extern "C" fn rusttype_font_pair_kerning(
+    font: *const RusttypeFont,
+    scale: f32,
+    first: u16,
+    second: u16,
+) -> f32 {
+    (*font)
+        .0
+        .pair_kerning(Scale::uniform(scale), GlyphId(first), GlyphId(second))
+}
+
+/// Writes the horizontal metrics of a glyph at pixel height `scale` to `out`.
+///
+/// Returns `false`, writing nothing, if `glyph` is not a valid glyph id.
+///
+/// # Safety
+///
+/// `font` must be a valid font handle & `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rusttype_glyph_h_metrics(
+    font: *const RusttypeFont,
+    glyph: u16,
+    scale: f32,
+    out: *mut RusttypeHMetrics,
+) -> bool {
+    let Some(glyph) = (*font).0.try_glyph(GlyphId(glyph)) else {
+        return false;
+    };
+    let h = glyph.scaled(Scale::uniform(scale)).h_metrics();
+    *out = RusttypeHMetrics {
+        advance_width: h.advance_width,
+        left_side_bearing: h.left_side_bearing,
+    };
+    true
+}
+
+/// Writes the pixel bounding box of a glyph at pixel height `scale`,
+/// positioned with its origin at `(x, y)`, to `out`.
+///
+/// Returns `false`, writing nothing, if the glyph is invalid or has no
+/// outline, eg a space.
+///
+/// # Safety
+///
+/// `font` must be a valid font handle & `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rusttype_glyph_pixel_bounds(
+    font: *const RusttypeFont,
+    glyph: u16,
+    scale: f32,
+    x: f32,
+    y: f32,
+    out: *mut RusttypeRect,
+) -> bool {
+    match positioned(&(*font).0, glyph, scale, x, y).and_then(|g| g.pixel_bounding_box()) {
+        Some(bb) => {
+            *out = RusttypeRect {
+                min_x: bb.min.x,
+                min_y: bb.min.y,
+                max_x: bb.max.x,
+                max_y: bb.max.y,
+            };
+            true
+        }
+        None => false,
+    }
+}
+
+/// Rasterizes a glyph at pixel height `scale`, positioned with its origin at
+/// `(x, y)`, writing 8-bit coverage into `buffer`.
+///
+/// The top left of the glyph's pixel bounding box is written to
+/// `buffer[0]`, with rows `stride` bytes apart. Pixels beyond `width` x
+/// `height` are clipped.
+///
+/// Returns `false` if the glyph is invalid or has no outline, if `stride` is
+/// less than `width` or if `stride * height` overflows.
+///
+/// # Safety
+///
+/// `font` must be a valid font handle & `buffer` valid for writes of
+/// `stride * height` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rusttype_glyph_rasterize(
+    font: *const RusttypeFont,
+    glyph: u16,
+    scale: f32,
+    x: f32,
+    y: f32,
+    buffer: *mut u8,
+    width: u32,
+    height: u32,
+    stride: usize,
+) -> bool {
+    let glyph = match positioned(&(*font).0, glyph, scale, x, y) {
+        Some(glyph) if glyph.pixel_bounding_box().is_some() => glyph,
+        _ => return false,
+    };
+    if buffer.is_null() || stride < width as usize {
+        return false;
+    }
+    let len = match stride.checked_mul(height as usize) {
+        Some(len) if len <= isize::MAX as usize => len,
+        _ => return false,
+    };
+    let buffer = slice::from_raw_parts_mut(buffer, len);
+    glyph.draw(|px, py, v| {
+        if px < width && py < height {
+            buffer[py as usize * stride + px as usize] = (v * 255.0).round() as u8;
+        }
+    });
+    true
+}
+
+fn positioned(
+    font: &Font<'static>,
+    glyph: u16,
+    scale: f32,
+    x: f32,
+    y: f32,
+) -> Option<PositionedGlyph<'static>> {
+    Some(
+        font.try_glyph(GlyphId(glyph))?
+            .scaled(Scale::uniform(scale))
+            .positioned(point(x, y)),
+    )
+}
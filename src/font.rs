@@ -1,11 +1,24 @@
-use crate::{Glyph, GlyphIter, IntoGlyphId, LayoutIter, Point, Scale, VMetrics};
-#[cfg(not(feature = "has-atomics"))]
-use alloc::rc::Rc as Arc;
-#[cfg(feature = "has-atomics")]
-use alloc::sync::Arc;
+use crate::{
+    point, vector, CaretSlope, FontCaches, FontError, Glyph, GlyphAdvance, GlyphId, GlyphIter,
+    IntoGlyphId, LayoutIds, LayoutIter, LayoutOptions, Point, PositionedGlyph, Scale,
+    ScriptMetrics, SizeSemantics, UnscaledHMetrics, VMetrics, Vector, WidthMetric,
+};
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 use core::fmt;
+use owned_ttf_parser::{FaceTables, Tag};
+
+#[cfg(not(feature = "has-atomics"))]
+use alloc::rc::Rc as SharedPtr;
+#[cfg(feature = "has-atomics")]
+use alloc::sync::Arc as SharedPtr;
+
+/// The shared pointer a `Font` holds its parsed data in.
+///
+/// `Arc` with the `has-atomics` feature, enabled by `std`, so fonts are
+/// `Send + Sync`. Otherwise `Rc`, for targets without atomics, so fonts &
+/// glyphs are neither `Send` nor `Sync`.
+pub type FontRc<T> = SharedPtr<T>;
 
 /// A single font. This may or may not own the font data.
 ///
@@ -14,6 +27,18 @@ use core::fmt;
 /// cases ie both dynamically loaded owned data and for referenced compile time
 /// font data.
 ///
+/// # Memory placement
+/// Fonts created from byte-slices borrow the data, so it may live in any
+/// memory, eg a buffer from a custom allocator in external RAM, as long as it
+/// outlives the font. Only the small parsed face is allocated, with the
+/// global allocator. Owned data, `try_from_vec`, uses the global allocator.
+///
+/// # Thread safety
+/// With the `has-atomics` feature, enabled by the default `std` feature,
+/// `Font<'static>` & the glyphs from it are `Send + Sync`, so may be shared
+/// for multi-threaded layout & rasterisation. Clones share the font data,
+/// see `FontRc`. Without `has-atomics` fonts are single threaded.
+///
 /// # Example
 ///
 /// ```
@@ -29,45 +54,116 @@ use core::fmt;
 /// ```
 #[derive(Clone)]
 pub enum Font<'a> {
-    Ref(Arc<owned_ttf_parser::Face<'a>>),
-    Owned(Arc<owned_ttf_parser::OwnedFace>),
+    Ref(FontRc<owned_ttf_parser::Face<'a>>),
+    Owned(FontRc<owned_ttf_parser::OwnedFace>),
 }
 
+// `Font` & glyphs are thread safe with `has-atomics`, see `FontRc`
+#[cfg(feature = "has-atomics")]
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Font<'static>>();
+    assert_send_sync::<crate::Glyph<'static>>();
+    assert_send_sync::<crate::ScaledGlyph<'static>>();
+    assert_send_sync::<PositionedGlyph<'static>>();
+};
+
 impl fmt::Debug for Font<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Font")
     }
 }
 
+/// Fonts are equal if they share the same font data, ie are clones. See
+/// `Font::data_id`.
+impl PartialEq for Font<'_> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.data_id() == other.data_id()
+    }
+}
+
+impl Eq for Font<'_> {}
+
+impl core::hash::Hash for Font<'_> {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.data_id().hash(state)
+    }
+}
+
 impl Font<'_> {
     /// Creates a Font from byte-slice data.
     ///
-    /// Returns `None` for invalid data.
+    /// Returns `None` for invalid data, see `from_bytes` for the reason.
     pub fn try_from_bytes(bytes: &[u8]) -> Option<Font<'_>> {
-        Self::try_from_bytes_and_index(bytes, 0)
+        Self::from_bytes(bytes).ok()
     }
 
     /// Creates a Font from byte-slice data & a font collection `index`.
     ///
-    /// Returns `None` for invalid data.
+    /// Returns `None` for invalid data, see `from_bytes_and_index` for the
+    /// reason.
     pub fn try_from_bytes_and_index(bytes: &[u8], index: u32) -> Option<Font<'_>> {
-        let inner = Arc::new(owned_ttf_parser::Face::parse(bytes, index).ok()?);
-        Some(Font::Ref(inner))
+        Self::from_bytes_and_index(bytes, index).ok()
     }
 
     /// Creates a Font from owned font data.
     ///
-    /// Returns `None` for invalid data.
+    /// Returns `None` for invalid data, see `from_vec` for the reason.
     pub fn try_from_vec(data: Vec<u8>) -> Option<Font<'static>> {
-        Self::try_from_vec_and_index(data, 0)
+        Self::from_vec(data).ok()
     }
 
     /// Creates a Font from owned font data & a font collection `index`.
     ///
-    /// Returns `None` for invalid data.
+    /// Returns `None` for invalid data, see `from_vec_and_index` for the
+    /// reason.
     pub fn try_from_vec_and_index(data: Vec<u8>, index: u32) -> Option<Font<'static>> {
-        let inner = Arc::new(owned_ttf_parser::OwnedFace::from_vec(data, index).ok()?);
-        Some(Font::Owned(inner))
+        Self::from_vec_and_index(data, index).ok()
+    }
+
+    /// Creates a Font from byte-slice data, returning why loading failed for
+    /// invalid data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, FontError};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// assert!(Font::from_bytes(font_data).is_ok());
+    /// assert_eq!(Font::from_bytes(b"not a font").unwrap_err(), FontError::UnknownMagic);
+    /// assert_eq!(
+    ///     Font::from_bytes_and_index(font_data, 1).unwrap_err(),
+    ///     FontError::FaceIndexOutOfBounds,
+    /// );
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Font<'_>, FontError> {
+        Self::from_bytes_and_index(bytes, 0)
+    }
+
+    /// Creates a Font from byte-slice data & a font collection `index`,
+    /// returning why loading failed for invalid data.
+    pub fn from_bytes_and_index(bytes: &[u8], index: u32) -> Result<Font<'_>, FontError> {
+        let face = owned_ttf_parser::Face::parse(bytes, index)
+            .map_err(|err| crate::font_error::from_parsing_error(bytes, index, err))?;
+        Ok(Font::Ref(FontRc::new(face)))
+    }
+
+    /// Creates a Font from owned font data, returning why loading failed for
+    /// invalid data.
+    pub fn from_vec(data: Vec<u8>) -> Result<Font<'static>, FontError> {
+        Self::from_vec_and_index(data, 0)
+    }
+
+    /// Creates a Font from owned font data & a font collection `index`,
+    /// returning why loading failed for invalid data.
+    pub fn from_vec_and_index(data: Vec<u8>, index: u32) -> Result<Font<'static>, FontError> {
+        // scan the table directory first, as the data is consumed on failure
+        let records = crate::font_error::RequiredRecords::scan(&data, index);
+        let inner = owned_ttf_parser::OwnedFace::from_vec(data, index)
+            .map_err(|err| records.classify(err))?;
+        Ok(Font::Owned(FontRc::new(inner)))
     }
 }
 
@@ -81,12 +177,117 @@ impl<'font> Font<'font> {
         }
     }
 
+    /// Returns an identifier for the font data shared by this font and all of
+    /// its clones. Fonts loaded separately have different ids, even if loaded
+    /// from the same bytes.
+    ///
+    /// The id is the address of the shared data, so is only unique among fonts
+    /// that are alive at the same time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::Font;
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// let font = Font::try_from_bytes(font_data).unwrap();
+    /// let other_font = Font::try_from_bytes(font_data).unwrap();
+    ///
+    /// assert_eq!(font.data_id(), font.clone().data_id());
+    /// assert_ne!(font.data_id(), other_font.data_id());
+    /// ```
+    #[inline]
+    pub fn data_id(&self) -> usize {
+        match self {
+            Self::Ref(f) => FontRc::as_ptr(f) as *const u8 as usize,
+            Self::Owned(f) => FontRc::as_ptr(f) as *const u8 as usize,
+        }
+    }
+
+    /// Returns an approximate count of the bytes owned by this font: the
+    /// parsed face &, for fonts loaded from a `Vec`, the font data. Borrowed
+    /// font data is not included.
+    ///
+    /// Clones share the same memory, so it should be counted once per
+    /// `data_id`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::Font;
+    /// let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// let font = Font::try_from_vec(font_data.to_vec()).unwrap();
+    ///
+    /// assert!(font.approx_memory_usage() > font_data.len());
+    /// ```
+    pub fn approx_memory_usage(&self) -> usize {
+        match self {
+            Self::Ref(_) => core::mem::size_of::<owned_ttf_parser::Face<'_>>(),
+            Self::Owned(f) => {
+                core::mem::size_of::<owned_ttf_parser::OwnedFace>() + f.as_slice().len()
+            }
+        }
+    }
+
+    /// Returns the data of the font table with `tag`, or `None` if the font
+    /// has no such table. Allows reading tables rusttype doesn't model, eg
+    /// `meta` or `BASE`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, Tag};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let head = font.raw_table(Tag::from_bytes(b"head")).unwrap();
+    /// // version 1.0
+    /// assert_eq!(head[..4], [0, 1, 0, 0]);
+    /// assert_eq!(font.raw_table(Tag::from_bytes(b"BASE")), None);
+    /// ```
+    #[inline]
+    pub fn raw_table(&self, tag: Tag) -> Option<&[u8]> {
+        self.inner().raw_face().table(tag)
+    }
+
+    /// Returns the font's tables as parsed by
+    /// [ttf-parser](https://docs.rs/ttf-parser), for advanced uses beyond
+    /// rusttype's API without parsing the font again.
+    ///
+    /// The table types are ttf-parser's, so may change with its version.
+    #[inline]
+    pub fn tables(&self) -> &FaceTables<'_> {
+        self.inner().tables()
+    }
+
+    /// Returns the index of this face in its font collection, or `0` if the
+    /// data is not a collection.
+    pub(crate) fn collection_index(&self) -> u32 {
+        let raw = self.inner().raw_face();
+        let first_table = |face: &owned_ttf_parser::RawFace<'_>| {
+            face.table_records.get(0).map(|r| (r.tag, r.offset))
+        };
+        let target = first_table(raw);
+
+        let count = owned_ttf_parser::fonts_in_collection(raw.data).unwrap_or(1);
+        (0..count)
+            .find(|&index| {
+                owned_ttf_parser::RawFace::parse(raw.data, index)
+                    .is_ok_and(|face| first_table(&face) == target)
+            })
+            .unwrap_or(0)
+    }
+
     /// The "vertical metrics" for this font at a given scale. These metrics are
     /// shared by all of the glyphs in the font. See `VMetrics` for more detail.
     pub fn v_metrics(&self, scale: Scale) -> VMetrics {
         self.v_metrics_unscaled() * self.scale_for_pixel_height(scale.y)
     }
 
+    /// The "vertical metrics" for this font at `size`, interpreted according
+    /// to `semantics`. See `Font::resolve_scale`.
+    pub fn v_metrics_with_semantics(&self, size: Scale, semantics: SizeSemantics) -> VMetrics {
+        self.v_metrics(self.resolve_scale(size, semantics))
+    }
+
     /// Get the unscaled VMetrics for this font, shared by all glyphs.
     /// See `VMetrics` for more detail.
     pub fn v_metrics_unscaled(&self) -> VMetrics {
@@ -98,6 +299,159 @@ impl<'font> Font<'font> {
         }
     }
 
+    /// Returns the superscript scale & offset for running text at `scale`,
+    /// as intended by the font designer, from the OS/2 table. `None` if the
+    /// font has no OS/2 table or superscript size.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{point, Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let scale = Scale::uniform(20.0);
+    /// let caret = point(40.0, 20.0);
+    /// let sup = font.superscript_metrics(scale).unwrap();
+    ///
+    /// let glyphs = font.layout("2", sup.scale, caret + sup.offset);
+    /// # assert!(sup.scale.y < scale.y && sup.offset.y < 0.0);
+    /// ```
+    pub fn superscript_metrics(&self, scale: Scale) -> Option<ScriptMetrics> {
+        let metrics = self.inner().superscript_metrics()?;
+        // superscript offsets are y-up
+        self.script_metrics(scale, metrics, -1.0)
+    }
+
+    /// Returns the subscript scale & offset for running text at `scale`, see
+    /// `superscript_metrics`.
+    pub fn subscript_metrics(&self, scale: Scale) -> Option<ScriptMetrics> {
+        let metrics = self.inner().subscript_metrics()?;
+        // subscript offsets are y-down
+        self.script_metrics(scale, metrics, 1.0)
+    }
+
+    /// Returns the italic angle from the `post` table, in degrees
+    /// counter-clockwise from vertical, eg `-12.0` for text leaning right.
+    /// `0.0` for upright fonts, `None` if the font has no `post` table.
+    pub fn italic_angle(&self) -> Option<f32> {
+        self.inner().italic_angle()
+    }
+
+    /// Returns the slope of carets in this font's text, from the `hhea`
+    /// table. See `caret_line` to position a caret.
+    ///
+    /// An invalid zero `rise` is returned as a vertical slope.
+    pub fn caret_slope(&self) -> CaretSlope {
+        let hhea = self.raw_table(Tag::from_bytes(b"hhea")).unwrap_or_default();
+        let read_i16 = |at: usize| {
+            hhea.get(at..at + 2)
+                .map_or(0, |b| i16::from_be_bytes([b[0], b[1]]))
+        };
+        match (read_i16(18), read_i16(20)) {
+            (rise, run) if rise != 0 => CaretSlope {
+                rise,
+                run,
+                offset: read_i16(22),
+            },
+            _ => CaretSlope {
+                rise: 1,
+                run: 0,
+                offset: 0,
+            },
+        }
+    }
+
+    /// Returns the bottom & top points of a caret at the baseline point
+    /// `caret`, eg a layout caret position, for text at `scale`.
+    ///
+    /// The caret spans the font's descent to ascent, slanted by
+    /// `caret_slope` so it leans with italic text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{point, Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/opensans/OpenSans-Italic.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let (bottom, top) = font.caret_line(Scale::uniform(20.0), point(10.0, 20.0));
+    /// // draw a line from bottom to top
+    ///
+    /// assert_eq!(font.italic_angle(), Some(-12.0));
+    /// assert!(top.x > bottom.x && top.y < bottom.y);
+    /// ```
+    pub fn caret_line(&self, scale: Scale, caret: Point<f32>) -> (Point<f32>, Point<f32>) {
+        let slope = self.caret_slope();
+        let v_factor = self.scale_for_pixel_height(scale.y);
+        let h_factor = self.kerning_factor(scale);
+        let unscaled = self.v_metrics_unscaled();
+        let at_height = |height: f32| {
+            point(
+                caret.x + h_factor * (f32::from(slope.offset) + slope.slant() * height),
+                caret.y - v_factor * height,
+            )
+        };
+        (at_height(unscaled.descent), at_height(unscaled.ascent))
+    }
+
+    fn script_metrics(
+        &self,
+        scale: Scale,
+        metrics: owned_ttf_parser::ScriptMetrics,
+        y_direction: f32,
+    ) -> Option<ScriptMetrics> {
+        if metrics.x_size <= 0 || metrics.y_size <= 0 {
+            return None;
+        }
+        let units_per_em = f32::from(self.units_per_em());
+        Some(ScriptMetrics {
+            scale: Scale {
+                x: scale.x * f32::from(metrics.x_size) / units_per_em,
+                y: scale.y * f32::from(metrics.y_size) / units_per_em,
+            },
+            offset: vector(
+                self.kerning_factor(scale) * f32::from(metrics.x_offset),
+                y_direction * self.scale_for_pixel_height(scale.y) * f32::from(metrics.y_offset),
+            ),
+        })
+    }
+
+    /// Returns the unscaled horizontal metrics of every glyph in this font, as
+    /// packed arrays in font units. See `UnscaledHMetrics`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let metrics = font.h_metrics_unscaled();
+    /// assert_eq!(metrics.advance_widths.len(), font.glyph_count());
+    ///
+    /// let scale = Scale::uniform(20.0);
+    /// let a = font.glyph('a');
+    /// let unscaled_advance = metrics.advance_widths[a.id().0 as usize];
+    /// assert_eq!(
+    ///     unscaled_advance as f32 * font.scale_for_pixel_height(scale.y),
+    ///     a.scaled(scale).h_metrics().advance_width,
+    /// );
+    /// ```
+    pub fn h_metrics_unscaled(&self) -> UnscaledHMetrics {
+        let font = self.inner();
+        let (advance_widths, left_side_bearings) = (0..font.number_of_glyphs())
+            .map(owned_ttf_parser::GlyphId)
+            .map(|id| {
+                (
+                    font.glyph_hor_advance(id).unwrap_or(0),
+                    font.glyph_hor_side_bearing(id).unwrap_or(0),
+                )
+            })
+            .unzip();
+        UnscaledHMetrics {
+            advance_widths,
+            left_side_bearings,
+        }
+    }
+
     /// Returns the units per EM square of this font
     pub fn units_per_em(&self) -> u16 {
         self.inner().units_per_em()
@@ -119,13 +473,77 @@ impl<'font> Font<'font> {
     ///
     /// Note that code points without corresponding glyphs in this font map to
     /// the ".notdef" glyph, glyph 0.
+    ///
+    /// Use `try_glyph` for glyph ids from untrusted sources.
     pub fn glyph<C: IntoGlyphId>(&self, id: C) -> Glyph<'font> {
         let gid = id.into_glyph_id(self);
-        assert!((gid.0 as usize) < self.glyph_count());
+        self.try_glyph(gid).unwrap_or_else(|| {
+            panic!(
+                "Invalid glyph id {} for font with {} glyphs",
+                gid.0,
+                self.glyph_count()
+            )
+        })
+    }
+
+    /// Returns the corresponding glyph for a Unicode code point or a glyph id
+    /// for this font, or `None` if `id` is a `GlyphId` that is not valid for
+    /// this font.
+    ///
+    /// Unlike `glyph` this never panics, so is suited to glyph ids from
+    /// untrusted data, eg deserialized layouts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, GlyphId};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// assert!(font.try_glyph('a').is_some());
+    /// assert!(font.try_glyph(GlyphId(u16::MAX)).is_none());
+    /// ```
+    pub fn try_glyph<C: IntoGlyphId>(&self, id: C) -> Option<Glyph<'font>> {
+        let id = id.into_glyph_id(self);
         // font clone either a reference clone, or arc clone
-        Glyph {
+        ((id.0 as usize) < self.glyph_count()).then(|| Glyph {
             font: self.clone(),
-            id: gid,
+            id,
+        })
+    }
+
+    /// Returns the name of the glyph `id`, from the font's `post` or `CFF `
+    /// table, eg for PDF output or debugging.
+    ///
+    /// Returns `None` if the font has no name for the glyph.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::Font;
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let ae = font.glyph('æ').id();
+    /// assert_eq!(font.glyph_name(ae), Some("ae"));
+    /// assert_eq!(font.glyph_by_name("ae").map(|g| g.id()), Some(ae));
+    /// ```
+    pub fn glyph_name(&self, id: GlyphId) -> Option<&str> {
+        self.inner().glyph_name(id.into())
+    }
+
+    /// Returns the glyph with the name `name`, the reverse of `glyph_name`.
+    ///
+    /// Returns `None` if no glyph has the name.
+    pub fn glyph_by_name(&self, name: &str) -> Option<Glyph<'font>> {
+        let id = self.inner().glyph_index_by_name(name)?;
+        self.try_glyph(GlyphId::from(id))
+    }
+
+    /// Returns the glyph id for `c`, or glyph 0 if there is none or the font's
+    /// `cmap` maps it to an invalid glyph id.
+    pub(crate) fn char_glyph_id(&self, c: char) -> GlyphId {
+        match self.inner().glyph_index(c) {
+            Some(id) if (id.0 as usize) < self.glyph_count() => id.into(),
+            _ => GlyphId(0),
         }
     }
 
@@ -142,6 +560,31 @@ impl<'font> Font<'font> {
         GlyphIter { font: self, itr }
     }
 
+    /// Appends the glyph id of each char of `s` to `ids`, mapping the whole
+    /// string in one pass without the per glyph `Font` clone of `glyph`.
+    /// Chars without a glyph map to glyph 0.
+    ///
+    /// Combined with `h_metrics_unscaled` this allows fast width estimates,
+    /// eg when sizing table columns.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, GlyphId, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let advances = font.h_metrics_unscaled().advance_widths;
+    /// let mut ids = Vec::new();
+    ///
+    /// font.glyph_ids("Total", &mut ids);
+    /// let units: u32 = ids.iter().map(|id| u32::from(advances[id.0 as usize])).sum();
+    /// let width = units as f32 * font.scale_for_pixel_height(20.0);
+    /// # assert_eq!(ids[0], font.glyph('T').id());
+    /// ```
+    pub fn glyph_ids(&self, s: &str, ids: &mut Vec<GlyphId>) {
+        ids.extend(s.chars().map(|c| self.char_glyph_id(c)));
+    }
+
     /// A convenience function for laying out glyphs for a string horizontally.
     /// It does not take control characters like line breaks into account, as
     /// treatment of these is likely to depend on the application.
@@ -201,9 +644,176 @@ impl<'font> Font<'font> {
             scale,
             start,
             last_glyph: None,
+            last_caret: 0.0,
+            last_advance: GlyphAdvance::default(),
+            options: LayoutOptions::default(),
+            kern_table: None,
+            #[cfg(feature = "unicode-segmentation")]
+            graphemes: None,
+        }
+    }
+
+    /// Lays out `s` as `layout`, producing plain `PlacedGlyph` values of
+    /// glyph id, position & scale without cloning the font into each glyph.
+    ///
+    /// Suits engines keeping their own glyph caches that only need positions,
+    /// avoiding reference count traffic for every glyph of every frame. Use
+    /// `LayoutIter::ids` to lay out with other `LayoutIter` options.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::*;
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let scale = Scale::uniform(20.0);
+    /// let placed: Vec<_> = font.layout_ids("Hello", scale, point(0.0, 20.0)).collect();
+    ///
+    /// assert_eq!(placed[0].id, font.glyph('H').id());
+    /// // positioned glyphs on demand, borrowing the font
+    /// let glyph = font.glyph_ref(placed[1].id).scaled(placed[1].scale).positioned(placed[1].position);
+    /// # assert_eq!(glyph.to_positioned(), font.layout("Hello", scale, point(0.0, 20.0)).nth(1).unwrap());
+    /// ```
+    pub fn layout_ids<'a, 's>(
+        &'a self,
+        s: &'s str,
+        scale: Scale,
+        start: Point<f32>,
+    ) -> LayoutIds<'a, 'font, 's> {
+        self.layout(s, scale, start).ids()
+    }
+
+    /// Lays out `s` as `layout`, with `size` interpreted according to
+    /// `semantics`. See `Font::resolve_scale`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::*;
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// // as CSS `font-size: 16px`
+    /// let size = Scale::uniform(16.0);
+    /// let glyphs: Vec<_> = font
+    ///     .layout_with_semantics("Hello", size, SizeSemantics::EmSquare, point(0.0, 16.0))
+    ///     .collect();
+    ///
+    /// assert_eq!(font.px_per_em(glyphs[0].scale()), 16.0);
+    /// ```
+    pub fn layout_with_semantics<'a, 's>(
+        &'a self,
+        s: &'s str,
+        size: Scale,
+        semantics: SizeSemantics,
+        start: Point<f32>,
+    ) -> LayoutIter<'a, 'font, 's> {
+        self.layout(s, self.resolve_scale(size, semantics), start)
+    }
+
+    /// Measures the width of `s` laid out horizontally with `layout`, in
+    /// pixels, using the given `metric`. See `WidthMetric` for the
+    /// difference between advance and ink widths.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, Scale, WidthMetric};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/opensans/OpenSans-Italic.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let scale = Scale::uniform(24.0);
+    /// // where to place the caret after the text
+    /// let caret_x = font.measure_width("Staff", scale, WidthMetric::Advance);
+    /// // how wide a box must be to contain the text's pixels
+    /// let ink_width = font.measure_width("Staff", scale, WidthMetric::Ink);
+    /// ```
+    pub fn measure_width(&self, s: &str, scale: Scale, metric: WidthMetric) -> f32 {
+        let mut glyphs = self.layout(s, scale, crate::point(0.0, 0.0));
+        match metric {
+            WidthMetric::Advance => {
+                let last = glyphs.by_ref().last();
+                last.map_or(0.0, |g| {
+                    g.position().x + g.unpositioned().h_metrics().advance_width
+                })
+            }
+            WidthMetric::Ink => {
+                let (min, max) = glyphs
+                    .filter_map(|g| {
+                        let bb = g.unpositioned().exact_bounding_box()?;
+                        Some((g.position().x + bb.min.x, g.position().x + bb.max.x))
+                    })
+                    .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), (l, r)| {
+                        (min.min(l), max.max(r))
+                    });
+                (max - min).max(0.0)
+            }
         }
     }
 
+    /// Lays out as much of `s` as fits in `max_width` pixels, like `layout`,
+    /// followed by `ellipsis` if it was truncated, eg `"…"`. Widths are
+    /// advance widths & include the kerning between the last kept glyph & the
+    /// ellipsis.
+    ///
+    /// Returns the glyphs & the number of chars of `s` laid out. If `s` fits
+    /// all its chars are returned without an ellipsis. If not even the
+    /// ellipsis fits nothing is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{point, Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let scale = Scale::uniform(20.0);
+    /// let (glyphs, chars) = font.layout_truncated("Hello World", scale, point(0.0, 20.0), 60.0, "…");
+    ///
+    /// assert_eq!(chars, 4);
+    /// assert_eq!(glyphs.len(), 5);
+    /// assert_eq!(glyphs[4].id(), font.glyph('…').id());
+    /// ```
+    pub fn layout_truncated(
+        &self,
+        s: &str,
+        scale: Scale,
+        start: Point<f32>,
+        max_width: f32,
+        ellipsis: &str,
+    ) -> (Vec<PositionedGlyph<'font>>, usize) {
+        let mut glyphs: Vec<_> = self.layout(s, scale, start).collect();
+        let end = |g: &PositionedGlyph<'_>| {
+            g.position().x - start.x + g.unpositioned().h_metrics().advance_width
+        };
+        if glyphs.last().map_or(0.0, end) <= max_width {
+            let chars = glyphs.len();
+            return (glyphs, chars);
+        }
+
+        let ellipsis_width = self.measure_width(ellipsis, scale, WidthMetric::Advance);
+        let ellipsis_first = ellipsis.chars().next().map(|c| self.glyph(c).id());
+        // x offset of the ellipsis following the first `chars` glyphs, if it fits
+        let ellipsis_x = |chars: usize| {
+            let x = match chars.checked_sub(1) {
+                Some(last) => {
+                    let last = &glyphs[last];
+                    end(last)
+                        + ellipsis_first
+                            .map_or(0.0, |first| self.pair_kerning(scale, last.id(), first))
+                }
+                None => 0.0,
+            };
+            (x + ellipsis_width <= max_width).then_some(x)
+        };
+
+        for chars in (0..glyphs.len()).rev() {
+            if let Some(x) = ellipsis_x(chars) {
+                glyphs.truncate(chars);
+                glyphs.extend(self.layout(ellipsis, scale, point(start.x + x, start.y)));
+                return (glyphs, chars);
+            }
+        }
+        (Vec::new(), 0)
+    }
+
     /// Returns additional kerning to apply as well as that given by HMetrics
     /// for a particular pair of glyphs.
     pub fn pair_kerning<A, B>(&self, scale: Scale, first: A, second: B) -> f32
@@ -211,25 +821,116 @@ impl<'font> Font<'font> {
         A: IntoGlyphId,
         B: IntoGlyphId,
     {
-        let first_id = first.into_glyph_id(self).into();
-        let second_id = second.into_glyph_id(self).into();
+        let first_id = first.into_glyph_id(self);
+        let second_id = second.into_glyph_id(self);
+        self.kerning_factor(scale) * f32::from(self.pair_kerning_unscaled(first_id, second_id))
+    }
 
-        let factor = {
-            let hscale = self.scale_for_pixel_height(scale.y);
-            hscale * (scale.x / scale.y)
-        };
+    /// Returns the same value as `pair_kerning`, looking up kerning in a
+    /// `KernTable` built on first use & kept in `caches`.
+    ///
+    /// `caches` should be dedicated to this font, if it was last used with a
+    /// different font it is cleared first.
+    pub fn pair_kerning_cached<A, B>(
+        &self,
+        caches: &mut FontCaches<'font>,
+        scale: Scale,
+        first: A,
+        second: B,
+    ) -> f32
+    where
+        A: IntoGlyphId,
+        B: IntoGlyphId,
+    {
+        let first_id = first.into_glyph_id(self);
+        let second_id = second.into_glyph_id(self);
+        let kern = caches
+            .for_font(self)
+            .kern_table
+            .get_or_insert_with(|| self.kern_table())
+            .pair_kerning_unscaled(first_id, second_id);
+        self.kerning_factor(scale) * f32::from(kern)
+    }
+
+    /// Returns the offset from the origin of a `base` glyph to the origin of
+    /// a `mark` glyph attached to it, eg a diacritic, using the font's GPOS
+    /// mark-to-base, mark-to-ligature & mark-to-mark anchors.
+    ///
+    /// `base` may itself be a mark, to stack marks using mark-to-mark
+    /// anchors. Marks attach to the last component of a ligature. Returns
+    /// `None` if the font has no anchors for the pair.
+    ///
+    /// Lookups are searched in order regardless of script & feature, which
+    /// suits the `mark` & `mkmk` features of most fonts.
+    pub fn mark_attachment<A, B>(&self, scale: Scale, base: A, mark: B) -> Option<Vector<f32>>
+    where
+        A: IntoGlyphId,
+        B: IntoGlyphId,
+    {
+        let base_id = base.into_glyph_id(self);
+        let mark_id = mark.into_glyph_id(self);
+        let (x, y) = self.mark_attachment_unscaled(base_id, mark_id)?;
+        let scale_y = self.scale_for_pixel_height(scale.y);
+        // font units are y-up
+        Some(vector(self.kerning_factor(scale) * x, -scale_y * y))
+    }
+
+    fn mark_attachment_unscaled(&self, base: GlyphId, mark: GlyphId) -> Option<(f32, f32)> {
+        use owned_ttf_parser::gpos::PositioningSubtable;
+
+        let gpos = self.inner().tables().gpos?;
+        let (base, mark) = (base.into(), mark.into());
+        gpos.lookups.into_iter().find_map(|lookup| {
+            lookup
+                .subtables
+                .into_iter::<PositioningSubtable>()
+                .find_map(|subtable| {
+                    let (marks, mark_index, anchors, row) = match subtable {
+                        PositioningSubtable::MarkToBase(t) => (
+                            t.marks,
+                            t.mark_coverage.get(mark)?,
+                            t.anchors,
+                            t.base_coverage.get(base)?,
+                        ),
+                        PositioningSubtable::MarkToLigature(t) => {
+                            let anchors = t.ligature_array.get(t.ligature_coverage.get(base)?)?;
+                            let last_component = anchors.rows.checked_sub(1)?;
+                            (t.marks, t.mark_coverage.get(mark)?, anchors, last_component)
+                        }
+                        PositioningSubtable::MarkToMark(t) => (
+                            t.marks,
+                            t.mark1_coverage.get(mark)?,
+                            t.mark2_matrix,
+                            t.mark2_coverage.get(base)?,
+                        ),
+                        _ => return None,
+                    };
+                    let (class, mark_anchor) = marks.get(mark_index)?;
+                    let base_anchor = anchors.get(row, class)?;
+                    Some((
+                        f32::from(base_anchor.x) - f32::from(mark_anchor.x),
+                        f32::from(base_anchor.y) - f32::from(mark_anchor.y),
+                    ))
+                })
+        })
+    }
+
+    #[inline]
+    pub(crate) fn kerning_factor(&self, scale: Scale) -> f32 {
+        let hscale = self.scale_for_pixel_height(scale.y);
+        hscale * (scale.x / scale.y)
+    }
 
-        let kern = if let Some(kern) = self.inner().tables().kern {
+    pub(crate) fn pair_kerning_unscaled(&self, first: GlyphId, second: GlyphId) -> i16 {
+        if let Some(kern) = self.inner().tables().kern {
             kern.subtables
                 .into_iter()
                 .filter(|st| st.horizontal && !st.variable)
-                .find_map(|st| st.glyphs_kerning(first_id, second_id))
+                .find_map(|st| st.glyphs_kerning(first.into(), second.into()))
                 .unwrap_or(0)
         } else {
             0
-        };
-
-        factor * f32::from(kern)
+        }
     }
 
     /// Computes a scale factor to produce a font whose "height" is 'pixels'
@@ -244,4 +945,59 @@ impl<'font> Font<'font> {
         let fheight = f32::from(inner.ascender()) - f32::from(inner.descender());
         height / fheight
     }
+
+    /// Returns the `Scale` at which this font's em square is `px` pixels
+    /// tall, ie CSS `font-size: {px}px` semantics.
+    ///
+    /// `Scale` is otherwise the height from the highest ascender to the
+    /// lowest descender, see `scale_for_pixel_height`, usually larger than
+    /// the em, so text at `Scale::uniform(px)` renders smaller than a browser
+    /// would at the same pixel size.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{point, Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// // as `font-size: 16px`
+    /// let scale = font.scale_for_em_height(16.0);
+    /// let glyphs = font.layout("Hello", scale, point(0.0, 16.0));
+    ///
+    /// // 12pt at 96 dpi is also 16px
+    /// let pt_scale = font.scale_for_em_height(Scale::from_pt(12.0, 96.0).y);
+    /// assert_eq!(pt_scale, scale);
+    /// assert_eq!(font.px_per_em(scale), 16.0);
+    /// ```
+    pub fn scale_for_em_height(&self, px: f32) -> Scale {
+        self.resolve_scale(Scale::uniform(px), SizeSemantics::EmSquare)
+    }
+
+    /// Returns the `Scale`, an ascent to descent height, for text of `size`
+    /// pixels measured according to `semantics`.
+    ///
+    /// Use this to size text consistently with other software without a
+    /// per font fudge factor, eg `SizeSemantics::EmSquare` to match CSS.
+    /// `layout_with_semantics`, `v_metrics_with_semantics` &
+    /// `Glyph::scaled_with_semantics` accept sizes directly.
+    pub fn resolve_scale(&self, size: Scale, semantics: SizeSemantics) -> Scale {
+        match semantics {
+            SizeSemantics::AscentDescent => size,
+            SizeSemantics::EmSquare => {
+                let inner = self.inner();
+                let fheight = f32::from(inner.ascender()) - f32::from(inner.descender());
+                let factor = fheight / f32::from(self.units_per_em());
+                Scale {
+                    x: size.x * factor,
+                    y: size.y * factor,
+                }
+            }
+        }
+    }
+
+    /// Returns the height in pixels of this font's em square at `scale`, ie
+    /// the equivalent CSS `font-size`. The reverse of `scale_for_em_height`.
+    pub fn px_per_em(&self, scale: Scale) -> f32 {
+        self.scale_for_pixel_height(scale.y) * f32::from(self.units_per_em())
+    }
 }
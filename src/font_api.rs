@@ -0,0 +1,218 @@
+use crate::outliner::{OutlineRasterizer, OutlineScaler, OutlineTranslator};
+use crate::{point, vector, Font, GlyphId, HMetrics, PixelRounding, Point, Rect, Scale, VMetrics};
+use owned_ttf_parser::OutlineBuilder;
+
+#[cfg(all(feature = "libm-math", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+
+/// The metric, outline & raster operations of a font, implemented by `Font`.
+///
+/// GUI toolkits can write their text handling against this trait to abstract
+/// over rusttype & alternative backends, or to inject mock fonts in tests
+/// without real font files.
+///
+/// Implementors provide the unscaled font data, in font units with y-up.
+/// Scaled metrics, outlines & rasterisation are provided in terms of these
+/// & match the results of the `Font` glyph API. The trait is object safe.
+///
+/// # Example
+///
+/// ```
+/// use rusttype::{point, Font, FontApi, Scale};
+/// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+/// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+///
+/// fn text_width(font: &dyn FontApi, text: &str, scale: Scale) -> f32 {
+///     text.chars()
+///         .map(|c| font.glyph_h_metrics(font.glyph_id(c), scale).advance_width)
+///         .sum()
+/// }
+///
+/// let scale = Scale::uniform(20.0);
+/// let glyph = font.glyph('a').scaled(scale);
+/// assert_eq!(text_width(&font, "a", scale), glyph.h_metrics().advance_width);
+/// ```
+pub trait FontApi {
+    /// Returns the units per EM square of the font.
+    fn units_per_em(&self) -> u16;
+
+    /// The number of glyphs in the font, glyph ids are in the range
+    /// `0..self.glyph_count()`.
+    fn glyph_count(&self) -> usize;
+
+    /// Returns the glyph id for a char, `GlyphId(0)` if the font has no glyph
+    /// for it.
+    fn glyph_id(&self, c: char) -> GlyphId;
+
+    /// Returns the vertical metrics of the font in font units.
+    fn v_metrics_unscaled(&self) -> VMetrics;
+
+    /// Returns the horizontal metrics of a glyph in font units.
+    fn glyph_h_metrics_unscaled(&self, id: GlyphId) -> HMetrics;
+
+    /// Returns the kerning between a pair of glyphs in font units.
+    fn pair_kerning_unscaled(&self, first: GlyphId, second: GlyphId) -> f32;
+
+    /// Returns the bounding box of a glyph's shape in font units, y-up.
+    /// `None` for glyphs with no shape.
+    fn glyph_bounding_box_unscaled(&self, id: GlyphId) -> Option<Rect<f32>>;
+
+    /// Builds the outline of a glyph in font units, y-up. Returns `false`
+    /// when the outline is either malformed or empty.
+    fn build_outline_unscaled(&self, id: GlyphId, builder: &mut dyn OutlineBuilder) -> bool;
+
+    /// Computes a scale factor to produce a font whose ascent to descent
+    /// height is `height` pixels, see `Font::scale_for_pixel_height`.
+    fn scale_for_pixel_height(&self, height: f32) -> f32 {
+        let v_metrics = self.v_metrics_unscaled();
+        height / (v_metrics.ascent - v_metrics.descent)
+    }
+
+    /// Returns the vertical metrics of the font at `scale`.
+    fn v_metrics(&self, scale: Scale) -> VMetrics {
+        self.v_metrics_unscaled() * self.scale_for_pixel_height(scale.y)
+    }
+
+    /// Returns the horizontal metrics of a glyph at `scale`, see
+    /// `ScaledGlyph::h_metrics`.
+    fn glyph_h_metrics(&self, id: GlyphId, scale: Scale) -> HMetrics {
+        let scale_x = scale_vector(self, scale).x;
+        let h_metrics = self.glyph_h_metrics_unscaled(id);
+        HMetrics {
+            advance_width: h_metrics.advance_width * scale_x,
+            left_side_bearing: h_metrics.left_side_bearing * scale_x,
+        }
+    }
+
+    /// Returns the kerning between a pair of glyphs at `scale`, see
+    /// `Font::pair_kerning`.
+    fn pair_kerning(&self, scale: Scale, first: GlyphId, second: GlyphId) -> f32 {
+        scale_vector(self, scale).x * self.pair_kerning_unscaled(first, second)
+    }
+
+    /// Returns the bounding box of a glyph's shape at `scale`, relative to
+    /// its origin, see `ScaledGlyph::exact_bounding_box`.
+    fn glyph_bounding_box(&self, id: GlyphId, scale: Scale) -> Option<Rect<f32>> {
+        let Rect { min, max } = self.glyph_bounding_box_unscaled(id)?;
+        let scale = scale_vector(self, scale);
+        Some(Rect {
+            min: point(min.x * scale.x, -max.y * scale.y),
+            max: point(max.x * scale.x, -min.y * scale.y),
+        })
+    }
+
+    /// Builds the outline of a glyph at `scale`, relative to its origin with
+    /// y-down, see `ScaledGlyph::build_outline`.
+    fn build_outline(&self, id: GlyphId, scale: Scale, builder: &mut dyn OutlineBuilder) -> bool {
+        let scale = scale_vector(self, scale);
+        let mut outliner = OutlineScaler::new(builder, vector(scale.x, -scale.y));
+        self.build_outline_unscaled(id, &mut outliner)
+    }
+
+    /// Returns the conservative pixel bounding box of a glyph at `scale` &
+    /// `position`, see `PositionedGlyph::pixel_bounding_box`.
+    fn pixel_bounding_box(
+        &self,
+        id: GlyphId,
+        scale: Scale,
+        position: Point<f32>,
+    ) -> Option<Rect<i32>> {
+        let Rect { min, max } = self.glyph_bounding_box_unscaled(id)?;
+        let scale = scale_vector(self, scale);
+        let rounding = PixelRounding::default();
+        let (x_trunc, x_fract) = (position.x.trunc() as i32, position.x.fract());
+        let (y_trunc, y_fract) = (position.y.trunc() as i32, position.y.fract());
+        let bb = Rect {
+            min: point(
+                rounding.round_min(min.x * scale.x + x_fract),
+                rounding.round_min(-max.y * scale.y + y_fract),
+            ),
+            max: point(
+                rounding.round_max(max.x * scale.x + x_fract),
+                rounding.round_max(-min.y * scale.y + y_fract),
+            ),
+        };
+        Some(bb.saturating_translate(vector(x_trunc, y_trunc)))
+    }
+
+    /// Rasterises a glyph at `scale` & `position`, calling `o` for each pixel
+    /// of its `pixel_bounding_box`, see `PositionedGlyph::draw`.
+    fn draw(
+        &self,
+        id: GlyphId,
+        scale: Scale,
+        position: Point<f32>,
+        o: &mut dyn FnMut(u32, u32, f32),
+    ) {
+        let Some(bb) = self.pixel_bounding_box(id, scale, position) else {
+            return;
+        };
+        let width = bb.saturating_width() as u32;
+        let height = bb.saturating_height() as u32;
+        let mut rasterizer = OutlineRasterizer::new(width as _, height as _);
+        let offset = vector(bb.min.x as f32, bb.min.y as f32);
+        let mut outliner = OutlineTranslator::new(&mut rasterizer, position - offset);
+        self.build_outline(id, scale, &mut outliner);
+        rasterizer.rasterizer.for_each_pixel_2d(o);
+    }
+}
+
+/// Returns the factors scaling font units to pixels at `scale`.
+#[inline]
+fn scale_vector<F: FontApi + ?Sized>(font: &F, scale: Scale) -> crate::Vector<f32> {
+    let scale_y = font.scale_for_pixel_height(scale.y);
+    vector(scale_y * scale.x / scale.y, scale_y)
+}
+
+impl FontApi for Font<'_> {
+    #[inline]
+    fn units_per_em(&self) -> u16 {
+        Font::units_per_em(self)
+    }
+
+    #[inline]
+    fn glyph_count(&self) -> usize {
+        Font::glyph_count(self)
+    }
+
+    #[inline]
+    fn glyph_id(&self, c: char) -> GlyphId {
+        self.glyph(c).id()
+    }
+
+    #[inline]
+    fn v_metrics_unscaled(&self) -> VMetrics {
+        Font::v_metrics_unscaled(self)
+    }
+
+    fn glyph_h_metrics_unscaled(&self, id: GlyphId) -> HMetrics {
+        let inner = self.inner();
+        HMetrics {
+            advance_width: inner.glyph_hor_advance(id.into()).unwrap_or(0) as f32,
+            left_side_bearing: inner.glyph_hor_side_bearing(id.into()).unwrap_or(0) as f32,
+        }
+    }
+
+    #[inline]
+    fn pair_kerning_unscaled(&self, first: GlyphId, second: GlyphId) -> f32 {
+        f32::from(Font::pair_kerning_unscaled(self, first, second))
+    }
+
+    fn glyph_bounding_box_unscaled(&self, id: GlyphId) -> Option<Rect<f32>> {
+        let bb = self.inner().glyph_bounding_box(id.into())?;
+        Some(Rect {
+            min: point(bb.x_min as f32, bb.y_min as f32),
+            max: point(bb.x_max as f32, bb.y_max as f32),
+        })
+    }
+
+    #[inline]
+    fn build_outline_unscaled(&self, id: GlyphId, builder: &mut dyn OutlineBuilder) -> bool {
+        self.inner().outline_glyph(id.into(), builder).is_some()
+    }
+
+    #[inline]
+    fn scale_for_pixel_height(&self, height: f32) -> f32 {
+        Font::scale_for_pixel_height(self, height)
+    }
+}
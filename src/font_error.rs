@@ -0,0 +1,192 @@
+use crate::Font;
+use core::fmt;
+use owned_ttf_parser::{FaceParsingError, RawFace, Tag};
+
+/// Error loading a font, see `Font::from_bytes`, `Font::try_from_bytes_strict` &
+/// `Font::try_from_bytes_lenient`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FontError {
+    /// The data doesn't start with a known font or collection signature.
+    UnknownMagic,
+    /// The data is truncated or the table directory is invalid.
+    MalformedFont,
+    /// The collection has no font at the requested index.
+    FaceIndexOutOfBounds,
+    /// A table needed to render text is missing, by its tag, eg `"cmap"`.
+    MissingTable(&'static str),
+    /// A table is present but could not be parsed, by its tag.
+    MalformedTable(&'static str),
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            FontError::UnknownMagic => f.write_str("Unknown font data signature"),
+            FontError::MalformedFont => f.write_str("Malformed font data"),
+            FontError::FaceIndexOutOfBounds => f.write_str("Font index out of bounds"),
+            FontError::MissingTable(tag) => write!(f, "Missing `{tag}` table"),
+            FontError::MalformedTable(tag) => write!(f, "Malformed `{tag}` table"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FontError {}
+
+impl Font<'_> {
+    /// Creates a Font from byte-slice data, returning why loading failed for
+    /// invalid data.
+    ///
+    /// Fails if any table rusttype uses is present but malformed, or if the
+    /// `cmap` & `hmtx` tables or glyph outlines, `glyf` or `CFF `, needed to
+    /// render text are missing. Where `try_from_bytes` would silently ignore
+    /// such tables, drawing blank or `.notdef` glyphs, this allows rejecting
+    /// untrusted fonts, eg user uploads, with an actionable error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, FontError};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// assert!(Font::try_from_bytes_strict(font_data).is_ok());
+    ///
+    /// let truncated = &font_data[..font_data.len() / 2];
+    /// assert!(Font::try_from_bytes_strict(truncated).is_err());
+    /// ```
+    pub fn try_from_bytes_strict(bytes: &[u8]) -> Result<Font<'_>, FontError> {
+        let font = Self::from_bytes(bytes)?;
+        font.check_tables(true)?;
+        Ok(font)
+    }
+
+    /// Creates a Font from byte-slice data, returning why loading failed for
+    /// invalid data.
+    ///
+    /// Like `try_from_bytes_strict` fails if the `cmap` & `hmtx` tables or
+    /// glyph outlines needed to render text are missing or malformed. Other
+    /// tables, eg `kern`, `GPOS` or `OS/2`, may be missing or malformed,
+    /// degrading as `try_from_bytes` does, eg without kerning.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, FontError};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// assert!(Font::try_from_bytes_lenient(font_data).is_ok());
+    /// assert_eq!(
+    ///     Font::try_from_bytes_lenient(b"not a font").unwrap_err(),
+    ///     FontError::UnknownMagic,
+    /// );
+    /// ```
+    pub fn try_from_bytes_lenient(bytes: &[u8]) -> Result<Font<'_>, FontError> {
+        let font = Self::from_bytes(bytes)?;
+        font.check_tables(false)?;
+        Ok(font)
+    }
+
+    /// Checks tables needed to render text are present & parsed, and if
+    /// `strict` that no other table is malformed.
+    fn check_tables(&self, strict: bool) -> Result<(), FontError> {
+        let face = self.inner();
+        let raw = face.raw_face();
+        let tables = face.tables();
+        let present = |tag| has_record(raw, tag);
+
+        let essential = [
+            ("cmap", tables.cmap.is_some()),
+            ("hmtx", tables.hmtx.is_some()),
+            ("glyf", tables.glyf.is_some()),
+            ("CFF ", tables.cff.is_some()),
+        ];
+        if let Some(&(tag, _)) = essential.iter().find(|&&(tag, ok)| !ok && present(tag)) {
+            return Err(FontError::MalformedTable(tag));
+        }
+
+        let optional = [
+            ("kern", tables.kern.is_some()),
+            ("OS/2", tables.os2.is_some()),
+            ("post", tables.post.is_some()),
+            ("name", tables.name.is_some()),
+            ("vhea", tables.vhea.is_some()),
+            ("vmtx", tables.vmtx.is_some()),
+            ("GDEF", tables.gdef.is_some()),
+            ("GPOS", tables.gpos.is_some()),
+            ("GSUB", tables.gsub.is_some()),
+        ];
+        let malformed = optional.iter().find(|&&(tag, ok)| !ok && present(tag));
+        if let Some(&(tag, _)) = malformed.filter(|_| strict) {
+            return Err(FontError::MalformedTable(tag));
+        }
+
+        for tag in ["cmap", "hmtx"] {
+            if !present(tag) {
+                return Err(FontError::MissingTable(tag));
+            }
+        }
+        if !present("glyf") && !present("CFF ") && !present("CFF2") {
+            return Err(FontError::MissingTable("glyf"));
+        }
+        Ok(())
+    }
+}
+
+/// Converts a ttf-parser error, distinguishing missing & malformed required
+/// tables.
+pub(crate) fn from_parsing_error(bytes: &[u8], index: u32, err: FaceParsingError) -> FontError {
+    RequiredRecords::scan(bytes, index).classify(err)
+}
+
+/// Which required tables the table directory lists, scanned before the data
+/// is consumed by a parse that may fail.
+#[derive(Copy, Clone)]
+pub(crate) struct RequiredRecords {
+    head: bool,
+    hhea: bool,
+    maxp: bool,
+}
+
+impl RequiredRecords {
+    pub(crate) fn scan(bytes: &[u8], index: u32) -> Self {
+        match RawFace::parse(bytes, index) {
+            Ok(raw) => Self {
+                head: has_record(&raw, "head"),
+                hhea: has_record(&raw, "hhea"),
+                maxp: has_record(&raw, "maxp"),
+            },
+            Err(_) => Self {
+                head: false,
+                hhea: false,
+                maxp: false,
+            },
+        }
+    }
+
+    /// Converts a ttf-parser error, reporting required tables listed in the
+    /// table directory as malformed, otherwise missing.
+    pub(crate) fn classify(self, err: FaceParsingError) -> FontError {
+        let required = |tag, present| {
+            if present {
+                FontError::MalformedTable(tag)
+            } else {
+                FontError::MissingTable(tag)
+            }
+        };
+        match err {
+            FaceParsingError::MalformedFont => FontError::MalformedFont,
+            FaceParsingError::UnknownMagic => FontError::UnknownMagic,
+            FaceParsingError::FaceIndexOutOfBounds => FontError::FaceIndexOutOfBounds,
+            FaceParsingError::NoHeadTable => required("head", self.head),
+            FaceParsingError::NoHheaTable => required("hhea", self.hhea),
+            FaceParsingError::NoMaxpTable => required("maxp", self.maxp),
+        }
+    }
+}
+
+/// Returns whether the table directory lists the table, even if its data is
+/// out of bounds.
+fn has_record(raw: &RawFace<'_>, tag: &str) -> bool {
+    let tag = Tag::from_bytes_lossy(tag.as_bytes());
+    raw.table_records
+        .into_iter()
+        .any(|record| record.tag == tag)
+}
@@ -156,3 +156,53 @@ impl<N: ops::Sub<Output = N> + Copy> Rect<N> {
         self.max.y - self.min.y
     }
 }
+
+/// Overflow safe variants of `width` & `height`. Pixel bounding boxes derived
+/// from corrupt or adversarial font data may have extreme coordinates, where
+/// `width` & `height` would overflow.
+///
+/// ```
+/// # use rusttype::*;
+/// let rect = Rect {
+///     min: point(i32::MIN, 0),
+///     max: point(i32::MAX, 10),
+/// };
+/// assert_eq!(rect.checked_width(), None);
+/// assert_eq!(rect.saturating_width(), i32::MAX);
+/// assert_eq!(rect.checked_height(), Some(10));
+/// ```
+impl Rect<i32> {
+    /// Returns the width, or `None` if it would overflow.
+    #[inline]
+    pub fn checked_width(&self) -> Option<i32> {
+        self.max.x.checked_sub(self.min.x)
+    }
+
+    /// Returns the height, or `None` if it would overflow.
+    #[inline]
+    pub fn checked_height(&self) -> Option<i32> {
+        self.max.y.checked_sub(self.min.y)
+    }
+
+    /// Returns the width, saturating at the numeric bounds.
+    #[inline]
+    pub fn saturating_width(&self) -> i32 {
+        self.max.x.saturating_sub(self.min.x)
+    }
+
+    /// Returns the height, saturating at the numeric bounds.
+    #[inline]
+    pub fn saturating_height(&self) -> i32 {
+        self.max.y.saturating_sub(self.min.y)
+    }
+
+    /// Returns this rect translated by `v`, saturating at the numeric bounds.
+    #[inline]
+    pub(crate) fn saturating_translate(self, v: Vector<i32>) -> Self {
+        let translate = |p: Point<i32>| point(p.x.saturating_add(v.x), p.y.saturating_add(v.y));
+        Rect {
+            min: translate(self.min),
+            max: translate(self.max),
+        }
+    }
+}
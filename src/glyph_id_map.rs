@@ -0,0 +1,96 @@
+use crate::GlyphId;
+use alloc::collections::BTreeMap;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// A mapping from the glyph ids of a subset font to the ids of the same
+/// glyphs in the original font it was subset from.
+///
+/// Subsetting tools generally assign new, compact glyph ids, and regenerating
+/// a subset with a different set of glyphs can give the same glyph a different
+/// id. Translating ids back to the original font keeps them stable across
+/// subsets, e.g. for use as glyph cache keys.
+///
+/// A subset font has at most 65536 glyphs, so original ids past that many
+/// are ignored.
+///
+/// # Example
+///
+/// ```
+/// # use rusttype::{GlyphId, GlyphIdMap};
+/// // subset glyph 0 is original glyph 0, 1 is 36, 2 is 68
+/// let map = GlyphIdMap::new(vec![GlyphId(0), GlyphId(36), GlyphId(68)]);
+///
+/// assert_eq!(map.original_id(GlyphId(2)), Some(GlyphId(68)));
+/// assert_eq!(map.original_id(GlyphId(3)), None);
+/// assert_eq!(map.subset_id(GlyphId(36)), Some(GlyphId(1)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct GlyphIdMap {
+    /// Original ids indexed by subset id.
+    original: Vec<GlyphId>,
+    /// Lowest subset id of each original id.
+    subset: BTreeMap<GlyphId, GlyphId>,
+}
+
+impl GlyphIdMap {
+    /// Creates a mapping from a list of original glyph ids, indexed by subset
+    /// glyph id.
+    pub fn new(mut original_ids: Vec<GlyphId>) -> Self {
+        // subset ids are u16
+        original_ids.truncate(usize::from(u16::MAX) + 1);
+        let mut subset = BTreeMap::new();
+        for (idx, id) in original_ids.iter().enumerate() {
+            let Ok(idx) = u16::try_from(idx) else {
+                break;
+            };
+            subset.entry(*id).or_insert(GlyphId(idx));
+        }
+        Self {
+            original: original_ids,
+            subset,
+        }
+    }
+
+    /// Returns the original font glyph id for a subset font glyph id, if
+    /// mapped.
+    #[inline]
+    pub fn original_id(&self, subset_id: GlyphId) -> Option<GlyphId> {
+        self.original.get(subset_id.0 as usize).copied()
+    }
+
+    /// Returns the subset font glyph id for an original font glyph id, if the
+    /// subset contains it.
+    pub fn subset_id(&self, original_id: GlyphId) -> Option<GlyphId> {
+        self.subset.get(&original_id).copied()
+    }
+
+    /// The number of mapped subset glyph ids.
+    pub fn len(&self) -> usize {
+        self.original.len()
+    }
+
+    /// Returns `true` if there are no mapped ids.
+    pub fn is_empty(&self) -> bool {
+        self.original.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ids_past_u16_ignored() {
+        // a full subset, reversed, followed by ids past u16::MAX
+        let original: Vec<_> = (0..=u16::MAX)
+            .map(|id| GlyphId(u16::MAX - id))
+            .chain([GlyphId(1), GlyphId(2)])
+            .collect();
+        let map = GlyphIdMap::new(original);
+
+        assert_eq!(map.len(), usize::from(u16::MAX) + 1);
+        assert_eq!(map.subset_id(GlyphId(1)), Some(GlyphId(u16::MAX - 1)));
+        assert_eq!(map.original_id(GlyphId(u16::MAX)), Some(GlyphId(0)));
+    }
+}
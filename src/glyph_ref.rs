@@ -0,0 +1,475 @@
+use crate::{
+    point, vector, Font, FontCaches, Glyph, GlyphId, HMetrics, IntoGlyphId, OutlineBuilder,
+    PixelRounding, Point, PositionedGlyph, Rect, Scale, ScaledGlyph, Vector,
+};
+use core::fmt;
+
+#[cfg(all(feature = "libm-math", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+
+/// A glyph borrowing its font, see `Font::glyph_ref`.
+///
+/// Provides the same scaled & positioned chain as `Glyph` without cloning the
+/// `Font`, avoiding reference count traffic in tight loops. Convert to the
+/// owned equivalents with `to_glyph`, `to_scaled` & `to_positioned`.
+///
+/// # Example
+///
+/// ```
+/// # use rusttype::{point, Font, Scale};
+/// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+/// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+/// let scale = Scale::uniform(20.0);
+/// let width: f32 = "Hello"
+///     .chars()
+///     .map(|c| font.glyph_ref(c).scaled(scale).h_metrics().advance_width)
+///     .sum();
+///
+/// let glyph = font.glyph_ref('H').scaled(scale).positioned(point(0.0, 16.0));
+/// glyph.draw(|x, y, v| {});
+/// assert_eq!(glyph.to_positioned(), font.glyph('H').scaled(scale).positioned(point(0.0, 16.0)));
+/// ```
+#[derive(Copy, Clone)]
+pub struct GlyphRef<'a, 'font> {
+    pub(crate) font: &'a Font<'font>,
+    pub(crate) id: GlyphId,
+}
+
+impl<'a, 'font> GlyphRef<'a, 'font> {
+    /// The font to which this glyph belongs.
+    #[inline]
+    pub fn font(&self) -> &'a Font<'font> {
+        self.font
+    }
+
+    /// The glyph identifier for this glyph.
+    #[inline]
+    pub fn id(&self) -> GlyphId {
+        self.id
+    }
+
+    /// Augments this glyph with scaling information, see `Glyph::scaled`.
+    pub fn scaled(self, scale: Scale) -> ScaledGlyphRef<'a, 'font> {
+        let scale_y = self.font.scale_for_pixel_height(scale.y);
+        let scale_x = scale_y * scale.x / scale.y;
+        ScaledGlyphRef {
+            g: self,
+            api_scale: scale,
+            scale: vector(scale_x, scale_y),
+        }
+    }
+
+    /// The horizontal metrics of this glyph in font units, see
+    /// `Glyph::h_metrics_unscaled`.
+    pub fn h_metrics_unscaled(&self) -> HMetrics {
+        let inner = self.font.inner();
+        let id = self.id.into();
+        HMetrics {
+            advance_width: inner.glyph_hor_advance(id).map_or(0.0, f32::from),
+            left_side_bearing: inner.glyph_hor_side_bearing(id).map_or(0.0, f32::from),
+        }
+    }
+
+    /// The bounding box of the shape of this glyph in font units, see
+    /// `Glyph::bounding_box_unscaled`.
+    pub fn bounding_box_unscaled(&self) -> Option<Rect<f32>> {
+        let owned_ttf_parser::Rect {
+            x_min,
+            y_min,
+            x_max,
+            y_max,
+        } = self.font.inner().glyph_bounding_box(self.id.into())?;
+        Some(Rect {
+            min: point(f32::from(x_min), f32::from(y_min)),
+            max: point(f32::from(x_max), f32::from(y_max)),
+        })
+    }
+
+    /// Returns the owned equivalent, cloning the font.
+    pub fn to_glyph(&self) -> Glyph<'font> {
+        Glyph {
+            font: self.font.clone(),
+            id: self.id,
+        }
+    }
+}
+
+impl fmt::Debug for GlyphRef<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GlyphRef").field("id", &self.id.0).finish()
+    }
+}
+
+/// A `GlyphRef` augmented with scaling information, the borrowed equivalent
+/// of `ScaledGlyph`.
+#[derive(Copy, Clone)]
+pub struct ScaledGlyphRef<'a, 'font> {
+    g: GlyphRef<'a, 'font>,
+    api_scale: Scale,
+    scale: Vector<f32>,
+}
+
+impl<'a, 'font> ScaledGlyphRef<'a, 'font> {
+    /// The glyph identifier for this glyph.
+    #[inline]
+    pub fn id(&self) -> GlyphId {
+        self.g.id
+    }
+
+    /// The font to which this glyph belongs.
+    #[inline]
+    pub fn font(&self) -> &'a Font<'font> {
+        self.g.font
+    }
+
+    /// Removes the scaling from this glyph.
+    #[inline]
+    pub fn unscaled(&self) -> GlyphRef<'a, 'font> {
+        self.g
+    }
+
+    #[inline]
+    pub fn scale(&self) -> Scale {
+        self.api_scale
+    }
+
+    /// Builds the outline of the glyph, see `ScaledGlyph::build_outline`.
+    pub fn build_outline(&self, builder: &mut impl OutlineBuilder) -> bool {
+        let mut outliner =
+            crate::outliner::OutlineScaler::new(builder, vector(self.scale.x, -self.scale.y));
+
+        self.font()
+            .inner()
+            .outline_glyph(self.id().into(), &mut outliner)
+            .is_some()
+    }
+
+    /// Builds the outline of the glyph as `build_outline`, replaying the
+    /// font's parsed outline from `caches`, see `ScaledGlyph::build_outline_cached`.
+    pub fn build_outline_cached(
+        &self,
+        caches: &mut FontCaches<'font>,
+        builder: &mut impl OutlineBuilder,
+    ) -> bool {
+        if caches.outline_capacity() == 0 {
+            return self.build_outline(builder);
+        }
+        let Some(outline) = caches.outline(self.font(), self.id()) else {
+            return false;
+        };
+        let mut outliner =
+            crate::outliner::OutlineScaler::new(builder, vector(self.scale.x, -self.scale.y));
+        outline.build(&mut outliner);
+        true
+    }
+
+    /// Retrieves the "horizontal metrics" of this glyph, see
+    /// `ScaledGlyph::h_metrics`.
+    pub fn h_metrics(&self) -> HMetrics {
+        let HMetrics {
+            advance_width,
+            left_side_bearing,
+        } = self.g.h_metrics_unscaled();
+        HMetrics {
+            advance_width: advance_width * self.scale.x,
+            left_side_bearing: left_side_bearing * self.scale.x,
+        }
+    }
+
+    /// Retrieves the "horizontal metrics" of this glyph, see
+    /// `ScaledGlyph::try_h_metrics`.
+    pub fn try_h_metrics(&self) -> Option<HMetrics> {
+        let inner = self.font().inner();
+        let id = self.id().into();
+
+        let advance = inner.glyph_hor_advance(id)?;
+        let left_side_bearing = inner.glyph_hor_side_bearing(id).unwrap_or(0);
+
+        Some(HMetrics {
+            advance_width: advance as f32 * self.scale.x,
+            left_side_bearing: left_side_bearing as f32 * self.scale.x,
+        })
+    }
+
+    /// The bounding box of the shape of this glyph, see
+    /// `ScaledGlyph::exact_bounding_box`.
+    pub fn exact_bounding_box(&self) -> Option<Rect<f32>> {
+        let owned_ttf_parser::Rect {
+            x_min,
+            y_min,
+            x_max,
+            y_max,
+        } = self.font().inner().glyph_bounding_box(self.id().into())?;
+
+        Some(Rect {
+            min: point(x_min as f32 * self.scale.x, -y_max as f32 * self.scale.y),
+            max: point(x_max as f32 * self.scale.x, -y_min as f32 * self.scale.y),
+        })
+    }
+
+    /// The maximum pixel bounding box dimensions of this glyph over all
+    /// subpixel positions, see `ScaledGlyph::pixel_extents`.
+    pub fn pixel_extents(&self) -> Option<Vector<u32>> {
+        let Rect { min, max } = self.exact_bounding_box()?;
+        let extent = |exact: f32| (exact.ceil() as u32).saturating_add(1);
+        Some(vector(extent(max.x - min.x), extent(max.y - min.y)))
+    }
+
+    /// Augments this glyph with positioning information, see
+    /// `ScaledGlyph::positioned`.
+    pub fn positioned(self, p: Point<f32>) -> PositionedGlyphRef<'a, 'font> {
+        self.positioned_with_rounding(p, PixelRounding::default())
+    }
+
+    /// Augments this glyph with positioning information, see
+    /// `ScaledGlyph::positioned_with_rounding`.
+    pub fn positioned_with_rounding(
+        self,
+        p: Point<f32>,
+        rounding: PixelRounding,
+    ) -> PositionedGlyphRef<'a, 'font> {
+        let bb = self.pixel_bounds_at(p, rounding);
+        PositionedGlyphRef {
+            sg: self,
+            position: p,
+            rounding,
+            bb,
+        }
+    }
+
+    /// Returns the owned equivalent, cloning the font.
+    pub fn to_scaled(&self) -> ScaledGlyph<'font> {
+        ScaledGlyph {
+            g: self.g.to_glyph(),
+            api_scale: self.api_scale,
+            scale: self.scale,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn pixel_bounds_at(
+        &self,
+        p: Point<f32>,
+        rounding: PixelRounding,
+    ) -> Option<Rect<i32>> {
+        // Use subpixel fraction in floor/ceil rounding to eliminate rounding error
+        // from identical subpixel positions
+        let (x_trunc, x_fract) = (p.x.trunc() as i32, p.x.fract());
+        let (y_trunc, y_fract) = (p.y.trunc() as i32, p.y.fract());
+
+        let owned_ttf_parser::Rect {
+            x_min,
+            y_min,
+            x_max,
+            y_max,
+        } = self.font().inner().glyph_bounding_box(self.id().into())?;
+
+        let bb = Rect {
+            min: point(
+                rounding.round_min(x_min as f32 * self.scale.x + x_fract),
+                rounding.round_min(-y_max as f32 * self.scale.y + y_fract),
+            ),
+            max: point(
+                rounding.round_max(x_max as f32 * self.scale.x + x_fract),
+                rounding.round_max(-y_min as f32 * self.scale.y + y_fract),
+            ),
+        };
+        Some(bb.saturating_translate(vector(x_trunc, y_trunc)))
+    }
+}
+
+impl fmt::Debug for ScaledGlyphRef<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScaledGlyphRef")
+            .field("id", &self.id().0)
+            .field("scale", &self.api_scale)
+            .finish()
+    }
+}
+
+/// A `ScaledGlyphRef` augmented with positioning information, the borrowed
+/// equivalent of `PositionedGlyph`.
+#[derive(Copy, Clone)]
+pub struct PositionedGlyphRef<'a, 'font> {
+    sg: ScaledGlyphRef<'a, 'font>,
+    position: Point<f32>,
+    rounding: PixelRounding,
+    bb: Option<Rect<i32>>,
+}
+
+impl<'a, 'font> PositionedGlyphRef<'a, 'font> {
+    /// The glyph identifier for this glyph.
+    #[inline]
+    pub fn id(&self) -> GlyphId {
+        self.sg.id()
+    }
+
+    /// The font to which this glyph belongs.
+    #[inline]
+    pub fn font(&self) -> &'a Font<'font> {
+        self.sg.font()
+    }
+
+    /// Removes the positioning from this glyph.
+    #[inline]
+    pub fn unpositioned(&self) -> ScaledGlyphRef<'a, 'font> {
+        self.sg
+    }
+
+    /// The conservative pixel-boundary bounding box for this glyph, see
+    /// `PositionedGlyph::pixel_bounding_box`.
+    #[inline]
+    pub fn pixel_bounding_box(&self) -> Option<Rect<i32>> {
+        self.bb
+    }
+
+    #[inline]
+    pub fn scale(&self) -> Scale {
+        self.sg.api_scale
+    }
+
+    #[inline]
+    pub fn position(&self) -> Point<f32> {
+        self.position
+    }
+
+    /// The rounding mode used to calculate the pixel bounding box.
+    #[inline]
+    pub fn rounding(&self) -> PixelRounding {
+        self.rounding
+    }
+
+    /// Builds the outline of the glyph, see `PositionedGlyph::build_outline`.
+    pub fn build_outline(&self, builder: &mut impl OutlineBuilder) -> bool {
+        let bb = if let Some(bb) = self.bb.as_ref() {
+            bb
+        } else {
+            return false;
+        };
+
+        let offset = vector(bb.min.x as f32, bb.min.y as f32);
+
+        let mut outliner = crate::outliner::OutlineTranslator::new(builder, self.position - offset);
+
+        self.sg.build_outline(&mut outliner)
+    }
+
+    /// Builds the outline of the glyph, see
+    /// `PositionedGlyph::build_outline_cached`.
+    pub fn build_outline_cached(
+        &self,
+        caches: &mut FontCaches<'font>,
+        builder: &mut impl OutlineBuilder,
+    ) -> bool {
+        let Some(bb) = self.bb.as_ref() else {
+            return false;
+        };
+        let offset = vector(bb.min.x as f32, bb.min.y as f32);
+        let mut outliner = crate::outliner::OutlineTranslator::new(builder, self.position - offset);
+        self.sg.build_outline_cached(caches, &mut outliner)
+    }
+
+    /// Rasterises this glyph, see `PositionedGlyph::draw`.
+    pub fn draw<O: FnMut(u32, u32, f32)>(&self, o: O) {
+        self.draw_impl(o, |outliner| self.build_outline(outliner));
+    }
+
+    /// Rasterises this glyph, see `PositionedGlyph::draw_cached`.
+    pub fn draw_cached<O: FnMut(u32, u32, f32)>(&self, caches: &mut FontCaches<'font>, o: O) {
+        self.draw_impl(o, |outliner| self.build_outline_cached(caches, outliner));
+    }
+
+    fn draw_impl<O, B>(&self, o: O, build_outline: B)
+    where
+        O: FnMut(u32, u32, f32),
+        B: FnOnce(&mut crate::outliner::OutlineRasterizer) -> bool,
+    {
+        let bb = if let Some(bb) = self.bb.as_ref() {
+            bb
+        } else {
+            return;
+        };
+
+        let width = bb.saturating_width() as u32;
+        let height = bb.saturating_height() as u32;
+
+        let mut outliner = crate::outliner::OutlineRasterizer::new(width as _, height as _);
+
+        build_outline(&mut outliner);
+
+        outliner.rasterizer.for_each_pixel_2d(o);
+    }
+
+    /// Returns the owned equivalent, cloning the font.
+    pub fn to_positioned(&self) -> PositionedGlyph<'font> {
+        PositionedGlyph {
+            sg: self.sg.to_scaled(),
+            position: self.position,
+            rounding: self.rounding,
+            bb: self.bb,
+        }
+    }
+}
+
+impl fmt::Debug for PositionedGlyphRef<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PositionedGlyphRef")
+            .field("id", &self.id().0)
+            .field("scale", &self.scale())
+            .field("position", &self.position)
+            .finish()
+    }
+}
+
+impl<'font> Glyph<'font> {
+    /// Returns a borrowed view of this glyph.
+    #[inline]
+    pub(crate) fn as_glyph_ref(&self) -> GlyphRef<'_, 'font> {
+        GlyphRef {
+            font: &self.font,
+            id: self.id,
+        }
+    }
+}
+
+impl<'font> ScaledGlyph<'font> {
+    /// Returns a borrowed view of this glyph.
+    #[inline]
+    pub(crate) fn as_glyph_ref(&self) -> ScaledGlyphRef<'_, 'font> {
+        ScaledGlyphRef {
+            g: GlyphRef {
+                font: self.font(),
+                id: self.id(),
+            },
+            api_scale: self.api_scale,
+            scale: self.scale,
+        }
+    }
+}
+
+impl<'font> PositionedGlyph<'font> {
+    /// Returns a borrowed view of this glyph.
+    #[inline]
+    pub(crate) fn as_glyph_ref(&self) -> PositionedGlyphRef<'_, 'font> {
+        PositionedGlyphRef {
+            sg: self.sg.as_glyph_ref(),
+            position: self.position,
+            rounding: self.rounding,
+            bb: self.bb,
+        }
+    }
+}
+
+impl<'font> Font<'font> {
+    /// Returns the glyph for a Unicode code point or glyph id, borrowing
+    /// this font rather than cloning it like `glyph`. See `GlyphRef`.
+    ///
+    /// # Panics
+    ///
+    /// If `id` is a `GlyphId` that is not valid for this font, see `glyph`.
+    pub fn glyph_ref<C: IntoGlyphId>(&self, id: C) -> GlyphRef<'_, 'font> {
+        let id = id.into_glyph_id(self);
+        assert!((id.0 as usize) < self.glyph_count());
+        GlyphRef { font: self, id }
+    }
+}
@@ -68,13 +68,26 @@
 //! # Ok(())
 //! # }
 //! ```
-use crate::{point, vector, GlyphId, Point, PositionedGlyph, Rect, Vector};
+pub mod bench_support;
+#[cfg(feature = "image")]
+mod debug_image;
+mod sdf;
+mod snapshot;
+
+pub use snapshot::CacheSnapshot;
+
+use crate::{
+    point, vector, Font, GlyphId, GlyphIdMap, PixelRounding, Point, PositionedGlyph, Rect, Scale,
+    Vector,
+};
 use linked_hash_map::LinkedHashMap;
 use rustc_hash::{FxHashMap, FxHasher};
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::error;
 use std::fmt;
 use std::hash::BuildHasherDefault;
+use std::sync::{Arc, OnceLock};
 
 type FxBuildHasher = BuildHasherDefault<FxHasher>;
 
@@ -84,6 +97,101 @@ type FxBuildHasher = BuildHasherDefault<FxHasher>;
 pub type TextureCoords = (Rect<f32>, Rect<i32>);
 type FontId = usize;
 
+/// A small copyable key to look up a cached glyph with `Cache::rect_for_key`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CacheKey {
+    /// The `font_id` the glyph was queued with.
+    pub font_id: usize,
+    /// The glyph's id.
+    pub glyph_id: GlyphId,
+    /// The glyph's scale.
+    pub scale: Scale,
+    /// Subpixel offset of the glyph position, ie the fractional part.
+    pub offset: Vector<f32>,
+    /// Pixel bounding box rounding of the glyph.
+    pub rounding: PixelRounding,
+}
+
+impl CacheKey {
+    /// Returns the key for a positioned glyph queued with `font_id`.
+    pub fn from_glyph(font_id: usize, glyph: &PositionedGlyph<'_>) -> Self {
+        let position = glyph.position();
+        Self {
+            font_id,
+            glyph_id: glyph.id(),
+            scale: glyph.scale(),
+            offset: vector(position.x.fract(), position.y.fract()),
+            rounding: glyph.rounding(),
+        }
+    }
+}
+
+/// Information about a glyph removed from the cache, see
+/// `CacheBuilder::on_evict`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct EvictedGlyph {
+    /// The `font_id` the glyph was queued with.
+    pub font_id: usize,
+    /// The glyph's id, after any `Cache::set_glyph_id_map` mapping.
+    pub glyph_id: GlyphId,
+    /// Scale of the cached glyph, quantised by the cache's scale tolerance.
+    pub scale: Scale,
+    /// Normalised subpixel offset the glyph was rasterized at.
+    pub offset: Vector<f32>,
+    /// Texture region the glyph occupied, excluding any padding.
+    pub tex_coords: Rect<u32>,
+}
+
+type EvictCallback = Arc<dyn Fn(&EvictedGlyph) + Send + Sync>;
+
+/// Texture coordinates of a glyph queued with `Cache::queue_glyph_tagged`,
+/// see `Cache::tagged_rects`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TaggedRect {
+    /// The tag the glyph was queued with.
+    pub tag: u32,
+    /// Normalised texture coordinates of the glyph, as `Cache::rect_for`.
+    pub uv_rect: Rect<f32>,
+    /// Pixel-space coordinates to draw the glyph at, as `Cache::rect_for`.
+    pub screen_rect: Rect<i32>,
+}
+
+/// A cache texture slot & the glyph it holds, see `Cache::slot_changes_since`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GlyphSlot {
+    /// The cached glyph & its texture region, excluding any padding.
+    pub glyph: EvictedGlyph,
+    /// The `Cache::generation` the glyph was cached in.
+    pub generation: u64,
+}
+
+/// Texture slots changed since a generation, see `Cache::slot_changes_since`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SlotChanges {
+    /// The cache was cleared, so all slots from before are invalid.
+    pub reset: bool,
+    /// Slots holding glyphs cached since.
+    pub slots: Vec<GlyphSlot>,
+}
+
+/// Checksum of the pixels uploaded for a cached glyph, see
+/// `Cache::raster_checksum`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RasterChecksum {
+    /// Texture region of the upload, including any padding & alignment.
+    pub tex_rect: Rect<u32>,
+    /// `raster_checksum` of the uploaded row major pixels.
+    pub checksum: u32,
+}
+
+/// Returns the checksum used by `CacheBuilder::raster_checksums`, 32-bit
+/// FNV-1a, of row major pixel data.
+pub fn raster_checksum(data: &[u8]) -> u32 {
+    data.iter().fold(0x811c_9dc5, |hash, &byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
 /// Indicates where a glyph texture is stored in the cache
 /// (row position, glyph index in row)
 type TextureRowGlyphIndex = (u32, u32);
@@ -94,13 +202,15 @@ type TextureRowGlyphIndex = (u32, u32);
 struct LossyGlyphInfo {
     font_id: FontId,
     glyph_id: GlyphId,
-    /// x & y scales divided by `scale_tolerance` & rounded
+    /// x & y scales quantised per `scale_quantization`, by default divided by
+    /// `scale_tolerance` & rounded
     scale_over_tolerance: (u32, u32),
     /// Normalised subpixel positions divided by `position_tolerance` & rounded
     ///
     /// `u16` is enough as subpixel position `[-0.5, 0.5]` converted to `[0, 1]`
     ///  divided by the min `position_tolerance` (`0.001`) is small.
     offset_over_tolerance: (u16, u16),
+    rounding: PixelRounding,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -125,6 +235,15 @@ impl ByteArray2d {
         self.inner_array.as_slice()
     }
 
+    /// Writes row major `data` into the `rect` region.
+    fn write_rect(&mut self, rect: Rect<u32>, data: &[u8]) {
+        let width = rect.width() as usize;
+        for (y, data_row) in (rect.min.y as usize..).zip(data.chunks_exact(width)) {
+            let start = self.get_vec_index(y, rect.min.x as usize);
+            self.inner_array[start..start + width].copy_from_slice(data_row);
+        }
+    }
+
     #[inline]
     fn get_vec_index(&self, row: usize, col: usize) -> usize {
         debug_assert!(
@@ -173,20 +292,26 @@ struct GlyphTexInfo {
     glyph_info: LossyGlyphInfo,
     /// Actual (lossless) normalised subpixel offset of rasterized glyph
     offset: Vector<f32>,
+    /// Pixel bounds of the rasterized glyph positioned at `offset`
+    local_bb: Rect<i32>,
     tex_coords: Rect<u32>,
+    /// Checksum of the uploaded pixels, see `CacheBuilder::raster_checksums`
+    checksum: Option<RasterChecksum>,
+    /// `Cache::generation` the glyph was cached in
+    generation: u64,
 }
 
 trait PaddingAware {
-    fn unpadded(self) -> Self;
+    fn unpadded(self, padding: u32) -> Self;
 }
 
 impl PaddingAware for Rect<u32> {
-    /// A padded texture has 1 extra pixel on all sides
-    fn unpadded(mut self) -> Self {
-        self.min.x += 1;
-        self.min.y += 1;
-        self.max.x -= 1;
-        self.max.y -= 1;
+    /// A padded texture has `padding` extra pixels on all sides
+    fn unpadded(mut self, padding: u32) -> Self {
+        self.min.x += padding;
+        self.min.y += padding;
+        self.max.x -= padding;
+        self.max.y -= padding;
         self
     }
 }
@@ -195,6 +320,7 @@ impl PaddingAware for Rect<u32> {
 /// for more information.
 pub struct Cache<'font> {
     scale_tolerance: f32,
+    scale_quantization: ScaleQuantization,
     position_tolerance: f32,
     width: u32,
     height: u32,
@@ -203,11 +329,207 @@ pub struct Cache<'font> {
     space_start_for_end: FxHashMap<u32, u32>,
     /// Mapping of row gaps top -> bottom
     space_end_for_start: FxHashMap<u32, u32>,
-    queue: Vec<(FontId, PositionedGlyph<'font>)>,
+    /// Queued glyphs with the ids they are cached as, mapped when queued
+    queue: Vec<(FontId, GlyphId, PositionedGlyph<'font>)>,
+    /// Queue indices & tags of glyphs queued with `queue_glyph_tagged`
+    queue_tags: Vec<(usize, u32)>,
+    /// Rects of the tagged glyphs of the last cached queue
+    tagged_rects: Vec<TaggedRect>,
     all_glyphs: FxHashMap<LossyGlyphInfo, TextureRowGlyphIndex>,
-    pad_glyphs: bool,
+    /// Per font mappings of subset glyph ids to original font ids
+    glyph_id_maps: FxHashMap<FontId, GlyphIdMap>,
+    /// Fonts of glyphs queued with `queue_glyph_with_data_id`, held so their
+    /// data ids aren't reused while their glyphs are cached
+    data_id_fonts: FxHashMap<FontId, Font<'font>>,
+    glyph_padding: u32,
     align_4x4: bool,
     multithread: bool,
+    multithread_threshold: usize,
+    ignore_subpixel: bool,
+    upload_order: UploadOrder,
+    coverage_curve: CoverageCurve,
+    /// `coverage_curve` lookup table
+    coverage_lut: [u8; 256],
+    content: Content,
+    /// CPU side copy of the cache texture, if retained
+    texture: Option<ByteArray2d>,
+    raster_checksums: bool,
+    expected_glyph_count: usize,
+    on_evict: Option<EvictCallback>,
+    /// Incremented by each `cache_queued` & `clear`
+    generation: u64,
+    /// Generation of the last `clear`
+    cleared_generation: u64,
+    /// Snapshot of the current glyphs, taken on first use
+    snapshot: OnceLock<CacheSnapshot>,
+}
+
+/// Returns the id `glyph_id` of `font_id` is cached as, see
+/// `Cache::set_glyph_id_map`. Ids the map doesn't cover are cached as is.
+fn original_id(
+    glyph_id_maps: &FxHashMap<FontId, GlyphIdMap>,
+    font_id: FontId,
+    glyph_id: GlyphId,
+) -> GlyphId {
+    glyph_id_maps
+        .get(&font_id)
+        .and_then(|map| map.original_id(glyph_id))
+        .unwrap_or(glyph_id)
+}
+
+/// The `Cache` settings deciding which glyphs share a cached texture, shared
+/// with `CacheSnapshot` lookups.
+#[derive(Copy, Clone, Debug)]
+struct KeySettings {
+    scale_tolerance: f32,
+    scale_quantization: ScaleQuantization,
+    position_tolerance: f32,
+    ignore_subpixel: bool,
+    content: Content,
+}
+
+impl KeySettings {
+    /// Returns the step of `scale` according to `scale_tolerance` &
+    /// `scale_quantization`, or the power of two step for `Content::Sdf`.
+    fn quantize_scale(&self, scale: f32) -> u32 {
+        if let Content::Sdf { .. } = self.content {
+            let scale = scale.max(PERCEPTUAL_MIN_SCALE);
+            return ((scale / PERCEPTUAL_MIN_SCALE).log2() + 0.5) as u32;
+        }
+        match self.scale_quantization {
+            ScaleQuantization::Linear => (scale / self.scale_tolerance + 0.5) as u32,
+            ScaleQuantization::Perceptual => {
+                // even steps of log scale, each `tolerance / reference` relative
+                let log_step = (self.scale_tolerance / PERCEPTUAL_REFERENCE_SCALE).ln_1p();
+                let scale = scale.max(PERCEPTUAL_MIN_SCALE);
+                ((scale / PERCEPTUAL_MIN_SCALE).ln() / log_step + 0.5) as u32
+            }
+        }
+    }
+
+    /// Returns the scale at the centre of a `quantize_scale` step.
+    fn dequantize_scale(&self, step: u32) -> f32 {
+        if let Content::Sdf { .. } = self.content {
+            return PERCEPTUAL_MIN_SCALE * (step as f32).exp2();
+        }
+        match self.scale_quantization {
+            ScaleQuantization::Linear => step as f32 * self.scale_tolerance,
+            ScaleQuantization::Perceptual => {
+                let log_step = (self.scale_tolerance / PERCEPTUAL_REFERENCE_SCALE).ln_1p();
+                PERCEPTUAL_MIN_SCALE * (step as f32 * log_step).exp()
+            }
+        }
+    }
+
+    /// Returns the subpixel offset glyphs at `position` are keyed by, zero
+    /// when subpixel positions are ignored.
+    fn key_offset(&self, position: Point<f32>) -> Vector<f32> {
+        if self.ignore_subpixel || self.content != Content::Coverage {
+            vector(0.0, 0.0)
+        } else {
+            normalised_offset_from_position(position)
+        }
+    }
+
+    /// Returns the key of `glyph`, cached as `glyph_id`.
+    fn lossy_info_for(
+        &self,
+        font_id: FontId,
+        glyph_id: GlyphId,
+        glyph: &PositionedGlyph<'_>,
+    ) -> LossyGlyphInfo {
+        self.lossy_info(
+            font_id,
+            glyph_id,
+            glyph.scale(),
+            self.key_offset(glyph.position()),
+            glyph.rounding(),
+        )
+    }
+
+    /// Returns the pixel-space bounds to draw the cached texture of `info`
+    /// for `glyph`, rasterized at `tex_offset`.
+    fn screen_bounds_for(
+        &self,
+        glyph: &PositionedGlyph<'_>,
+        info: &LossyGlyphInfo,
+        tex_offset: Vector<f32>,
+    ) -> Rect<i32> {
+        let local_bb = local_bounds(glyph, tex_offset);
+        let bb = screen_bounds(local_bb, tex_offset, glyph.position());
+        match self.content {
+            Content::Coverage => bb,
+            Content::Sdf { .. } => {
+                // the distance field margin, scaled as the glyph
+                let margin = self.content.margin() as f32;
+                let (step_x, step_y) = info.scale_over_tolerance;
+                let scale = glyph.scale();
+                let margin_x = (margin * scale.x / self.dequantize_scale(step_x)).round() as i32;
+                let margin_y = (margin * scale.y / self.dequantize_scale(step_y)).round() as i32;
+                Rect {
+                    min: point(
+                        bb.min.x.saturating_sub(margin_x),
+                        bb.min.y.saturating_sub(margin_y),
+                    ),
+                    max: point(
+                        bb.max.x.saturating_add(margin_x),
+                        bb.max.y.saturating_add(margin_y),
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Returns the glyph to rasterize for the texture of `info`, the glyph
+    /// itself unless caching distance fields at its power of two scale.
+    ///
+    /// `None` if the glyph has no pixels at the cached scale, such glyphs are
+    /// not cached & have no rect, like empty glyphs.
+    fn raster_glyph<'a, 'font>(
+        &self,
+        glyph: &'a PositionedGlyph<'font>,
+        info: &LossyGlyphInfo,
+    ) -> Option<Cow<'a, PositionedGlyph<'font>>> {
+        if self.content == Content::Coverage {
+            return Some(Cow::Borrowed(glyph));
+        }
+        let (step_x, step_y) = info.scale_over_tolerance;
+        let scale = Scale {
+            x: self.dequantize_scale(step_x),
+            y: self.dequantize_scale(step_y),
+        };
+        let raster = glyph
+            .unpositioned()
+            .unscaled()
+            .clone()
+            .scaled(scale)
+            .positioned_with_rounding(point(0.0, 0.0), glyph.rounding());
+        raster
+            .pixel_bounding_box()
+            .filter(|bb| bb.width() > 0 && bb.height() > 0)?;
+        Some(Cow::Owned(raster))
+    }
+
+    fn lossy_info(
+        &self,
+        font_id: FontId,
+        glyph_id: GlyphId,
+        scale: Scale,
+        offset: Vector<f32>,
+        rounding: PixelRounding,
+    ) -> LossyGlyphInfo {
+        LossyGlyphInfo {
+            font_id,
+            glyph_id,
+            scale_over_tolerance: (self.quantize_scale(scale.x), self.quantize_scale(scale.y)),
+            // convert [-0.5, 0.5] -> [0, 1] then divide
+            offset_over_tolerance: (
+                ((offset.x + 0.5) / self.position_tolerance + 0.5) as u16,
+                ((offset.y + 0.5) / self.position_tolerance + 0.5) as u16,
+            ),
+            rounding,
+        }
+    }
 }
 
 /// Builder & rebuilder for `Cache`.
@@ -215,30 +537,72 @@ pub struct Cache<'font> {
 /// # Example
 ///
 /// ```
-/// use rusttype::gpu_cache::Cache;
+/// use rusttype::gpu_cache::{Cache, Content, CoverageCurve, ScaleQuantization, UploadOrder};
 ///
 /// // Create a cache with all default values set explicitly
 /// // equivalent to `Cache::builder().build()`
 /// let default_cache = Cache::builder()
 ///     .dimensions(256, 256)
 ///     .scale_tolerance(0.1)
+///     .scale_quantization(ScaleQuantization::Linear)
 ///     .position_tolerance(0.1)
-///     .pad_glyphs(true)
+///     .glyph_padding(1)
 ///     .align_4x4(false)
 ///     .multithread(true)
+///     .multithread_threshold(2)
+///     .ignore_subpixel(false)
+///     .upload_order(UploadOrder::Packing)
+///     .coverage_curve(CoverageCurve::Linear)
+///     .content(Content::Coverage)
+///     .retain_texture(false)
+///     .raster_checksums(false)
+///     .expected_glyph_count(0)
 ///     .build();
 ///
 /// // Create a cache with all default values, except with a dimension of 1024x1024
 /// let bigger_cache = Cache::builder().dimensions(1024, 1024).build();
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct CacheBuilder {
     dimensions: (u32, u32),
     scale_tolerance: f32,
+    scale_quantization: ScaleQuantization,
     position_tolerance: f32,
-    pad_glyphs: bool,
+    glyph_padding: u32,
     align_4x4: bool,
     multithread: bool,
+    multithread_threshold: usize,
+    ignore_subpixel: bool,
+    upload_order: UploadOrder,
+    coverage_curve: CoverageCurve,
+    content: Content,
+    retain_texture: bool,
+    raster_checksums: bool,
+    expected_glyph_count: usize,
+    on_evict: Option<EvictCallback>,
+}
+
+impl fmt::Debug for CacheBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CacheBuilder")
+            .field("dimensions", &self.dimensions)
+            .field("scale_tolerance", &self.scale_tolerance)
+            .field("scale_quantization", &self.scale_quantization)
+            .field("position_tolerance", &self.position_tolerance)
+            .field("glyph_padding", &self.glyph_padding)
+            .field("align_4x4", &self.align_4x4)
+            .field("multithread", &self.multithread)
+            .field("multithread_threshold", &self.multithread_threshold)
+            .field("ignore_subpixel", &self.ignore_subpixel)
+            .field("upload_order", &self.upload_order)
+            .field("coverage_curve", &self.coverage_curve)
+            .field("content", &self.content)
+            .field("retain_texture", &self.retain_texture)
+            .field("raster_checksums", &self.raster_checksums)
+            .field("expected_glyph_count", &self.expected_glyph_count)
+            .field("on_evict", &self.on_evict.is_some())
+            .finish()
+    }
 }
 
 impl Default for CacheBuilder {
@@ -246,10 +610,20 @@ impl Default for CacheBuilder {
         Self {
             dimensions: (256, 256),
             scale_tolerance: 0.1,
+            scale_quantization: ScaleQuantization::Linear,
             position_tolerance: 0.1,
-            pad_glyphs: true,
+            glyph_padding: 1,
             align_4x4: false,
             multithread: true,
+            multithread_threshold: 2,
+            ignore_subpixel: false,
+            upload_order: UploadOrder::Packing,
+            coverage_curve: CoverageCurve::Linear,
+            content: Content::Coverage,
+            retain_texture: false,
+            raster_checksums: false,
+            expected_glyph_count: 0,
+            on_evict: None,
         }
     }
 }
@@ -299,6 +673,24 @@ impl CacheBuilder {
         self.scale_tolerance = scale_tolerance.into();
         self
     }
+    /// How `scale_tolerance` applies at different scales.
+    ///
+    /// `ScaleQuantization::Perceptual` scales the tolerance with glyph size,
+    /// reducing the glyph variants cached while zooming text without losing
+    /// quality at small sizes.
+    ///
+    /// # Example (set to default value)
+    ///
+    /// ```
+    /// # use rusttype::gpu_cache::{Cache, ScaleQuantization};
+    /// let cache = Cache::builder()
+    ///     .scale_quantization(ScaleQuantization::Linear)
+    ///     .build();
+    /// ```
+    pub fn scale_quantization(mut self, scale_quantization: ScaleQuantization) -> Self {
+        self.scale_quantization = scale_quantization;
+        self
+    }
     /// Specifies the tolerances (maximum allowed difference) for judging
     /// whether an existing glyph in the cache is close enough to the requested
     /// glyph in subpixel offset to be used in its place. Due to floating
@@ -342,7 +734,26 @@ impl CacheBuilder {
     /// let cache = Cache::builder().pad_glyphs(true).build();
     /// ```
     pub fn pad_glyphs(mut self, pad_glyphs: bool) -> Self {
-        self.pad_glyphs = pad_glyphs;
+        self.glyph_padding = pad_glyphs as u32;
+        self
+    }
+    /// Pack glyphs in texture with a padding of `glyph_padding` zero alpha
+    /// pixels on all sides. `pad_glyphs(true)` is equivalent to
+    /// `glyph_padding(1)` & `pad_glyphs(false)` to `glyph_padding(0)`.
+    ///
+    /// A single pixel avoids bleeding from bilinear texture lookups. More
+    /// padding is needed when the cache texture is sampled with mipmapping, or
+    /// otherwise scaled down, as each halving of resolution doubles the
+    /// sampled footprint.
+    ///
+    /// # Example (set to default value)
+    ///
+    /// ```
+    /// # use rusttype::gpu_cache::Cache;
+    /// let cache = Cache::builder().glyph_padding(1).build();
+    /// ```
+    pub fn glyph_padding(mut self, glyph_padding: u32) -> Self {
+        self.glyph_padding = glyph_padding;
         self
     }
     /// Align glyphs in texture to 4x4 texel boundaries.
@@ -380,9 +791,187 @@ impl CacheBuilder {
         self
     }
 
+    /// The minimum number of glyphs that must need rasterizing in a single
+    /// `cache_queued` call for work to be spread across multiple threads, when
+    /// `multithread` is enabled. Below this the overhead of starting threads
+    /// outweighs the benefit. Values below `2` are treated as `2`.
+    ///
+    /// Regardless of threading, uploads are always made in the same order.
+    ///
+    /// # Example (set to default value)
+    ///
+    /// ```
+    /// # use rusttype::gpu_cache::Cache;
+    /// let cache = Cache::builder().multithread_threshold(2).build();
+    /// ```
+    pub fn multithread_threshold(mut self, multithread_threshold: usize) -> Self {
+        self.multithread_threshold = multithread_threshold;
+        self
+    }
+
+    /// Ignore subpixel positions, rasterizing & caching all glyphs at integer
+    /// pixel positions. Glyph positions are rounded to the nearest pixel when
+    /// queued.
+    ///
+    /// Suitable for pixel-aligned text, e.g. terminals & pixel-art UIs, which
+    /// never need subpixel variants. Cheaper than setting a
+    /// `position_tolerance` of `1.0` or more, which still rasterizes glyphs at
+    /// subpixel offsets.
+    ///
+    /// Glyphs are keyed by their font, id & scale with a fixed zero offset, so
+    /// cache keys stay the same size; the saving is in rasterization & offset
+    /// math, not key memory.
+    ///
+    /// # Example (set to default value)
+    ///
+    /// ```
+    /// # use rusttype::gpu_cache::Cache;
+    /// let cache = Cache::builder().ignore_subpixel(false).build();
+    /// ```
+    pub fn ignore_subpixel(mut self, ignore_subpixel: bool) -> Self {
+        self.ignore_subpixel = ignore_subpixel;
+        self
+    }
+
+    /// The order in which `cache_queued` makes texture uploads.
+    ///
+    /// `UploadOrder::Row` suits upload batching schemes that require
+    /// uploads sorted by texture row.
+    ///
+    /// # Example (set to default value)
+    ///
+    /// ```
+    /// # use rusttype::gpu_cache::{Cache, UploadOrder};
+    /// let cache = Cache::builder().upload_order(UploadOrder::Packing).build();
+    /// ```
+    pub fn upload_order(mut self, upload_order: UploadOrder) -> Self {
+        self.upload_order = upload_order;
+        self
+    }
+
+    /// The mapping of rasterized glyph coverage, `0.0..=1.0`, to the texture
+    /// bytes uploaded by `cache_queued`.
+    ///
+    /// The encoding of alpha noticeably changes the perceived weight of text,
+    /// so should match how the texture is sampled & blended, eg use
+    /// `CoverageCurve::Srgb` when sampling from an sRGB texture format.
+    ///
+    /// # Example (set to default value)
+    ///
+    /// ```
+    /// # use rusttype::gpu_cache::{Cache, CoverageCurve};
+    /// let cache = Cache::builder().coverage_curve(CoverageCurve::Linear).build();
+    /// ```
+    pub fn coverage_curve(mut self, coverage_curve: CoverageCurve) -> Self {
+        self.coverage_curve = coverage_curve;
+        self
+    }
+
+    /// What cached glyph textures contain, coverage or signed distance fields.
+    ///
+    /// With `Content::Sdf` glyphs are cached once per power of two scale
+    /// range, without subpixel offsets, as distance fields scale & move
+    /// smoothly, so one texture serves all nearby sizes. `scale_tolerance`,
+    /// `scale_quantization`, `position_tolerance` & `coverage_curve` don't
+    /// apply. Screen rects from `rect_for` include the distance field margin
+    /// around the glyph.
+    ///
+    /// # Example (set to default value)
+    ///
+    /// ```
+    /// # use rusttype::gpu_cache::{Cache, Content};
+    /// let cache = Cache::builder().content(Content::Coverage).build();
+    /// ```
+    pub fn content(mut self, content: Content) -> Self {
+        self.content = content;
+        self
+    }
+
+    /// Retain a CPU side copy of the cache texture contents, available with
+    /// `Cache::texture_snapshot` & `Cache::texture_data`.
+    ///
+    /// This allows saving the texture for debugging, or re-uploading it after
+    /// GPU texture loss (e.g. a lost graphics device) without clearing the
+    /// cache and rasterizing every glyph again. Costs memory equal to the
+    /// cache texture size.
+    ///
+    /// # Example (set to default value)
+    ///
+    /// ```
+    /// # use rusttype::gpu_cache::Cache;
+    /// let cache = Cache::builder().retain_texture(false).build();
+    /// ```
+    pub fn retain_texture(mut self, retain_texture: bool) -> Self {
+        self.retain_texture = retain_texture;
+        self
+    }
+
+    /// Compute & retain a checksum of the pixels uploaded for each cached
+    /// glyph, available with `Cache::raster_checksum`.
+    ///
+    /// When debugging texture atlas corruption, eg the wrong glyph appearing,
+    /// compare these with `raster_checksum` of the region read back from the
+    /// GPU texture to tell whether the CPU side pixels or the upload path is
+    /// at fault. Costs a pass over the pixels of each upload.
+    ///
+    /// # Example (set to default value)
+    ///
+    /// ```
+    /// # use rusttype::gpu_cache::Cache;
+    /// let cache = Cache::builder().raster_checksums(false).build();
+    /// ```
+    pub fn raster_checksums(mut self, raster_checksums: bool) -> Self {
+        self.raster_checksums = raster_checksums;
+        self
+    }
+
+    /// The number of glyphs the cache is expected to hold, so storage for
+    /// them is allocated up front.
+    ///
+    /// Without this, storage grows as glyphs are cached, rehashing lookup
+    /// tables as they grow, which can cause frame time spikes when caching
+    /// thousands of glyphs at once, eg a page of CJK text.
+    ///
+    /// # Example (set to default value)
+    ///
+    /// ```
+    /// # use rusttype::gpu_cache::Cache;
+    /// let cache = Cache::builder().expected_glyph_count(0).build();
+    /// ```
+    pub fn expected_glyph_count(mut self, expected_glyph_count: usize) -> Self {
+        self.expected_glyph_count = expected_glyph_count;
+        self
+    }
+
+    /// Sets a callback to be invoked for each glyph removed from the cache.
+    ///
+    /// Glyphs are removed when their rows are recycled to make room for newly
+    /// queued glyphs, and when the cache is cleared (including the clear
+    /// before a `CachedBy::Reordering`). Renderers keeping derived data per
+    /// cached glyph can use this to invalidate it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::gpu_cache::Cache;
+    /// let cache = Cache::builder()
+    ///     .on_evict(|evicted| println!("evicted {:?}", evicted.glyph_id))
+    ///     .build();
+    /// ```
+    pub fn on_evict<F>(mut self, on_evict: F) -> Self
+    where
+        F: Fn(&EvictedGlyph) + Send + Sync + 'static,
+    {
+        self.on_evict = Some(Arc::new(on_evict));
+        self
+    }
+
     fn validated(self) -> Self {
         assert!(self.scale_tolerance >= 0.0);
         assert!(self.position_tolerance >= 0.0);
+        if let Content::Sdf { spread } = self.content {
+            assert!(spread.is_finite() && spread > 0.0);
+        }
         let scale_tolerance = self.scale_tolerance.max(0.001);
         let position_tolerance = self.position_tolerance.max(0.001);
         #[cfg(not(target_arch = "wasm32"))]
@@ -402,7 +991,7 @@ impl CacheBuilder {
     /// # Panics
     ///
     /// `scale_tolerance` or `position_tolerance` are less than or equal to
-    /// zero.
+    /// zero, or a `Content::Sdf` spread is not positive & finite.
     ///
     /// # Example
     ///
@@ -414,14 +1003,25 @@ impl CacheBuilder {
         let CacheBuilder {
             dimensions: (width, height),
             scale_tolerance,
+            scale_quantization,
             position_tolerance,
-            pad_glyphs,
+            glyph_padding,
             align_4x4,
             multithread,
+            multithread_threshold,
+            ignore_subpixel,
+            upload_order,
+            coverage_curve,
+            content,
+            retain_texture,
+            raster_checksums,
+            expected_glyph_count,
+            on_evict,
         } = self.validated();
 
         Cache {
             scale_tolerance,
+            scale_quantization,
             position_tolerance,
             width,
             height,
@@ -436,11 +1036,28 @@ impl CacheBuilder {
                 m.insert(0, height);
                 m
             },
-            queue: Vec::new(),
-            all_glyphs: HashMap::default(),
-            pad_glyphs,
+            queue: Vec::with_capacity(expected_glyph_count),
+            queue_tags: Vec::new(),
+            tagged_rects: Vec::new(),
+            all_glyphs: HashMap::with_capacity_and_hasher(expected_glyph_count, <_>::default()),
+            glyph_id_maps: HashMap::default(),
+            data_id_fonts: HashMap::default(),
+            glyph_padding,
             align_4x4,
             multithread,
+            multithread_threshold,
+            ignore_subpixel,
+            upload_order,
+            coverage_lut: coverage_curve.lut(),
+            coverage_curve,
+            content,
+            texture: retain_texture.then(|| ByteArray2d::zeros(height as _, width as _)),
+            raster_checksums,
+            expected_glyph_count,
+            on_evict,
+            generation: 0,
+            cleared_generation: 0,
+            snapshot: OnceLock::new(),
         }
     }
 
@@ -450,7 +1067,7 @@ impl CacheBuilder {
     /// # Panics
     ///
     /// `scale_tolerance` or `position_tolerance` are less than or equal to
-    /// zero.
+    /// zero, or a `Content::Sdf` spread is not positive & finite.
     ///
     /// # Example
     ///
@@ -464,20 +1081,48 @@ impl CacheBuilder {
         let CacheBuilder {
             dimensions: (width, height),
             scale_tolerance,
+            scale_quantization,
             position_tolerance,
-            pad_glyphs,
+            glyph_padding,
             align_4x4,
             multithread,
+            multithread_threshold,
+            ignore_subpixel,
+            upload_order,
+            coverage_curve,
+            content,
+            retain_texture,
+            raster_checksums,
+            expected_glyph_count,
+            on_evict,
         } = self.validated();
 
+        // evict with the old callback & settings
+        cache.clear();
+
         cache.width = width;
         cache.height = height;
         cache.scale_tolerance = scale_tolerance;
+        cache.scale_quantization = scale_quantization;
         cache.position_tolerance = position_tolerance;
-        cache.pad_glyphs = pad_glyphs;
+        cache.glyph_padding = glyph_padding;
         cache.align_4x4 = align_4x4;
         cache.multithread = multithread;
-        cache.clear();
+        cache.multithread_threshold = multithread_threshold;
+        cache.ignore_subpixel = ignore_subpixel;
+        cache.upload_order = upload_order;
+        cache.coverage_lut = coverage_curve.lut();
+        cache.coverage_curve = coverage_curve;
+        cache.content = content;
+        cache.texture = retain_texture.then(|| ByteArray2d::zeros(height as _, width as _));
+        cache.raster_checksums = raster_checksums;
+        cache.expected_glyph_count = expected_glyph_count;
+        cache.on_evict = on_evict;
+        cache.reset_free_space();
+        cache.all_glyphs.reserve(expected_glyph_count);
+        cache
+            .queue
+            .reserve(expected_glyph_count.saturating_sub(cache.queue.len()));
     }
 }
 
@@ -519,6 +1164,120 @@ impl fmt::Display for CacheWriteErr {
 
 impl error::Error for CacheWriteErr {}
 
+/// Glyphs grouped by the cache texture they would share, see
+/// `Cache::glyph_classes`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GlyphClasses {
+    /// Indices of the glyphs in each class, classes in order of first
+    /// occurrence. Empty glyphs, which are never cached, are in no class.
+    pub classes: Vec<Vec<usize>>,
+    /// Texels needed to cache one glyph of each class, including padding &
+    /// alignment. Row packing may need more.
+    pub texture_area: u64,
+    /// Part of `texture_area` for classes not currently cached.
+    pub uncached_area: u64,
+}
+
+/// The order of texture uploads made by `Cache::cache_queued`, see
+/// `CacheBuilder::upload_order`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum UploadOrder {
+    /// The order glyphs are packed into the texture, tallest glyphs first.
+    #[default]
+    Packing,
+    /// Sorted by texture position, top to bottom then left to right, so
+    /// uploads to the same texture row are consecutive.
+    Row,
+}
+
+/// Scale in pixels at which `ScaleQuantization::Perceptual` tolerance equals
+/// `scale_tolerance`.
+const PERCEPTUAL_REFERENCE_SCALE: f32 = 16.0;
+/// Scales below are cached as this in `ScaleQuantization::Perceptual` mode.
+const PERCEPTUAL_MIN_SCALE: f32 = 0.01;
+
+/// How `scale_tolerance` quantises glyph scales, see
+/// `CacheBuilder::scale_quantization`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ScaleQuantization {
+    /// Even steps of `scale_tolerance` pixels at all scales.
+    #[default]
+    Linear,
+    /// Steps proportional to scale, `scale_tolerance` at 16px, so eg with
+    /// `scale_tolerance(0.1)` steps are 0.05px at 8px & 1px at 160px.
+    /// Differences in size are perceived relative to size, so this avoids
+    /// caching many imperceptibly different variants of large glyphs, eg
+    /// during zoom animations, while small text is cached more accurately
+    /// than `Linear`.
+    Perceptual,
+}
+
+/// The mapping of glyph coverage to texture bytes, see
+/// `CacheBuilder::coverage_curve`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CoverageCurve {
+    /// Coverage scaled to `0..=255`, ie linear alpha.
+    #[default]
+    Linear,
+    /// Coverage encoded with the sRGB transfer function, so sampling from an
+    /// sRGB texture format decodes back to linear coverage.
+    Srgb,
+    /// A custom mapping, indexed by linear coverage scaled to `0..=255`.
+    Lut(Arc<[u8; 256]>),
+}
+
+impl CoverageCurve {
+    fn lut(&self) -> [u8; 256] {
+        let mut lut = [0; 256];
+        for (index, value) in lut.iter_mut().enumerate() {
+            let linear = index as f32 / 255.0;
+            *value = match self {
+                Self::Linear => index as u8,
+                Self::Srgb => {
+                    let srgb = if linear <= 0.003_130_8 {
+                        linear * 12.92
+                    } else {
+                        1.055 * linear.powf(1.0 / 2.4) - 0.055
+                    };
+                    (srgb * 255.0).round() as u8
+                }
+                Self::Lut(lut) => lut[index],
+            };
+        }
+        lut
+    }
+}
+
+/// What cached glyph textures contain, see `CacheBuilder::content`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Content {
+    /// Glyph coverage, mapped by the `CacheBuilder::coverage_curve`.
+    #[default]
+    Coverage,
+    /// Signed distance fields, `128` on the glyph outline rising to `255`
+    /// `spread` texels inside & falling to `0` `spread` texels outside.
+    /// Glyph textures have a margin of `spread` texels, rounded up, to hold
+    /// the field outside the outline.
+    ///
+    /// Render by thresholding the sampled distance around `0.5`, eg with
+    /// `smoothstep` over a screen-space derivative width.
+    Sdf {
+        /// Distance in texels, at the cached scale, of the field range. Must
+        /// be positive & finite.
+        spread: f32,
+    },
+}
+
+impl Content {
+    /// Texels around the glyph in its texture, excluding padding.
+    fn margin(self) -> u32 {
+        match self {
+            Self::Coverage => 0,
+            Self::Sdf { spread } => spread.ceil() as u32,
+        }
+    }
+}
+
 /// Successful method of caching of the queue.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum CachedBy {
@@ -547,6 +1306,31 @@ fn normalised_offset_from_position(position: Point<f32>) -> Vector<f32> {
     offset
 }
 
+/// Snaps `x` to the nearest of `bins` evenly spaced subpixel positions,
+/// `0`, `1 / bins`, .. `(bins - 1) / bins` past a whole pixel, eg 3 bins as
+/// in many text stacks. `0` or `1` bins snap to whole pixels.
+///
+/// Laying out glyphs at snapped positions & caching with
+/// `position_tolerance(0.5 / bins as f32)`, so bins are at the centres of
+/// tolerance steps, renders each glyph at one of `bins` exact subpixel
+/// offsets, cached once each. See also
+/// `Cache::snap_position` to snap to a cache's own tolerance.
+///
+/// # Example
+///
+/// ```
+/// use rusttype::gpu_cache::snap_subpixel;
+///
+/// assert_eq!(snap_subpixel(10.3, 3), 10.0 + 1.0 / 3.0);
+/// assert_eq!(snap_subpixel(10.9, 3), 11.0);
+/// assert_eq!(snap_subpixel(-0.4, 2), -0.5);
+/// ```
+pub fn snap_subpixel(x: f32, bins: u32) -> f32 {
+    let bins = bins.max(1) as f32;
+    let whole = x.floor();
+    whole + ((x - whole) * bins).round() / bins
+}
+
 impl<'font> Cache<'font> {
     /// Returns a default `CacheBuilder`.
     #[inline]
@@ -559,11 +1343,51 @@ impl<'font> Cache<'font> {
         self.scale_tolerance
     }
 
+    /// Returns how the scale tolerance applies at different scales.
+    pub fn scale_quantization(&self) -> ScaleQuantization {
+        self.scale_quantization
+    }
+
     /// Returns the current subpixel position tolerance for the cache.
     pub fn position_tolerance(&self) -> f32 {
         self.position_tolerance
     }
 
+    /// Returns `position` moved to the subpixel offset of the nearest
+    /// `position_tolerance` step, to whole pixels if `ignore_subpixel` is set.
+    ///
+    /// Glyphs matching the same cached texture are otherwise drawn at
+    /// slightly different offsets than the texture was rasterised at. Laying
+    /// out at snapped positions makes them exact, eg to match the cache's
+    /// binning in other renderers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{gpu_cache::Cache, point};
+    /// let cache = Cache::builder().position_tolerance(0.25).build();
+    /// assert_eq!(cache.snap_position(point(10.3, 4.9)), point(10.25, 5.0));
+    /// ```
+    pub fn snap_position(&self, position: Point<f32>) -> Point<f32> {
+        if self.ignore_subpixel {
+            return point(position.x.round(), position.y.round());
+        }
+        let offset = normalised_offset_from_position(position);
+        // the centre of the range of offsets `lossy_info` keys to the same
+        // step, steps at the ends are cut short by the offset range
+        let snap = |offset: f32| {
+            let tolerance = self.position_tolerance;
+            let step = f32::from(((offset + 0.5) / tolerance + 0.5) as u16);
+            let min = ((step - 0.5) * tolerance - 0.5).max(-0.5);
+            let max = ((step + 0.5) * tolerance - 0.5).min(0.5);
+            (min + max) / 2.0
+        };
+        point(
+            position.x - offset.x + snap(offset.x),
+            position.y - offset.y + snap(offset.y),
+        )
+    }
+
     /// Returns the cache texture dimensions assumed by the cache. For proper
     /// operation this should match the dimensions of the used GPU texture.
     pub fn dimensions(&self) -> (u32, u32) {
@@ -573,57 +1397,263 @@ impl<'font> Cache<'font> {
     /// Queue a glyph for caching by the next call to `cache_queued`. `font_id`
     /// is used to disambiguate glyphs from different fonts. The user should
     /// ensure that `font_id` is unique to the font the glyph is from.
-    pub fn queue_glyph(&mut self, font_id: usize, glyph: PositionedGlyph<'font>) {
+    pub fn queue_glyph(&mut self, font_id: usize, mut glyph: PositionedGlyph<'font>) {
+        if self.ignore_subpixel {
+            let p = glyph.position();
+            glyph.set_position(point(p.x.round(), p.y.round()));
+        }
         if glyph.pixel_bounding_box().is_some() {
-            self.queue.push((font_id, glyph));
+            self.queue.push((
+                font_id,
+                original_id(&self.glyph_id_maps, font_id, glyph.id()),
+                glyph,
+            ));
         }
     }
 
-    /// Clears the cache. Does not affect the glyph queue.
-    pub fn clear(&mut self) {
-        self.rows.clear();
-        self.space_end_for_start.clear();
-        self.space_end_for_start.insert(0, self.height);
-        self.space_start_for_end.clear();
-        self.space_start_for_end.insert(self.height, 0);
-        self.all_glyphs.clear();
-    }
-
-    /// Clears the glyph queue.
-    pub fn clear_queue(&mut self) {
-        self.queue.clear();
+    /// Queue a glyph for caching by the next call to `cache_queued`, using the
+    /// glyph's `Font::data_id` as the `font_id`.
+    ///
+    /// This avoids maintaining a registry of font ids. Look such glyphs up
+    /// with `rect_for_glyph`. Don't mix with user chosen `font_id`s unless
+    /// they are guaranteed not to collide with font data ids.
+    ///
+    /// As font data ids are only unique among live fonts, the cache keeps a
+    /// clone of each font queued this way until it is `clear`ed, so another
+    /// font can't reuse the data id of cached glyphs.
+    pub fn queue_glyph_with_data_id(&mut self, glyph: PositionedGlyph<'font>) {
+        let data_id = glyph.font().data_id();
+        self.data_id_fonts
+            .entry(data_id)
+            .or_insert_with(|| glyph.font().clone());
+        self.queue_glyph(data_id, glyph);
     }
 
-    /// Returns a `CacheBuilder` with this cache's attributes.
-    pub fn to_builder(&self) -> CacheBuilder {
-        CacheBuilder {
-            dimensions: (self.width, self.height),
-            position_tolerance: self.position_tolerance,
+    /// Queue a glyph for caching by the next call to `cache_queued`, as
+    /// `queue_glyph`, with a user `tag` returned with its texture coordinates
+    /// by `tagged_rects`.
+    ///
+    /// Tags carry per glyph data, eg a colour, z-order or index into the
+    /// renderer's own glyph runs, through caching without a parallel lookup
+    /// by glyph. Tags needn't be unique. Empty glyphs aren't queued so have
+    /// no tagged rect.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{gpu_cache::Cache, point, Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let colors = [[1.0, 0.0, 0.0], [0.0, 0.0, 1.0]];
+    /// let mut cache = Cache::builder().build();
+    /// for (index, text) in ["red", "blue"].iter().enumerate() {
+    ///     let y = 24.0 * (index + 1) as f32;
+    ///     for glyph in font.layout(text, Scale::uniform(20.0), point(0.0, y)) {
+    ///         cache.queue_glyph_tagged(0, glyph, index as u32);
+    ///     }
+    /// }
+    /// cache.cache_queued(|_, _| {}).unwrap();
+    ///
+    /// for rect in cache.tagged_rects() {
+    ///     let color = colors[rect.tag as usize];
+    ///     // push a quad of `color` at `rect.screen_rect` sampling `rect.uv_rect`
+    /// }
+    /// assert_eq!(cache.tagged_rects().len(), 7);
+    /// ```
+    pub fn queue_glyph_tagged(&mut self, font_id: usize, glyph: PositionedGlyph<'font>, tag: u32) {
+        let queued = self.queue.len();
+        self.queue_glyph(font_id, glyph);
+        if self.queue.len() > queued {
+            self.queue_tags.push((queued, tag));
+        }
+    }
+
+    /// Returns the texture coordinates of the glyphs queued with
+    /// `queue_glyph_tagged` cached by the last successful `cache_queued`, in
+    /// queue order.
+    ///
+    /// Rects remain valid until the next `cache_queued` or `clear`, which
+    /// empty the list until the next successful `cache_queued`.
+    pub fn tagged_rects(&self) -> &[TaggedRect] {
+        &self.tagged_rects
+    }
+
+    /// Sets the glyph id mapping to use for glyphs of `font_id`, or removes
+    /// it with `None`.
+    ///
+    /// Use this when `font_id` refers to a subset font that may be
+    /// regenerated with different glyph id assignments. Glyphs are then
+    /// cached & looked up by their original font ids, so cached glyphs remain
+    /// valid when switching to a new subset of the same original font.
+    ///
+    /// Ids are mapped as glyphs are queued, so glyphs queued before the map
+    /// changes are still cached as the glyphs of the subset they came from.
+    ///
+    /// Glyphs the map doesn't cover are cached by their subset id, which may
+    /// be the original id of another glyph, so the map should cover every
+    /// glyph of the subset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{gpu_cache::Cache, GlyphId, GlyphIdMap};
+    /// # let mut cache = Cache::builder().build();
+    /// // subset font glyph 1 is glyph 36 in the original font
+    /// let map = GlyphIdMap::new(vec![GlyphId(0), GlyphId(36)]);
+    /// cache.set_glyph_id_map(0, Some(map));
+    /// ```
+    pub fn set_glyph_id_map(&mut self, font_id: usize, map: Option<GlyphIdMap>) {
+        match map {
+            Some(map) => self.glyph_id_maps.insert(font_id, map),
+            None => self.glyph_id_maps.remove(&font_id),
+        };
+        self.snapshot = OnceLock::new();
+    }
+
+    /// Clears the cache. Does not affect the glyph queue.
+    pub fn clear(&mut self) {
+        if let Some(on_evict) = &self.on_evict {
+            for row in self.rows.values() {
+                for g in &row.glyphs {
+                    on_evict(&self.evicted_glyph(g));
+                }
+            }
+        }
+        self.generation += 1;
+        self.cleared_generation = self.generation;
+        self.snapshot = OnceLock::new();
+        self.tagged_rects.clear();
+        self.rows.clear();
+        self.reset_free_space();
+        self.all_glyphs.clear();
+        // fonts of queued glyphs are still needed once they are cached
+        let queue = &self.queue;
+        self.data_id_fonts
+            .retain(|data_id, _| queue.iter().any(|(font_id, ..)| font_id == data_id));
+    }
+
+    /// Marks the whole texture height as free space.
+    fn reset_free_space(&mut self) {
+        self.space_end_for_start.clear();
+        self.space_end_for_start.insert(0, self.height);
+        self.space_start_for_end.clear();
+        self.space_start_for_end.insert(self.height, 0);
+    }
+
+    /// Clears the glyph queue.
+    pub fn clear_queue(&mut self) {
+        self.queue.clear();
+        self.queue_tags.clear();
+    }
+
+    /// Returns a `CacheBuilder` with this cache's attributes.
+    pub fn to_builder(&self) -> CacheBuilder {
+        CacheBuilder {
+            dimensions: (self.width, self.height),
+            position_tolerance: self.position_tolerance,
             scale_tolerance: self.scale_tolerance,
-            pad_glyphs: self.pad_glyphs,
+            scale_quantization: self.scale_quantization,
+            glyph_padding: self.glyph_padding,
             align_4x4: self.align_4x4,
             multithread: self.multithread,
+            multithread_threshold: self.multithread_threshold,
+            ignore_subpixel: self.ignore_subpixel,
+            upload_order: self.upload_order,
+            coverage_curve: self.coverage_curve.clone(),
+            content: self.content,
+            retain_texture: self.texture.is_some(),
+            raster_checksums: self.raster_checksums,
+            expected_glyph_count: self.expected_glyph_count,
+            on_evict: self.on_evict.clone(),
+        }
+    }
+
+    fn evicted_glyph(&self, g: &GlyphTexInfo) -> EvictedGlyph {
+        let (scale_x, scale_y) = g.glyph_info.scale_over_tolerance;
+        let settings = self.key_settings();
+        EvictedGlyph {
+            font_id: g.glyph_info.font_id,
+            glyph_id: g.glyph_info.glyph_id,
+            scale: Scale {
+                x: settings.dequantize_scale(scale_x),
+                y: settings.dequantize_scale(scale_y),
+            },
+            offset: g.offset,
+            tex_coords: g.tex_coords.unpadded(self.glyph_padding),
+        }
+    }
+
+    /// Returns the texture size of a non-empty glyph including padding.
+    fn padded_size(&self, glyph: &PositionedGlyph<'_>) -> (u32, u32) {
+        let bb = glyph.pixel_bounding_box().unwrap();
+        // saturate, so huge paddings fail with `GlyphTooLarge`
+        let padding = self
+            .glyph_padding
+            .saturating_add(self.content.margin())
+            .saturating_mul(2);
+        (
+            (bb.saturating_width() as u32).saturating_add(padding),
+            (bb.saturating_height() as u32).saturating_add(padding),
+        )
+    }
+
+    /// Returns the texture space taken by a non-empty glyph including padding
+    /// & alignment.
+    fn aligned_size(&self, glyph: &PositionedGlyph<'_>) -> (u32, u32) {
+        let (width, height) = self.padded_size(glyph);
+        if self.align_4x4 {
+            // align to the next 4x4 texel boundary
+            (width.saturating_add(3) & !3, height.saturating_add(3) & !3)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Returns the settings deciding which glyphs share a cached texture.
+    fn key_settings(&self) -> KeySettings {
+        KeySettings {
+            scale_tolerance: self.scale_tolerance,
+            scale_quantization: self.scale_quantization,
+            position_tolerance: self.position_tolerance,
+            ignore_subpixel: self.ignore_subpixel,
+            content: self.content,
         }
     }
 
+    /// Returns the glyph to rasterize for the texture of `glyph_info`, see
+    /// `KeySettings::raster_glyph`.
+    fn raster_glyph<'a>(
+        &self,
+        glyph: &'a PositionedGlyph<'font>,
+        glyph_info: &LossyGlyphInfo,
+    ) -> Option<Cow<'a, PositionedGlyph<'font>>> {
+        self.key_settings().raster_glyph(glyph, glyph_info)
+    }
+
     /// Returns glyph info with accuracy according to the set tolerances.
     fn lossy_info_for(&self, font_id: FontId, glyph: &PositionedGlyph<'font>) -> LossyGlyphInfo {
-        let scale = glyph.scale();
-        let offset = normalised_offset_from_position(glyph.position());
+        self.key_settings().lossy_info_for(
+            font_id,
+            original_id(&self.glyph_id_maps, font_id, glyph.id()),
+            glyph,
+        )
+    }
 
-        LossyGlyphInfo {
+    fn lossy_info(
+        &self,
+        font_id: FontId,
+        glyph_id: GlyphId,
+        scale: Scale,
+        offset: Vector<f32>,
+        rounding: PixelRounding,
+    ) -> LossyGlyphInfo {
+        self.key_settings().lossy_info(
             font_id,
-            glyph_id: glyph.id(),
-            scale_over_tolerance: (
-                (scale.x / self.scale_tolerance + 0.5) as u32,
-                (scale.y / self.scale_tolerance + 0.5) as u32,
-            ),
-            // convert [-0.5, 0.5] -> [0, 1] then divide
-            offset_over_tolerance: (
-                ((offset.x + 0.5) / self.position_tolerance + 0.5) as u16,
-                ((offset.y + 0.5) / self.position_tolerance + 0.5) as u16,
-            ),
-        }
+            original_id(&self.glyph_id_maps, font_id, glyph_id),
+            scale,
+            offset,
+            rounding,
+        )
     }
 
     /// Caches the queued glyphs. If this is unsuccessful, the queue is
@@ -640,9 +1670,61 @@ impl<'font> Cache<'font> {
     ///
     /// If successful returns a `CachedBy` that can indicate the validity of
     /// previously cached glyph textures.
+    ///
+    /// # Upload contract
+    ///
+    /// Within a single call:
+    ///
+    /// * Upload regions never overlap, so uploads may be batched or reordered
+    ///   freely by the caller.
+    /// * Each glyph texture is uploaded at most once.
+    /// * Uploads are made in a deterministic order, independent of
+    ///   multithreading, as specified by `CacheBuilder::upload_order`.
+    /// * Uploads only happen if caching succeeds. An `Err` result means no
+    ///   uploads were made.
     pub fn cache_queued<F: FnMut(Rect<u32>, &[u8])>(
         &mut self,
         mut uploader: F,
+    ) -> Result<CachedBy, CacheWriteErr> {
+        self.generation += 1;
+        self.snapshot = OnceLock::new();
+        self.tagged_rects.clear();
+        if self.texture.is_none() && !self.raster_checksums {
+            return self.cache_queued_inner(uploader);
+        }
+
+        let mut texture = self.texture.take();
+        let mut checksums = Vec::new();
+        let raster_checksums = self.raster_checksums;
+        let result = self.cache_queued_inner(|rect, data| {
+            if let Some(texture) = &mut texture {
+                texture.write_rect(rect, data);
+            }
+            if raster_checksums {
+                checksums.push(RasterChecksum {
+                    tex_rect: rect,
+                    checksum: raster_checksum(data),
+                });
+            }
+            uploader(rect, data);
+        });
+        self.texture = texture;
+
+        // uploads start at the glyph's texture coords in its row
+        for checksum in checksums {
+            let min = checksum.tex_rect.min;
+            if let Some(row) = self.rows.get_mut(&min.y) {
+                if let Some(g) = row.glyphs.iter_mut().find(|g| g.tex_coords.min == min) {
+                    g.checksum = Some(checksum);
+                }
+            }
+        }
+        result
+    }
+
+    fn cache_queued_inner<F: FnMut(Rect<u32>, &[u8])>(
+        &mut self,
+        mut uploader: F,
     ) -> Result<CachedBy, CacheWriteErr> {
         let mut queue_success = true;
         let from_empty = self.all_glyphs.is_empty();
@@ -655,26 +1737,32 @@ impl<'font> Cache<'font> {
 
                 // divide glyphs into texture rows where a matching glyph texture
                 // already exists & glyphs where new textures must be cached
-                for (font_id, ref glyph) in &self.queue {
-                    let glyph_info = self.lossy_info_for(*font_id, glyph);
+                let settings = self.key_settings();
+                for (font_id, glyph_id, ref glyph) in &self.queue {
+                    let glyph_info = settings.lossy_info_for(*font_id, *glyph_id, glyph);
                     if let Some((row, ..)) = self.all_glyphs.get(&glyph_info) {
                         in_use_rows.insert(*row);
-                    } else {
-                        uncached_glyphs.push((glyph, glyph_info));
+                    } else if let Some(raster) = self.raster_glyph(glyph, &glyph_info) {
+                        uncached_glyphs.push((raster, glyph_info));
                     }
                 }
 
                 (in_use_rows, uncached_glyphs)
             };
 
-            for row in &in_use_rows {
+            // refresh in a fixed order, independent of hash set capacity, as
+            // it decides which rows are evicted first
+            let mut refreshed: Vec<_> = in_use_rows.iter().copied().collect();
+            refreshed.sort_unstable();
+            for row in &refreshed {
                 self.rows.get_refresh(row);
             }
 
             // tallest first gives better packing
             // can use 'sort_unstable' as order of equal elements is unimportant
-            uncached_glyphs
-                .sort_unstable_by_key(|(glyph, ..)| -glyph.pixel_bounding_box().unwrap().height());
+            uncached_glyphs.sort_unstable_by_key(|(glyph, ..)| {
+                -glyph.pixel_bounding_box().unwrap().saturating_height()
+            });
 
             self.all_glyphs.reserve(uncached_glyphs.len());
             let mut draw_and_upload = Vec::with_capacity(uncached_glyphs.len());
@@ -686,20 +1774,8 @@ impl<'font> Cache<'font> {
                 }
 
                 // Not cached, so add it:
-                let (unaligned_width, unaligned_height) = {
-                    let bb = glyph.pixel_bounding_box().unwrap();
-                    if self.pad_glyphs {
-                        (bb.width() as u32 + 2, bb.height() as u32 + 2)
-                    } else {
-                        (bb.width() as u32, bb.height() as u32)
-                    }
-                };
-                let (aligned_width, aligned_height) = if self.align_4x4 {
-                    // align to the next 4x4 texel boundary
-                    ((unaligned_width + 3) & !3, (unaligned_height + 3) & !3)
-                } else {
-                    (unaligned_width, unaligned_height)
-                };
+                let (unaligned_width, unaligned_height) = self.padded_size(&glyph);
+                let (aligned_width, aligned_height) = self.aligned_size(&glyph);
                 if aligned_width >= self.width || aligned_height >= self.height {
                     return Result::Err(CacheWriteErr::GlyphTooLarge);
                 }
@@ -714,14 +1790,14 @@ impl<'font> Cache<'font> {
                 }
 
                 if row_top.is_none() {
-                    let mut gap = None;
-                    // See if there is space for a new row
-                    for (start, end) in &self.space_end_for_start {
-                        if end - start >= aligned_height {
-                            gap = Some((*start, *end));
-                            break;
-                        }
-                    }
+                    // See if there is space for a new row, topmost first so
+                    // packing doesn't depend on hash map iteration order
+                    let mut gap = self
+                        .space_end_for_start
+                        .iter()
+                        .filter(|(start, end)| *end - *start >= aligned_height)
+                        .map(|(start, end)| (*start, *end))
+                        .min();
                     if gap.is_none() {
                         // Remove old rows until room is available
                         while !self.rows.is_empty() {
@@ -731,6 +1807,9 @@ impl<'font> Cache<'font> {
                                 let (top, row) = self.rows.pop_front().unwrap();
 
                                 for g in row.glyphs {
+                                    if let Some(on_evict) = &self.on_evict {
+                                        on_evict(&self.evicted_glyph(&g));
+                                    }
                                     self.all_glyphs.remove(&g.glyph_info);
                                 }
 
@@ -797,39 +1876,55 @@ impl<'font> Cache<'font> {
                     max: point(row.width + unaligned_width, row_top + unaligned_height),
                 };
 
-                draw_and_upload.push((aligned_tex_coords, glyph));
-
                 // add the glyph to the row
+                let offset = normalised_offset_from_position(glyph.position());
+                let margin = self.content.margin() as i32;
+                let local_bb = local_bounds(&glyph, offset);
                 row.glyphs.push(GlyphTexInfo {
                     glyph_info,
-                    offset: normalised_offset_from_position(glyph.position()),
+                    offset,
+                    local_bb: Rect {
+                        min: local_bb.min - vector(margin, margin),
+                        max: local_bb.max + vector(margin, margin),
+                    },
                     tex_coords: unaligned_tex_coords,
+                    checksum: None,
+                    generation: self.generation,
                 });
                 row.width += aligned_width;
                 in_use_rows.insert(row_top);
 
                 self.all_glyphs
                     .insert(glyph_info, (row_top, row.glyphs.len() as u32 - 1));
+                draw_and_upload.push((aligned_tex_coords, glyph));
             }
 
             if queue_success {
+                if self.upload_order == UploadOrder::Row {
+                    draw_and_upload.sort_unstable_by_key(|(rect, _)| (rect.min.y, rect.min.x));
+                }
+
                 #[cfg(not(target_arch = "wasm32"))]
                 {
                     let glyph_count = draw_and_upload.len();
 
-                    if self.multithread && glyph_count > 1 {
-                        // multithread rasterization
+                    if self.multithread && glyph_count >= self.multithread_threshold.max(2) {
+                        // multithread rasterization, uploads are made in the
+                        // same order as single thread rasterization
                         use crossbeam_deque::Steal;
                         use std::{
+                            collections::BTreeMap,
                             mem,
                             sync::mpsc::{self, TryRecvError},
                         };
 
                         let rasterize_queue = crossbeam_deque::Injector::new();
                         let (to_main, from_stealers) = mpsc::channel();
-                        let pad_glyphs = self.pad_glyphs;
+                        let glyph_padding = self.glyph_padding;
+                        let coverage_lut = self.coverage_lut;
+                        let content = self.content;
 
-                        for el in draw_and_upload {
+                        for el in draw_and_upload.into_iter().enumerate() {
                             rasterize_queue.push(el);
                         }
                         crossbeam_utils::thread::scope(|scope| {
@@ -838,9 +1933,15 @@ impl<'font> Cache<'font> {
                                 let to_main = to_main.clone();
                                 scope.spawn(move |_| loop {
                                     match rasterize_queue.steal() {
-                                        Steal::Success((tex_coords, glyph)) => {
-                                            let pixels = draw_glyph(tex_coords, glyph, pad_glyphs);
-                                            to_main.send((tex_coords, pixels)).unwrap();
+                                        Steal::Success((index, (tex_coords, glyph))) => {
+                                            let pixels = draw_glyph(
+                                                tex_coords,
+                                                &glyph,
+                                                glyph_padding,
+                                                &coverage_lut,
+                                                content,
+                                            );
+                                            to_main.send((index, tex_coords, pixels)).unwrap();
                                         }
                                         Steal::Empty => break,
                                         Steal::Retry => {}
@@ -849,12 +1950,21 @@ impl<'font> Cache<'font> {
                             }
                             mem::drop(to_main);
 
+                            // rasterized glyphs waiting on earlier glyph uploads
+                            let mut pending = BTreeMap::new();
+                            let mut next_upload = 0;
                             let mut workers_finished = false;
                             loop {
                                 match rasterize_queue.steal() {
-                                    Steal::Success((tex_coords, glyph)) => {
-                                        let pixels = draw_glyph(tex_coords, glyph, pad_glyphs);
-                                        uploader(tex_coords, pixels.as_slice());
+                                    Steal::Success((index, (tex_coords, glyph))) => {
+                                        let pixels = draw_glyph(
+                                            tex_coords,
+                                            &glyph,
+                                            glyph_padding,
+                                            &coverage_lut,
+                                            content,
+                                        );
+                                        pending.insert(index, (tex_coords, pixels));
                                     }
                                     Steal::Empty if workers_finished => break,
                                     Steal::Empty | Steal::Retry => {}
@@ -862,20 +1972,33 @@ impl<'font> Cache<'font> {
 
                                 while !workers_finished {
                                     match from_stealers.try_recv() {
-                                        Ok((tex_coords, pixels)) => {
-                                            uploader(tex_coords, pixels.as_slice())
+                                        Ok((index, tex_coords, pixels)) => {
+                                            pending.insert(index, (tex_coords, pixels));
                                         }
                                         Err(TryRecvError::Disconnected) => workers_finished = true,
                                         Err(TryRecvError::Empty) => break,
                                     }
                                 }
+
+                                while let Some((tex_coords, pixels)) = pending.remove(&next_upload)
+                                {
+                                    uploader(tex_coords, pixels.as_slice());
+                                    next_upload += 1;
+                                }
                             }
+                            debug_assert!(pending.is_empty());
                         })
                         .unwrap();
                     } else {
                         // single thread rasterization
                         for (tex_coords, glyph) in draw_and_upload {
-                            let pixels = draw_glyph(tex_coords, glyph, self.pad_glyphs);
+                            let pixels = draw_glyph(
+                                tex_coords,
+                                &glyph,
+                                self.glyph_padding,
+                                &self.coverage_lut,
+                                self.content,
+                            );
                             uploader(tex_coords, pixels.as_slice());
                         }
                     }
@@ -883,7 +2006,13 @@ impl<'font> Cache<'font> {
                 #[cfg(target_arch = "wasm32")]
                 {
                     for (tex_coords, glyph) in draw_and_upload {
-                        let pixels = draw_glyph(tex_coords, glyph, self.pad_glyphs);
+                        let pixels = draw_glyph(
+                            tex_coords,
+                            &glyph,
+                            self.glyph_padding,
+                            &self.coverage_lut,
+                            self.content,
+                        );
                         uploader(tex_coords, pixels.as_slice());
                     }
                 }
@@ -891,12 +2020,26 @@ impl<'font> Cache<'font> {
         }
 
         if queue_success {
+            let settings = self.key_settings();
+            let tagged_rects = self.queue_tags.iter().filter_map(|&(index, tag)| {
+                let (font_id, glyph_id, glyph) = &self.queue[index];
+                let glyph_info = settings.lossy_info_for(*font_id, *glyph_id, glyph);
+                let (uv_rect, screen_rect) = self.rect_for_info(glyph, &glyph_info).ok()?;
+                Some(TaggedRect {
+                    tag,
+                    uv_rect,
+                    screen_rect,
+                })
+            });
+            self.tagged_rects = tagged_rects.collect();
             self.queue.clear();
+            self.queue_tags.clear();
             Ok(CachedBy::Adding)
         } else {
             // clear the cache then try again with optimal packing
             self.clear();
-            self.cache_queued(uploader).map(|_| CachedBy::Reordering)
+            self.cache_queued_inner(uploader)
+                .map(|_| CachedBy::Reordering)
         }
     }
 
@@ -910,7 +2053,8 @@ impl<'font> Cache<'font> {
     /// the cache tolerance parameters.
     ///
     /// A sucessful result is `Some` if the glyph is not an empty glyph (no
-    /// shape, and thus no rect to return).
+    /// shape, and thus no rect to return). With `Content::Sdf` glyphs without
+    /// pixels at their cached power of two scale are also empty.
     ///
     /// Ensure that `font_id` matches the `font_id` that was passed to
     /// `queue_glyph` with this `glyph`.
@@ -923,118 +2067,600 @@ impl<'font> Cache<'font> {
             return Ok(None);
         }
 
+        let glyph_info = self.lossy_info_for(font_id, glyph);
+        match self.rect_for_info(glyph, &glyph_info) {
+            Err(CacheReadErr::GlyphNotCached)
+                if self.raster_glyph(glyph, &glyph_info).is_none() =>
+            {
+                Ok(None)
+            }
+            result => result.map(Some),
+        }
+    }
+
+    /// Returns the texture coordinates of the texture cached as `glyph_info`
+    /// for `glyph`.
+    fn rect_for_info(
+        &self,
+        glyph: &PositionedGlyph<'_>,
+        glyph_info: &LossyGlyphInfo,
+    ) -> Result<TextureCoords, CacheReadErr> {
         let (row, index) = self
             .all_glyphs
-            .get(&self.lossy_info_for(font_id, glyph))
+            .get(glyph_info)
             .ok_or(CacheReadErr::GlyphNotCached)?;
 
-        let (tex_width, tex_height) = (self.width as f32, self.height as f32);
-
         let GlyphTexInfo {
-            tex_coords: mut tex_rect,
+            tex_coords,
             offset: tex_offset,
             ..
         } = self.rows[row].glyphs[*index as usize];
-        if self.pad_glyphs {
-            tex_rect = tex_rect.unpadded();
-        }
-        let uv_rect = Rect {
-            min: point(
-                tex_rect.min.x as f32 / tex_width,
-                tex_rect.min.y as f32 / tex_height,
-            ),
-            max: point(
-                tex_rect.max.x as f32 / tex_width,
-                tex_rect.max.y as f32 / tex_height,
-            ),
-        };
 
-        let local_bb = glyph
-            .unpositioned()
+        let bb = self
+            .key_settings()
+            .screen_bounds_for(glyph, glyph_info, tex_offset);
+        Ok((self.uv_rect(tex_coords), bb))
+    }
+
+    /// Returns a read only view of the currently cached glyphs, which can be
+    /// shared across threads to look up glyphs while this cache is kept for
+    /// the next `cache_queued`.
+    ///
+    /// The snapshot is taken on the first call after the cache changes,
+    /// later calls cheaply share it until the next `cache_queued`, `clear` or
+    /// `set_glyph_id_map`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, gpu_cache::Cache, point, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// # let mut cache = Cache::builder().build();
+    /// let glyphs: Vec<_> = font.layout("Hi there", Scale::uniform(20.0), point(0.0, 20.0)).collect();
+    /// for glyph in &glyphs {
+    ///     cache.queue_glyph(0, glyph.clone());
+    /// }
+    /// cache.cache_queued(|_, _| {}).unwrap();
+    ///
+    /// let snapshot = cache.snapshot();
+    /// std::thread::scope(|scope| {
+    ///     for chunk in glyphs.chunks(4) {
+    ///         let snapshot = snapshot.clone();
+    ///         scope.spawn(move || {
+    ///             for glyph in chunk {
+    ///                 let rect = snapshot.rect_for(0, glyph).unwrap();
+    ///                 // build command buffer
+    ///             }
+    ///         });
+    ///     }
+    /// });
+    /// ```
+    pub fn snapshot(&self) -> CacheSnapshot {
+        self.snapshot
+            .get_or_init(|| CacheSnapshot::new(self))
             .clone()
-            .positioned(point(0.0, 0.0) + tex_offset)
-            .pixel_bounding_box()
-            .unwrap();
-        let min_from_origin =
-            point(local_bb.min.x as f32, local_bb.min.y as f32) - (point(0.0, 0.0) + tex_offset);
-        let ideal_min = min_from_origin + glyph.position();
-        let min = point(ideal_min.x.round() as i32, ideal_min.y.round() as i32);
-        let bb_offset = min - local_bb.min;
-        let bb = Rect {
-            min,
-            max: local_bb.max + bb_offset,
-        };
-        Ok(Some((uv_rect, bb)))
     }
-}
 
-#[inline]
-fn draw_glyph(tex_coords: Rect<u32>, glyph: &PositionedGlyph<'_>, pad_glyphs: bool) -> ByteArray2d {
-    let mut pixels = ByteArray2d::zeros(tex_coords.height() as usize, tex_coords.width() as usize);
-    if pad_glyphs {
-        glyph.draw(|x, y, v| {
-            let v = (v * 255.0).round() as u8;
-            // `+ 1` accounts for top/left glyph padding
-            pixels[(y as usize + 1, x as usize + 1)] = v;
-        });
-    } else {
-        glyph.draw(|x, y, v| {
-            let v = (v * 255.0).round() as u8;
-            pixels[(y as usize, x as usize)] = v;
-        });
+    /// Appends a quad for each non-empty glyph of `glyphs` to `quads`, as
+    /// `[uv_min_x, uv_min_y, uv_max_x, uv_max_y, min_x, min_y, max_x, max_y]`,
+    /// the `rect_for` texture & pixel-space coordinates. Suited to uploading as
+    /// GPU instance data to draw each glyph quad with instanced rendering.
+    ///
+    /// Empty glyphs, eg spaces, have no quad, see `indexed_quads_for` to map
+    /// quads back to their glyphs.
+    ///
+    /// Returns the number of quads appended. If any glyph is not cached
+    /// returns `Err(CacheReadErr::GlyphNotCached)` & leaves `quads` unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, gpu_cache::Cache, point, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// # let mut cache = Cache::builder().build();
+    /// let glyphs: Vec<_> = font.layout("Hi there", Scale::uniform(20.0), point(0.0, 20.0)).collect();
+    /// for glyph in &glyphs {
+    ///     cache.queue_glyph(0, glyph.clone());
+    /// }
+    /// cache.cache_queued(|_, _| {}).unwrap();
+    ///
+    /// let mut quads: Vec<[f32; 8]> = Vec::new();
+    /// // the space has no quad
+    /// assert_eq!(cache.quads_for(0, &glyphs, &mut quads), Ok(7));
+    /// ```
+    pub fn quads_for(
+        &self,
+        font_id: usize,
+        glyphs: &[PositionedGlyph<'_>],
+        quads: &mut Vec<[f32; 8]>,
+    ) -> Result<usize, CacheReadErr> {
+        self.push_quads(font_id, glyphs, quads, None)
     }
-    pixels
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::{Font, Scale};
-    use approx::*;
+    /// Appends quads as `quads_for` & the index in `glyphs` of each quad's
+    /// glyph to `indices`, as empty glyphs have no quad.
+    ///
+    /// If any glyph is not cached returns `Err(CacheReadErr::GlyphNotCached)`
+    /// & leaves both `quads` & `indices` unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, gpu_cache::Cache, point, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// # let mut cache = Cache::builder().build();
+    /// let glyphs: Vec<_> = font.layout("Hi there", Scale::uniform(20.0), point(0.0, 20.0)).collect();
+    /// for glyph in &glyphs {
+    ///     cache.queue_glyph(0, glyph.clone());
+    /// }
+    /// cache.cache_queued(|_, _| {}).unwrap();
+    ///
+    /// let (mut quads, mut indices) = (Vec::new(), Vec::new());
+    /// assert_eq!(cache.indexed_quads_for(0, &glyphs, &mut quads, &mut indices), Ok(7));
+    /// // the quad after the space
+    /// assert_eq!(indices[2], 3);
+    /// ```
+    pub fn indexed_quads_for(
+        &self,
+        font_id: usize,
+        glyphs: &[PositionedGlyph<'_>],
+        quads: &mut Vec<[f32; 8]>,
+        indices: &mut Vec<usize>,
+    ) -> Result<usize, CacheReadErr> {
+        self.push_quads(font_id, glyphs, quads, Some(indices))
+    }
 
-    #[test]
-    fn cache_test() {
-        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
-        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+    fn push_quads(
+        &self,
+        font_id: usize,
+        glyphs: &[PositionedGlyph<'_>],
+        quads: &mut Vec<[f32; 8]>,
+        mut indices: Option<&mut Vec<usize>>,
+    ) -> Result<usize, CacheReadErr> {
+        let start = quads.len();
+        let indices_start = indices.as_ref().map_or(0, |indices| indices.len());
+        quads.reserve(glyphs.len());
+        for (index, glyph) in glyphs.iter().enumerate() {
+            match self.rect_for(font_id, glyph) {
+                Ok(Some((uv, bb))) => {
+                    quads.push([
+                        uv.min.x,
+                        uv.min.y,
+                        uv.max.x,
+                        uv.max.y,
+                        bb.min.x as f32,
+                        bb.min.y as f32,
+                        bb.max.x as f32,
+                        bb.max.y as f32,
+                    ]);
+                    if let Some(indices) = indices.as_mut() {
+                        indices.push(index);
+                    }
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    quads.truncate(start);
+                    if let Some(indices) = indices {
+                        indices.truncate(indices_start);
+                    }
+                    return Err(err);
+                }
+            }
+        }
+        Ok(quads.len() - start)
+    }
 
-        let mut cache: Cache<'static> = Cache::builder()
-            .dimensions(32, 32)
-            .scale_tolerance(0.1)
-            .position_tolerance(0.1)
-            .pad_glyphs(false)
-            .build();
-        let strings = [
-            ("Hello World!", 15.0),
-            ("Hello World!", 14.0),
-            ("Hello World!", 10.0),
-            ("Hello World!", 15.0),
-            ("Hello World!", 14.0),
-            ("Hello World!", 10.0),
-        ];
-        for &(string, scale) in &strings {
-            println!("Caching {:?}", (string, scale));
-            for glyph in font.layout(string, Scale::uniform(scale), point(0.0, 0.0)) {
-                cache.queue_glyph(0, glyph);
+    /// Groups `glyphs` into classes that would share a cache texture, ie
+    /// rasterise identically within the cache's scale & position tolerances,
+    /// without queueing or caching them.
+    ///
+    /// The number of classes is the number of texture slots the glyphs need,
+    /// so a frame's cache pressure can be estimated, & checked against the
+    /// cache dimensions, before committing to a layout.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, gpu_cache::Cache, point, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let cache = Cache::builder().dimensions(256, 256).build();
+    /// let glyphs: Vec<_> = "aaa bb"
+    ///     .chars()
+    ///     .enumerate()
+    ///     .map(|(i, c)| font.glyph(c).scaled(Scale::uniform(20.0)).positioned(point(12.0 * i as f32, 20.0)))
+    ///     .collect();
+    ///
+    /// let classes = cache.glyph_classes(0, &glyphs);
+    /// // the space is empty
+    /// assert_eq!(classes.classes, [vec![0, 1, 2], vec![4, 5]]);
+    /// assert!(classes.texture_area <= 256 * 256);
+    /// ```
+    pub fn glyph_classes(&self, font_id: usize, glyphs: &[PositionedGlyph<'_>]) -> GlyphClasses {
+        let mut class_for_info = FxHashMap::default();
+        let mut classes = GlyphClasses::default();
+        for (index, glyph) in glyphs.iter().enumerate() {
+            if glyph.pixel_bounding_box().is_none() {
+                continue;
             }
-            cache.cache_queued(|_, _| {}).unwrap();
+            let glyph_info = self.lossy_info_for(font_id, glyph);
+            let Some(raster) = self.raster_glyph(glyph, &glyph_info) else {
+                continue;
+            };
+            let class = *class_for_info.entry(glyph_info).or_insert_with(|| {
+                let (width, height) = self.aligned_size(&raster);
+                let area = u64::from(width) * u64::from(height);
+                classes.texture_area += area;
+                if !self.all_glyphs.contains_key(&glyph_info) {
+                    classes.uncached_area += area;
+                }
+                classes.classes.push(Vec::new());
+                classes.classes.len() - 1
+            });
+            classes.classes[class].push(index);
         }
+        classes
     }
 
-    #[test]
-    fn need_to_check_whole_cache() {
-        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
-        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+    /// Retrieves the texture coordinates of a cached glyph by `key`, without
+    /// needing the `PositionedGlyph`. Pixel-space coordinates are relative to
+    /// an origin at the integer part of the glyph's position, ie for a glyph
+    /// positioned at `(x, y)` offset them by `(x.trunc(), y.trunc())`.
+    ///
+    /// Unlike `rect_for` the pixel-space coordinates are derived from the
+    /// cached glyph, so may differ slightly when a substitute glyph within the
+    /// cache tolerances was found.
+    ///
+    /// Empty glyphs are never cached so always return
+    /// `Err(CacheReadErr::GlyphNotCached)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, gpu_cache::{Cache, CacheKey}, point, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// # let mut cache = Cache::builder().build();
+    /// let glyph = font.glyph('a').scaled(Scale::uniform(25.0)).positioned(point(10.3, 20.0));
+    /// let key = CacheKey::from_glyph(0, &glyph);
+    ///
+    /// cache.queue_glyph(0, glyph);
+    /// cache.cache_queued(|_, _| {}).unwrap();
+    ///
+    /// let (uv_rect, screen_rect) = cache.rect_for_key(key).unwrap();
+    /// ```
+    pub fn rect_for_key(&self, key: CacheKey) -> Result<TextureCoords, CacheReadErr> {
+        let GlyphTexInfo {
+            tex_coords,
+            offset: tex_offset,
+            local_bb,
+            ..
+        } = *self.tex_info_for_key(key)?;
 
-        let glyph = font.glyph('l');
+        let bb = screen_bounds(local_bb, tex_offset, point(0.0, 0.0) + key.offset);
+        Ok((self.uv_rect(tex_coords), bb))
+    }
 
-        let small = glyph.clone().scaled(Scale::uniform(10.0));
-        let large = glyph.clone().scaled(Scale::uniform(10.05));
+    /// Returns the checksum of the pixels uploaded for a cached glyph by
+    /// `key`, see `CacheBuilder::raster_checksums`. `Ok(None)` if checksums
+    /// were not enabled when the glyph was cached.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, gpu_cache::{self, Cache, CacheKey}, point, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// # fn read_gpu_texture(_: rusttype::Rect<u32>) -> Vec<u8> { vec![] }
+    /// let mut cache = Cache::builder().raster_checksums(true).build();
+    /// let glyph = font.glyph('a').scaled(Scale::uniform(25.0)).positioned(point(10.3, 20.0));
+    /// let key = CacheKey::from_glyph(0, &glyph);
+    ///
+    /// cache.queue_glyph(0, glyph);
+    /// cache.cache_queued(|_, _| {}).unwrap();
+    ///
+    /// let uploaded = cache.raster_checksum(key).unwrap().unwrap();
+    /// let gpu_pixels = read_gpu_texture(uploaded.tex_rect);
+    /// if gpu_cache::raster_checksum(&gpu_pixels) != uploaded.checksum {
+    ///     // texture upload or storage is broken
+    /// }
+    /// ```
+    pub fn raster_checksum(&self, key: CacheKey) -> Result<Option<RasterChecksum>, CacheReadErr> {
+        Ok(self.tex_info_for_key(key)?.checksum)
+    }
 
-        let small_left = small.clone().positioned(point(0.0, 0.0));
-        let large_left = large.clone().positioned(point(0.0, 0.0));
-        let large_right = large.clone().positioned(point(-0.2, 0.0));
+    fn tex_info_for_key(&self, key: CacheKey) -> Result<&GlyphTexInfo, CacheReadErr> {
+        let offset = self.key_settings().key_offset(point(0.0, 0.0) + key.offset);
+        let info = self.lossy_info(key.font_id, key.glyph_id, key.scale, offset, key.rounding);
+        let (row, index) = self
+            .all_glyphs
+            .get(&info)
+            .ok_or(CacheReadErr::GlyphNotCached)?;
+        Ok(&self.rows[row].glyphs[*index as usize])
+    }
 
-        let mut cache = Cache::builder()
+    /// Calls `f` with the retained contents of each in use region of the cache
+    /// texture, see `CacheBuilder::retain_texture`. Each region spans the full
+    /// texture width, so data is row major with stride equal to the texture
+    /// width, matching the `cache_queued` uploader.
+    ///
+    /// Re-uploading these regions restores a lost GPU texture. Returns `false`,
+    /// without calling `f`, if the texture is not retained.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::gpu_cache::Cache;
+    /// # fn update_gpu_texture(_: rusttype::Rect<u32>, _: &[u8]) {}
+    /// # let cache = Cache::builder().retain_texture(true).build();
+    /// // after device loss, recreate the texture & re-upload the contents
+    /// cache.texture_snapshot(|region, data| update_gpu_texture(region, data));
+    /// ```
+    pub fn texture_snapshot<F: FnMut(Rect<u32>, &[u8])>(&self, mut f: F) -> bool {
+        let texture = match &self.texture {
+            Some(texture) => texture,
+            None => return false,
+        };
+        for (top, row) in self.rows.iter() {
+            let rect = Rect {
+                min: point(0, *top),
+                max: point(self.width, top + row.height),
+            };
+            let start = *top as usize * self.width as usize;
+            let end = start + rect.height() as usize * self.width as usize;
+            f(rect, &texture.as_slice()[start..end]);
+        }
+        true
+    }
+
+    /// Returns the retained contents of the whole cache texture, row major
+    /// with stride equal to the texture width. See
+    /// `CacheBuilder::retain_texture`.
+    ///
+    /// Regions not used by cached glyphs may contain stale data.
+    pub fn texture_data(&self) -> Option<&[u8]> {
+        self.texture.as_ref().map(|t| t.as_slice())
+    }
+
+    /// Returns an approximate count of the bytes owned by the cache: its
+    /// glyph queue, row & lookup tables, glyph id maps, the retained CPU
+    /// texture & the last `snapshot`, if any. Allocator overhead & fonts
+    /// referenced by queued glyphs are not included.
+    ///
+    /// Useful to account for text rendering in a memory budget.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::gpu_cache::Cache;
+    /// let cache = Cache::builder().retain_texture(true).build();
+    /// assert!(cache.approx_memory_usage() >= 256 * 256);
+    /// ```
+    pub fn approx_memory_usage(&self) -> usize {
+        use std::mem::size_of;
+
+        fn hash_map<K, V, S>(map: &HashMap<K, V, S>) -> usize {
+            // entries & a control byte per bucket
+            map.capacity() * (size_of::<(K, V)>() + 1)
+        }
+
+        let rows: usize = self
+            .rows
+            .values()
+            .map(|row| row.glyphs.capacity() * size_of::<GlyphTexInfo>())
+            .sum::<usize>()
+            // entries & linked list pointers
+            + self.rows.capacity() * (size_of::<(u32, Row)>() + 2 * size_of::<usize>());
+        let glyph_id_maps: usize = self
+            .glyph_id_maps
+            .values()
+            // ids & reverse index entries
+            .map(|map| map.len() * 3 * size_of::<GlyphId>())
+            .sum();
+
+        size_of::<Self>()
+            + rows
+            + hash_map(&self.space_start_for_end)
+            + hash_map(&self.space_end_for_start)
+            + self.queue.capacity() * size_of::<(FontId, GlyphId, PositionedGlyph<'font>)>()
+            + self.queue_tags.capacity() * size_of::<(usize, u32)>()
+            + self.tagged_rects.capacity() * size_of::<TaggedRect>()
+            + hash_map(&self.all_glyphs)
+            + hash_map(&self.glyph_id_maps)
+            + glyph_id_maps
+            + hash_map(&self.data_id_fonts)
+            + self
+                .texture
+                .as_ref()
+                .map_or(0, |t| t.inner_array.capacity())
+            + self
+                .snapshot
+                .get()
+                .map_or(0, |s| s.inner.approx_memory_usage())
+    }
+
+    /// Returns the current generation of the cache contents, incremented by
+    /// each `cache_queued` & `clear`. See `slot_changes_since`.
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns the texture slots whose glyph changed since `generation`, ie
+    /// glyphs cached after it, & whether the cache was cleared since.
+    ///
+    /// Allows mirroring the cache texture remotely, eg for streamed UIs,
+    /// retransmitting only changed slots: record `generation` after each
+    /// sync & send the slots of the next `slot_changes_since` that call.
+    /// If `reset` is `true` all previously mirrored slots are invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, gpu_cache::Cache, point, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// # let mut cache = Cache::builder().build();
+    /// let synced = cache.generation();
+    ///
+    /// for glyph in font.layout("abc", Scale::uniform(20.0), point(0.0, 20.0)) {
+    ///     cache.queue_glyph(0, glyph);
+    /// }
+    /// cache.cache_queued(|_, _| {}).unwrap();
+    ///
+    /// let changes = cache.slot_changes_since(synced);
+    /// assert!(!changes.reset);
+    /// assert_eq!(changes.slots.len(), 3);
+    /// ```
+    pub fn slot_changes_since(&self, generation: u64) -> SlotChanges {
+        let slots = self
+            .rows
+            .values()
+            .flat_map(|row| &row.glyphs)
+            .filter(|g| g.generation > generation)
+            .map(|g| GlyphSlot {
+                glyph: self.evicted_glyph(g),
+                generation: g.generation,
+            })
+            .collect();
+        SlotChanges {
+            reset: self.cleared_generation > generation,
+            slots,
+        }
+    }
+
+    /// Returns the normalised texture coordinates of a glyph texture region.
+    fn uv_rect(&self, tex_rect: Rect<u32>) -> Rect<f32> {
+        let tex_rect = tex_rect.unpadded(self.glyph_padding);
+        let (tex_width, tex_height) = (self.width as f32, self.height as f32);
+        Rect {
+            min: point(
+                tex_rect.min.x as f32 / tex_width,
+                tex_rect.min.y as f32 / tex_height,
+            ),
+            max: point(
+                tex_rect.max.x as f32 / tex_width,
+                tex_rect.max.y as f32 / tex_height,
+            ),
+        }
+    }
+
+    /// Retrieves the texture coordinates of a glyph queued with
+    /// `queue_glyph_with_data_id`. Equivalent to calling `rect_for` with the
+    /// glyph's `Font::data_id` as the `font_id`.
+    pub fn rect_for_glyph(
+        &self,
+        glyph: &PositionedGlyph,
+    ) -> Result<Option<TextureCoords>, CacheReadErr> {
+        self.rect_for(glyph.font().data_id(), glyph)
+    }
+}
+
+/// Pixel bounds of `glyph` positioned at the normalised subpixel `offset`.
+#[inline]
+fn local_bounds(glyph: &PositionedGlyph<'_>, offset: Vector<f32>) -> Rect<i32> {
+    glyph
+        .unpositioned()
+        .clone()
+        .positioned_with_rounding(point(0.0, 0.0) + offset, glyph.rounding())
+        .pixel_bounding_box()
+        .unwrap()
+}
+
+/// Translates `local_bb`, the bounds of a glyph rasterized at `tex_offset`, to
+/// the pixel-space bounds for drawing at `position`.
+#[inline]
+fn screen_bounds(local_bb: Rect<i32>, tex_offset: Vector<f32>, position: Point<f32>) -> Rect<i32> {
+    let min_from_origin =
+        point(local_bb.min.x as f32, local_bb.min.y as f32) - (point(0.0, 0.0) + tex_offset);
+    let ideal_min = min_from_origin + position;
+    let min = point(ideal_min.x.round() as i32, ideal_min.y.round() as i32);
+    let bb_offset = vector(
+        min.x.saturating_sub(local_bb.min.x),
+        min.y.saturating_sub(local_bb.min.y),
+    );
+    local_bb.saturating_translate(bb_offset)
+}
+
+#[inline]
+fn draw_glyph(
+    tex_coords: Rect<u32>,
+    glyph: &PositionedGlyph<'_>,
+    padding: u32,
+    coverage_lut: &[u8; 256],
+    content: Content,
+) -> ByteArray2d {
+    if let Content::Sdf { spread } = content {
+        return sdf::draw_sdf(
+            glyph,
+            tex_coords.width() as usize,
+            tex_coords.height() as usize,
+            padding.saturating_add(content.margin()) as usize,
+            spread,
+        );
+    }
+    let mut pixels = ByteArray2d::zeros(tex_coords.height() as usize, tex_coords.width() as usize);
+    let padding = padding as usize;
+    glyph.draw(|x, y, v| {
+        let v = coverage_lut[usize::from((v * 255.0).round() as u8)];
+        // `+ padding` accounts for top/left glyph padding
+        pixels[(y as usize + padding, x as usize + padding)] = v;
+    });
+    pixels
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Font, Scale};
+    use approx::*;
+
+    #[test]
+    fn cache_test() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+
+        let mut cache: Cache<'static> = Cache::builder()
+            .dimensions(32, 32)
+            .scale_tolerance(0.1)
+            .position_tolerance(0.1)
+            .pad_glyphs(false)
+            .build();
+        let strings = [
+            ("Hello World!", 15.0),
+            ("Hello World!", 14.0),
+            ("Hello World!", 10.0),
+            ("Hello World!", 15.0),
+            ("Hello World!", 14.0),
+            ("Hello World!", 10.0),
+        ];
+        for &(string, scale) in &strings {
+            println!("Caching {:?}", (string, scale));
+            for glyph in font.layout(string, Scale::uniform(scale), point(0.0, 0.0)) {
+                cache.queue_glyph(0, glyph);
+            }
+            cache.cache_queued(|_, _| {}).unwrap();
+        }
+    }
+
+    #[test]
+    fn need_to_check_whole_cache() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+
+        let glyph = font.glyph('l');
+
+        let small = glyph.clone().scaled(Scale::uniform(10.0));
+        let large = glyph.clone().scaled(Scale::uniform(10.05));
+
+        let small_left = small.clone().positioned(point(0.0, 0.0));
+        let large_left = large.clone().positioned(point(0.0, 0.0));
+        let large_right = large.clone().positioned(point(-0.2, 0.0));
+
+        let mut cache = Cache::builder()
             .dimensions(32, 32)
             .scale_tolerance(0.1)
             .position_tolerance(0.1)
@@ -1094,10 +2720,20 @@ mod test {
         let cache = CacheBuilder {
             dimensions: (32, 64),
             scale_tolerance: 0.2,
+            scale_quantization: ScaleQuantization::Perceptual,
             position_tolerance: 0.3,
-            pad_glyphs: false,
+            glyph_padding: 0,
             align_4x4: false,
             multithread: false,
+            multithread_threshold: 16,
+            ignore_subpixel: true,
+            upload_order: UploadOrder::Row,
+            coverage_curve: CoverageCurve::Srgb,
+            content: Content::Sdf { spread: 3.0 },
+            retain_texture: false,
+            raster_checksums: true,
+            expected_glyph_count: 500,
+            on_evict: None,
         }
         .build();
 
@@ -1105,10 +2741,339 @@ mod test {
 
         assert_eq!(to_builder.dimensions, (32, 64));
         assert_relative_eq!(to_builder.scale_tolerance, 0.2);
+        assert_eq!(to_builder.scale_quantization, ScaleQuantization::Perceptual);
         assert_relative_eq!(to_builder.position_tolerance, 0.3);
-        assert_eq!(to_builder.pad_glyphs, false);
-        assert_eq!(to_builder.align_4x4, false);
-        assert_eq!(to_builder.multithread, false);
+        assert_eq!(to_builder.glyph_padding, 0);
+        assert!(!to_builder.align_4x4);
+        assert!(!to_builder.multithread);
+        assert_eq!(to_builder.multithread_threshold, 16);
+        assert!(to_builder.ignore_subpixel);
+        assert_eq!(to_builder.upload_order, UploadOrder::Row);
+        assert_eq!(to_builder.coverage_curve, CoverageCurve::Srgb);
+        assert_eq!(to_builder.content, Content::Sdf { spread: 3.0 });
+        assert!(to_builder.raster_checksums);
+        assert_eq!(to_builder.expected_glyph_count, 500);
+    }
+
+    #[test]
+    fn perceptual_scale_quantization() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+        let glyph = |scale| {
+            font.glyph('a')
+                .scaled(Scale::uniform(scale))
+                .positioned(point(0.0, 0.0))
+        };
+        let shares_texture = |quantization, a, b| {
+            let mut cache = Cache::builder()
+                .dimensions(512, 512)
+                .scale_quantization(quantization)
+                .multithread(false)
+                .build();
+            cache.queue_glyph(0, glyph(a));
+            cache.cache_queued(|_, _| {}).unwrap();
+            cache.rect_for(0, &glyph(b)).is_ok()
+        };
+
+        // large glyphs share textures over a wider scale range
+        assert!(!shares_texture(ScaleQuantization::Linear, 160.0, 160.4));
+        assert!(shares_texture(ScaleQuantization::Perceptual, 160.0, 160.4));
+        // small glyphs are cached more accurately
+        assert!(shares_texture(ScaleQuantization::Linear, 8.0, 8.04));
+        assert!(!shares_texture(ScaleQuantization::Perceptual, 8.0, 8.04));
+        assert!(shares_texture(ScaleQuantization::Perceptual, 8.0, 8.01));
+
+        // evicted scales are close to the cached scale
+        let settings = Cache::builder()
+            .scale_quantization(ScaleQuantization::Perceptual)
+            .build()
+            .key_settings();
+        for scale in [0.5, 8.0, 16.0, 100.0, 1000.0] {
+            let dequantized = settings.dequantize_scale(settings.quantize_scale(scale));
+            assert!((dequantized - scale).abs() <= scale * 0.1 / 16.0, "{scale}");
+        }
+    }
+
+    #[test]
+    fn subpixel_snapping() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+        let glyph = |x| {
+            font.glyph('a')
+                .scaled(Scale::uniform(16.0))
+                .positioned(point(x, 16.0))
+        };
+        let xs: Vec<f32> = (0..200).map(|i| i as f32 * 0.137).collect();
+
+        // 3 bins hit 3 cache entries
+        let mut cache = Cache::builder()
+            .position_tolerance(0.5 / 3.0)
+            .multithread(false)
+            .build();
+        for &x in &xs {
+            let snapped = snap_subpixel(x, 3);
+            assert!((snapped - x).abs() <= 1.0 / 6.0 + 1e-5);
+            cache.queue_glyph(0, glyph(snapped));
+        }
+        cache.cache_queued(|_, _| {}).unwrap();
+        assert_eq!(cache.all_glyphs.len(), 3);
+
+        for tolerance in [0.1, 0.25, 0.4] {
+            let cache = Cache::builder().position_tolerance(tolerance).build();
+            for &x in &xs {
+                let p = point(x, x * 0.71);
+                let snapped = cache.snap_position(p);
+                assert!((snapped.x - p.x).abs() <= tolerance / 2.0 + 1e-4, "{p:?}");
+                assert!((snapped.y - p.y).abs() <= tolerance / 2.0 + 1e-4, "{p:?}");
+                // same cache key as the unsnapped glyph
+                assert_eq!(
+                    cache.lossy_info_for(0, &glyph(snapped.x)),
+                    cache.lossy_info_for(0, &glyph(p.x))
+                );
+            }
+        }
+
+        let cache = Cache::builder().ignore_subpixel(true).build();
+        assert_eq!(cache.snap_position(point(3.4, 2.6)), point(3.0, 3.0));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn debug_image_layout() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+        let mut cache = Cache::builder()
+            .dimensions(128, 128)
+            .multithread(false)
+            .build();
+        // two rows, a glyph too tall for the first row needs another
+        for (c, size) in [('b', 16.0), ('A', 60.0)] {
+            let glyph = font.glyph(c).scaled(Scale::uniform(size));
+            cache.queue_glyph(0, glyph.positioned(point(0.0, 0.0)));
+            cache.cache_queued(|_, _| {}).unwrap();
+        }
+        assert_eq!(cache.rows.len(), 2);
+
+        let image = cache.debug_image();
+        assert_eq!(image.dimensions(), (128, 128));
+        let mut rows = cache.rows.iter();
+        let (&old_top, _) = rows.next().unwrap();
+        let (&new_top, _) = rows.next().unwrap();
+        // row top lines
+        assert_eq!(image.get_pixel(127, old_top).0, [255, 255, 255, 255]);
+        // heatmap from red to green, sampled past the glyphs
+        let old = image.get_pixel(127, old_top + 1).0;
+        let new = image.get_pixel(127, new_top + 1).0;
+        assert!(old[0] > old[1] && new[1] > new[0], "{old:?} {new:?}");
+        // free space
+        assert_eq!(image.get_pixel(127, 127).0, [0, 0, 0, 255]);
+        // glyph outlines
+        for (_, row) in cache.rows.iter() {
+            let rect = row.glyphs[0].tex_coords.unpadded(cache.glyph_padding);
+            assert_eq!(
+                image.get_pixel(rect.min.x, rect.min.y).0,
+                [255, 220, 0, 255]
+            );
+        }
+    }
+
+    #[test]
+    fn snapshot_matches_rect_for() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<CacheSnapshot>();
+
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+        let glyphs: Vec<_> = font
+            .layout("Hello World!", Scale::uniform(18.0), point(0.3, 20.7))
+            .collect();
+        let mut cache = Cache::builder().multithread(false).build();
+        for glyph in &glyphs {
+            cache.queue_glyph(0, glyph.clone());
+        }
+        cache.cache_queued(|_, _| {}).unwrap();
+
+        let snapshot = cache.snapshot();
+        assert_eq!(snapshot.generation(), cache.generation());
+        assert_eq!(snapshot.len(), cache.all_glyphs.len());
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                for glyph in &glyphs {
+                    assert_eq!(snapshot.rect_for(0, glyph), cache.rect_for(0, glyph));
+                    let key = CacheKey::from_glyph(0, glyph);
+                    assert_eq!(snapshot.rect_for_key(key), cache.rect_for_key(key));
+                }
+            });
+        });
+        assert_eq!(
+            snapshot.rect_for(1, &glyphs[0]),
+            Err(CacheReadErr::GlyphNotCached)
+        );
+
+        // shared until the cache changes
+        assert!(Arc::ptr_eq(&snapshot.inner, &cache.snapshot().inner));
+        cache.clear();
+        assert!(cache.snapshot().is_empty());
+        assert!(snapshot.rect_for(0, &glyphs[0]).unwrap().is_some());
+    }
+
+    #[test]
+    fn sdf_content() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+        let glyph = |size: f32, x: f32| {
+            font.glyph('O')
+                .scaled(Scale::uniform(size))
+                .positioned(point(x, 50.0))
+        };
+        let (small, large) = (glyph(40.0, 10.3), glyph(48.0, 60.8));
+
+        let mut cache = Cache::builder()
+            .content(Content::Sdf { spread: 4.0 })
+            .multithread(false)
+            .build();
+        cache.queue_glyph(0, small.clone());
+        cache.queue_glyph(0, large.clone());
+        let mut uploads = Vec::new();
+        cache
+            .cache_queued(|rect, data| uploads.push((rect, data.to_vec())))
+            .unwrap();
+
+        // both sizes & subpixel positions share one texture, drawn at 2^12 / 100
+        assert_eq!(uploads.len(), 1);
+        let (rect, data) = &uploads[0];
+        let raster = glyph(40.96, 0.0).pixel_bounding_box().unwrap();
+        assert_eq!(rect.width(), raster.width() as u32 + 2 * (1 + 4));
+        assert_eq!(rect.height(), raster.height() as u32 + 2 * (1 + 4));
+
+        // padding is far outside, the ring of the 'O' inside
+        let pixels = |y: u32| &data[(y * rect.width()) as usize..][..rect.width() as usize];
+        assert!(pixels(0).iter().all(|&v| v == 0));
+        let middle = pixels(rect.height() / 2);
+        assert_eq!(middle[0], 0);
+        assert!(middle.iter().any(|&v| v > 160));
+        // crosses the outline smoothly
+        assert!(middle.windows(2).all(|w| w[0].abs_diff(w[1]) <= 64));
+
+        // screen rects include the scaled distance field margin
+        for (g, margin) in [(&small, 4), (&large, 5)] {
+            let (_, screen) = cache.rect_for(0, g).unwrap().unwrap();
+            let bb = g.pixel_bounding_box().unwrap();
+            assert_eq!(screen.min, bb.min - vector(margin, margin));
+            assert_eq!(screen.max, bb.max + vector(margin, margin));
+            assert_eq!(cache.snapshot().rect_for(0, g), cache.rect_for(0, g));
+        }
+        let key = CacheKey::from_glyph(0, &large);
+        assert_eq!(cache.snapshot().rect_for_key(key), cache.rect_for_key(key));
+
+        // no pixels at the cached scale, so not cached
+        let thin = font
+            .glyph('-')
+            .scaled(Scale::uniform(6.0))
+            .positioned_with_rounding(point(0.0, 0.0), PixelRounding::Round);
+        assert!(thin.pixel_bounding_box().unwrap().width() > 0);
+        cache.queue_glyph(0, thin.clone());
+        cache.cache_queued(|_, _| panic!("uploaded")).unwrap();
+        assert_eq!(cache.rect_for(0, &thin), Ok(None));
+        assert_eq!(cache.snapshot().rect_for(0, &thin), Ok(None));
+        assert!(cache.glyph_classes(0, &[thin]).classes.is_empty());
+
+        // a different octave is cached separately
+        cache.queue_glyph(0, glyph(96.0, 0.0));
+        cache.queue_glyph(0, small.clone());
+        cache.cache_queued(|_, _| {}).unwrap();
+        assert_eq!(cache.all_glyphs.len(), 2);
+    }
+
+    #[test]
+    fn tagged_rects() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+        let glyphs: Vec<_> = font
+            .layout("ab c", Scale::uniform(18.0), point(0.3, 20.0))
+            .collect();
+        let mut cache = Cache::builder()
+            .dimensions(64, 64)
+            .multithread(false)
+            .build();
+        cache.queue_glyph_tagged(0, glyphs[0].clone(), 7);
+        cache.queue_glyph(0, glyphs[1].clone());
+        // empty, not queued
+        cache.queue_glyph_tagged(0, glyphs[2].clone(), 8);
+        cache.queue_glyph_tagged(0, glyphs[3].clone(), 9);
+        // a duplicate glyph has its own tag
+        cache.queue_glyph_tagged(0, glyphs[0].clone(), 10);
+        assert!(cache.tagged_rects().is_empty());
+        cache.cache_queued(|_, _| {}).unwrap();
+
+        let tags: Vec<_> = cache.tagged_rects().iter().map(|r| r.tag).collect();
+        assert_eq!(tags, [7, 9, 10]);
+        for (rect, glyph) in cache.tagged_rects().iter().zip([0, 3, 0]) {
+            let (uv_rect, screen_rect) = cache.rect_for(0, &glyphs[glyph]).unwrap().unwrap();
+            assert_eq!((rect.uv_rect, rect.screen_rect), (uv_rect, screen_rect));
+        }
+
+        // a failed caching keeps the tagged queue
+        let big = font
+            .glyph('a')
+            .scaled(Scale::uniform(200.0))
+            .positioned(point(0.0, 0.0));
+        cache.queue_glyph_tagged(0, glyphs[1].clone(), 1);
+        cache.queue_glyph_tagged(0, big, 2);
+        assert_eq!(
+            cache.cache_queued(|_, _| {}),
+            Err(CacheWriteErr::GlyphTooLarge)
+        );
+        assert!(cache.tagged_rects().is_empty());
+        assert_eq!(cache.queue_tags, [(0, 1), (1, 2)]);
+        cache.clear_queue();
+        cache.queue_glyph_tagged(0, glyphs[1].clone(), 1);
+        cache.cache_queued(|_, _| {}).unwrap();
+        assert_eq!(cache.tagged_rects().len(), 1);
+        assert_eq!(cache.tagged_rects()[0].tag, 1);
+
+        cache.clear();
+        assert!(cache.tagged_rects().is_empty());
+        cache.queue_glyph_tagged(0, glyphs[0].clone(), 3);
+        cache.clear_queue();
+        cache.queue_glyph(0, glyphs[0].clone());
+        cache.cache_queued(|_, _| {}).unwrap();
+        assert!(cache.tagged_rects().is_empty());
+    }
+
+    #[test]
+    fn expected_glyph_count_preallocates() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+        let builder = Cache::builder().dimensions(128, 128).multithread(false);
+        let mut plain = builder.clone().build();
+        let mut preallocated = builder.expected_glyph_count(1000).build();
+        assert!(preallocated.all_glyphs.capacity() >= 1000);
+        assert!(preallocated.queue.capacity() >= 1000);
+
+        // packing & eviction don't depend on allocation, frames overflow the
+        // cache so rows are evicted & reused
+        for frame in 0..6 {
+            let text: String = (0..40)
+                .map(|i| char::from_u32(0x4e00 + frame * 37 + i).unwrap())
+                .collect();
+            let glyphs: Vec<_> = font
+                .layout(&text, Scale::uniform(14.0), point(0.0, 14.0))
+                .collect();
+            for cache in [&mut plain, &mut preallocated] {
+                for glyph in &glyphs {
+                    cache.queue_glyph(0, glyph.clone());
+                }
+                cache.cache_queued(|_, _| {}).unwrap();
+            }
+            for glyph in &glyphs {
+                assert_eq!(plain.rect_for(0, glyph), preallocated.rect_for(0, glyph));
+            }
+        }
+
+        let rebuilt_builder = plain.to_builder().expected_glyph_count(2000);
+        rebuilt_builder.rebuild(&mut plain);
+        assert!(plain.all_glyphs.capacity() >= 2000);
     }
 
     #[test]
@@ -1148,15 +3113,17 @@ mod test {
             .pad_glyphs(true)
             .align_4x4(false)
             .multithread(false)
+            .ignore_subpixel(true)
             .rebuild(&mut cache);
 
         assert_eq!(cache.width, 64);
         assert_eq!(cache.height, 128);
         assert_relative_eq!(cache.scale_tolerance, 0.05);
         assert_relative_eq!(cache.position_tolerance, 0.15);
-        assert_eq!(cache.pad_glyphs, true);
-        assert_eq!(cache.align_4x4, false);
-        assert_eq!(cache.multithread, false);
+        assert_eq!(cache.glyph_padding, 1);
+        assert!(!cache.align_4x4);
+        assert!(!cache.multithread);
+        assert!(cache.ignore_subpixel);
 
         assert!(
             cache.all_glyphs.is_empty(),
@@ -1189,6 +3156,671 @@ mod test {
         assert_eq!(cache.cache_queued(|_, _| {}), Ok(CachedBy::Reordering));
     }
 
+    #[test]
+    fn glyph_id_map_stable_keys() {
+        let font = Font::try_from_bytes(include_bytes!(
+            "../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf"
+        ) as &[u8])
+        .unwrap();
+        let positioned = |c| {
+            font.glyph(c)
+                .scaled(Scale::uniform(20.0))
+                .positioned(point(0.0, 0.0))
+        };
+        let (l, a) = (positioned('l'), positioned('a'));
+        let map_to_original = |subset_id: GlyphId| {
+            let mut original = vec![GlyphId(0); subset_id.0 as usize + 1];
+            original[subset_id.0 as usize] = GlyphId(500);
+            GlyphIdMap::new(original)
+        };
+
+        let mut cache = Cache::builder().build();
+        cache.set_glyph_id_map(0, Some(map_to_original(l.id())));
+        let l_info = cache.lossy_info_for(0, &l);
+        assert_eq!(l_info.glyph_id, GlyphId(500));
+
+        // a regenerated subset assigns original glyph 500 a different id
+        cache.set_glyph_id_map(0, Some(map_to_original(a.id())));
+        assert_eq!(cache.lossy_info_for(0, &a), l_info);
+
+        cache.set_glyph_id_map(0, None);
+        assert_eq!(cache.lossy_info_for(0, &a).glyph_id, a.id());
+    }
+
+    #[test]
+    fn glyph_id_map_change_keeps_queued_ids() {
+        let font = Font::try_from_bytes(include_bytes!(
+            "../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf"
+        ) as &[u8])
+        .unwrap();
+        let positioned = |c| {
+            font.glyph(c)
+                .scaled(Scale::uniform(20.0))
+                .positioned(point(0.0, 0.0))
+        };
+        let (l, a) = (positioned('l'), positioned('a'));
+        let map_to_original = |subset_id: GlyphId| {
+            let mut original = vec![GlyphId(0); subset_id.0 as usize + 1];
+            original[subset_id.0 as usize] = GlyphId(500);
+            GlyphIdMap::new(original)
+        };
+
+        let mut cache = Cache::builder().build();
+        cache.set_glyph_id_map(0, Some(map_to_original(l.id())));
+        cache.queue_glyph_tagged(0, l.clone(), 7);
+
+        // switch to a regenerated subset before the old one's glyphs are cached
+        cache.set_glyph_id_map(0, Some(map_to_original(a.id())));
+        cache.cache_queued(|_, _| {}).unwrap();
+
+        assert_eq!(cache.tagged_rects().len(), 1);
+        assert!(cache.rect_for(0, &a).unwrap().is_some());
+    }
+
+    #[test]
+    fn font_data_id_keys() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+        let other_font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+        let glyph = |font: &Font<'static>| {
+            font.glyph('l')
+                .scaled(Scale::uniform(20.0))
+                .positioned(point(0.0, 0.0))
+        };
+
+        let mut cache = Cache::builder().build();
+        cache.queue_glyph_with_data_id(glyph(&font));
+        cache.cache_queued(|_, _| {}).unwrap();
+
+        assert!(cache.rect_for_glyph(&glyph(&font.clone())).is_ok());
+        assert_eq!(
+            cache.rect_for_glyph(&glyph(&other_font)),
+            Err(CacheReadErr::GlyphNotCached)
+        );
+
+        // the cache holds the font, so its data id isn't reused while cached
+        let data_id = font.data_id();
+        drop(font);
+        assert_eq!(cache.data_id_fonts[&data_id].data_id(), data_id);
+        cache.clear();
+        assert!(cache.data_id_fonts.is_empty());
+    }
+
+    #[test]
+    fn on_evict_recycled_rows() {
+        use std::sync::Mutex;
+
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        let mut cache = Cache::builder()
+            .dimensions(24, 32)
+            .on_evict({
+                let evicted = Arc::clone(&evicted);
+                move |e: &EvictedGlyph| evicted.lock().unwrap().push(*e)
+            })
+            .build();
+
+        let b = font.glyph('B').scaled(Scale::uniform(24.0));
+        let b_pos = b.clone().positioned(point(0.0, 0.0));
+        cache.queue_glyph(0, b_pos.clone());
+        cache.cache_queued(|_, _| {}).unwrap();
+        assert!(evicted.lock().unwrap().is_empty());
+        let (_, b_screen) = cache.rect_for(0, &b_pos).unwrap().unwrap();
+
+        // no room for another row, so the row containing 'B' is recycled
+        let x = font.glyph('X').scaled(Scale::uniform(24.0));
+        cache.queue_glyph(0, x.positioned(point(0.0, 0.0)));
+        assert_eq!(cache.cache_queued(|_, _| {}), Ok(CachedBy::Adding));
+
+        let evicted = evicted.lock().unwrap();
+        let evicted_b = evicted
+            .iter()
+            .find(|e| e.glyph_id == b.id())
+            .expect("'B' evicted");
+        assert_eq!(evicted_b.font_id, 0);
+        assert_relative_eq!(evicted_b.scale.y, 24.0);
+        assert_eq!(evicted_b.tex_coords.width() as i32, b_screen.width());
+        assert_eq!(evicted_b.tex_coords.height() as i32, b_screen.height());
+    }
+
+    #[test]
+    fn rebuild_evicts_with_old_settings() {
+        use std::sync::Mutex;
+
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+
+        let old_evicted = Arc::new(Mutex::new(Vec::new()));
+        let new_evicted = Arc::new(Mutex::new(Vec::new()));
+        let mut cache = Cache::builder()
+            .glyph_padding(1)
+            .on_evict({
+                let evicted = Arc::clone(&old_evicted);
+                move |e: &EvictedGlyph| evicted.lock().unwrap().push(*e)
+            })
+            .build();
+        let glyph = font
+            .glyph('B')
+            .scaled(Scale::uniform(24.0))
+            .positioned(point(0.0, 0.0));
+        cache.queue_glyph(0, glyph.clone());
+        cache.cache_queued(|_, _| {}).unwrap();
+        let (_, screen) = cache.rect_for(0, &glyph).unwrap().unwrap();
+
+        cache
+            .to_builder()
+            .dimensions(128, 64)
+            .glyph_padding(4)
+            .on_evict({
+                let evicted = Arc::clone(&new_evicted);
+                move |e: &EvictedGlyph| evicted.lock().unwrap().push(*e)
+            })
+            .rebuild(&mut cache);
+
+        assert!(new_evicted.lock().unwrap().is_empty());
+        let old_evicted = old_evicted.lock().unwrap();
+        assert_eq!(old_evicted.len(), 1);
+        assert_eq!(old_evicted[0].tex_coords.min, point(1, 1));
+        assert_eq!(old_evicted[0].tex_coords.width() as i32, screen.width());
+
+        // free space covers the new height
+        cache.queue_glyph(0, glyph);
+        cache.cache_queued(|_, _| {}).unwrap();
+        assert_eq!(cache.space_end_for_start.values().max(), Some(&64));
+    }
+
+    #[test]
+    fn rect_for_key_matches_rect_for() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+
+        let mut cache = Cache::builder().dimensions(128, 128).build();
+        let glyphs: Vec<_> = font
+            .layout("Hello World", Scale::uniform(18.3), point(10.4, 30.7))
+            .filter(|g| g.pixel_bounding_box().is_some())
+            .collect();
+        for glyph in &glyphs {
+            cache.queue_glyph(0, glyph.clone());
+        }
+        cache.cache_queued(|_, _| {}).unwrap();
+
+        for glyph in &glyphs {
+            let (uv, screen) = cache.rect_for(0, glyph).unwrap().unwrap();
+            let (key_uv, key_screen) = cache.rect_for_key(CacheKey::from_glyph(0, glyph)).unwrap();
+            let origin = vector(glyph.position().x as i32, glyph.position().y as i32);
+
+            assert_eq!(uv, key_uv);
+            assert_eq!(screen.min, key_screen.min + origin);
+            assert_eq!(screen.max, key_screen.max + origin);
+        }
+    }
+
+    #[test]
+    fn ignore_subpixel() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+        let glyph = font.glyph('a').scaled(Scale::uniform(16.0));
+
+        let mut cache = Cache::builder().ignore_subpixel(true).build();
+        cache.queue_glyph(0, glyph.clone().positioned(point(10.3, 4.2)));
+        cache.queue_glyph(0, glyph.clone().positioned(point(2.6, 1.45)));
+        let mut uploads = 0;
+        cache.cache_queued(|_, _| uploads += 1).unwrap();
+        assert_eq!(uploads, 1);
+
+        let at = point(20.4, 7.3);
+        let (_, screen) = cache
+            .rect_for(0, &glyph.clone().positioned(at))
+            .unwrap()
+            .unwrap();
+        let snapped = glyph.positioned(point(20.0, 7.0)).pixel_bounding_box();
+        assert_eq!(Some(screen), snapped);
+    }
+
+    #[test]
+    fn glyph_padding() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+        let glyph = font
+            .glyph('l')
+            .scaled(Scale::uniform(25.0))
+            .positioned(point(0.0, 0.0));
+        let bb = glyph.pixel_bounding_box().unwrap();
+
+        let mut cache = Cache::builder().dimensions(64, 64).glyph_padding(4).build();
+        cache.queue_glyph(0, glyph.clone());
+        cache
+            .cache_queued(|rect, data| {
+                assert_eq!(rect.width() as i32, bb.width() + 8);
+                assert_eq!(rect.height() as i32, bb.height() + 8);
+                // padding rows & columns are empty
+                let width = rect.width() as usize;
+                assert!(data[..4 * width].iter().all(|v| *v == 0));
+                assert!(data.chunks(width).all(|row| row[..4] == [0; 4]));
+            })
+            .unwrap();
+
+        let (uv_rect, _) = cache.rect_for(0, &glyph).unwrap().unwrap();
+        assert_relative_eq!(uv_rect.min.x, 4.0 / 64.0);
+        assert_relative_eq!(uv_rect.min.y, 4.0 / 64.0);
+        assert_relative_eq!(uv_rect.max.x, (4 + bb.width()) as f32 / 64.0);
+    }
+
+    #[test]
+    fn builder_rebuild_glyph_padding() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+        let glyph = font
+            .glyph('l')
+            .scaled(Scale::uniform(25.0))
+            .positioned(point(0.0, 0.0));
+        let bb = glyph.pixel_bounding_box().unwrap();
+
+        let mut cache = Cache::builder().dimensions(64, 64).build();
+        cache.queue_glyph(0, glyph.clone());
+        cache.cache_queued(|_, _| {}).unwrap();
+
+        cache.to_builder().glyph_padding(3).rebuild(&mut cache);
+        assert_eq!(cache.glyph_padding, 3);
+        cache.queue_glyph(0, glyph.clone());
+        cache
+            .cache_queued(|rect, _| assert_eq!(rect.width() as i32, bb.width() + 6))
+            .unwrap();
+
+        // paddings too large to fit don't overflow
+        cache
+            .to_builder()
+            .glyph_padding(u32::MAX)
+            .rebuild(&mut cache);
+        cache.queue_glyph(0, glyph);
+        assert_eq!(
+            cache.cache_queued(|_, _| {}),
+            Err(CacheWriteErr::GlyphTooLarge)
+        );
+    }
+
+    #[test]
+    fn retained_texture_snapshot() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+
+        let mut cache = Cache::builder()
+            .dimensions(64, 64)
+            .retain_texture(true)
+            .build();
+        let mut gpu_texture = vec![0; 64 * 64];
+        let mut upload = |rect: Rect<u32>, data: &[u8]| {
+            let width = rect.width() as usize;
+            for (y, row) in (rect.min.y as usize..).zip(data.chunks(width)) {
+                let start = y * 64 + rect.min.x as usize;
+                gpu_texture[start..start + width].copy_from_slice(row);
+            }
+        };
+        for glyph in font.layout("Hello", Scale::uniform(16.0), point(0.0, 0.0)) {
+            cache.queue_glyph(0, glyph);
+        }
+        cache.cache_queued(&mut upload).unwrap();
+        assert_eq!(cache.texture_data(), Some(gpu_texture.as_slice()));
+
+        let mut restored = vec![0; 64 * 64];
+        assert!(cache.texture_snapshot(|rect, data| {
+            let start = rect.min.y as usize * 64;
+            restored[start..start + data.len()].copy_from_slice(data);
+        }));
+        assert_eq!(restored, gpu_texture);
+
+        let cache = cache.to_builder().retain_texture(false).build();
+        assert!(!cache.texture_snapshot(|_, _| {}));
+    }
+
+    #[test]
+    fn coverage_curve_maps_uploads() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+
+        let texture = |coverage_curve| {
+            let mut cache = Cache::builder()
+                .dimensions(64, 64)
+                .multithread(false)
+                .coverage_curve(coverage_curve)
+                .retain_texture(true)
+                .build();
+            for glyph in font.layout("Hello", Scale::uniform(16.0), point(0.0, 0.0)) {
+                cache.queue_glyph(0, glyph);
+            }
+            cache.cache_queued(|_, _| {}).unwrap();
+            cache.texture_data().unwrap().to_vec()
+        };
+        let linear = texture(CoverageCurve::Linear);
+        assert!(linear.iter().any(|&v| v > 0 && v < 255));
+
+        let mut inverse = [0; 256];
+        for (index, v) in inverse.iter_mut().enumerate() {
+            *v = 255 - index as u8;
+        }
+        let inverted = texture(CoverageCurve::Lut(Arc::new(inverse)));
+        assert!(linear
+            .iter()
+            .zip(&inverted)
+            .all(|(&l, &i)| i == 255 - l || l == 0));
+
+        // sRGB encoding brightens partial coverage
+        let srgb = texture(CoverageCurve::Srgb);
+        assert!(linear.iter().zip(&srgb).all(|(&l, &s)| s >= l));
+        assert!(linear.iter().zip(&srgb).any(|(&l, &s)| s > l));
+    }
+
+    #[test]
+    fn coverage_curve_saturates_overlapping_contours() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+        // the ring overlaps the 'A', summing coverage up to 2
+        let glyph = font
+            .glyph('\u{c5}')
+            .scaled(Scale::uniform(40.0))
+            .positioned(point(0.0, 0.0));
+        let mut max_coverage = 0.0_f32;
+        glyph.draw(|_, _, v| max_coverage = max_coverage.max(v));
+        assert!(max_coverage > 1.5);
+
+        let mut inverse = [0; 256];
+        for (index, v) in inverse.iter_mut().enumerate() {
+            *v = 255 - index as u8;
+        }
+        let mut cache = Cache::builder()
+            .multithread(false)
+            .coverage_curve(CoverageCurve::Lut(Arc::new(inverse)))
+            .build();
+        cache.queue_glyph(0, glyph);
+        let mut uploaded = Vec::new();
+        cache
+            .cache_queued(|_, data| uploaded.extend_from_slice(data))
+            .unwrap();
+        // clamped to full coverage
+        assert!(uploaded.contains(&0));
+    }
+
+    #[test]
+    fn multithread_upload_order() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+
+        let uploads = |multithread| {
+            let mut cache = Cache::builder()
+                .dimensions(256, 256)
+                .multithread(multithread)
+                .build();
+            for glyph in font.layout(
+                "The quick brown fox jumps over the lazy dog",
+                Scale::uniform(18.0),
+                point(0.0, 0.0),
+            ) {
+                cache.queue_glyph(0, glyph);
+            }
+            let mut uploads = Vec::new();
+            cache
+                .cache_queued(|rect, data| uploads.push((rect, data.to_vec())))
+                .unwrap();
+            uploads
+        };
+
+        let single_thread = uploads(false);
+        for _ in 0..5 {
+            assert_eq!(uploads(true), single_thread);
+        }
+    }
+
+    #[test]
+    fn approx_memory_usage_grows() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+
+        let mut cache = Cache::builder().dimensions(256, 256).build();
+        let empty = cache.approx_memory_usage();
+        for glyph in font.layout("Memory budget", Scale::uniform(18.0), point(0.0, 0.0)) {
+            cache.queue_glyph(0, glyph);
+        }
+        let queued = cache.approx_memory_usage();
+        assert!(queued > empty);
+        cache.cache_queued(|_, _| {}).unwrap();
+        let cached = cache.approx_memory_usage();
+        assert!(cached > empty);
+        cache.snapshot();
+        assert!(cache.approx_memory_usage() > cached);
+
+        Cache::builder()
+            .dimensions(256, 256)
+            .retain_texture(true)
+            .rebuild(&mut cache);
+        assert!(cache.approx_memory_usage() >= empty + 256 * 256);
+    }
+
+    #[test]
+    fn raster_checksums_match_uploads() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+
+        let mut cache = Cache::builder()
+            .dimensions(256, 256)
+            .raster_checksums(true)
+            .build();
+        let glyphs: Vec<_> = font
+            .layout("Checksum", Scale::uniform(18.0), point(0.0, 0.0))
+            .collect();
+        for glyph in &glyphs {
+            cache.queue_glyph(0, glyph.clone());
+        }
+        let mut uploads = HashMap::new();
+        cache
+            .cache_queued(|rect, data| {
+                uploads.insert(rect, raster_checksum(data));
+            })
+            .unwrap();
+
+        for glyph in &glyphs {
+            let checksum = cache
+                .raster_checksum(CacheKey::from_glyph(0, glyph))
+                .unwrap()
+                .unwrap();
+            assert_eq!(uploads[&checksum.tex_rect], checksum.checksum);
+        }
+
+        cache
+            .to_builder()
+            .raster_checksums(false)
+            .rebuild(&mut cache);
+        let glyph = &glyphs[0];
+        cache.queue_glyph(0, glyph.clone());
+        cache.cache_queued(|_, _| {}).unwrap();
+        assert_eq!(
+            cache.raster_checksum(CacheKey::from_glyph(0, glyph)),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn quads_for_matches_rect_for() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+
+        let mut cache = Cache::builder().dimensions(256, 256).build();
+        let glyphs: Vec<_> = font
+            .layout("Instanced quads", Scale::uniform(18.0), point(3.3, 20.0))
+            .collect();
+        let mut quads = vec![[0.0; 8]];
+        let mut indices = vec![0];
+        assert_eq!(
+            cache.quads_for(0, &glyphs, &mut quads),
+            Err(CacheReadErr::GlyphNotCached)
+        );
+        assert_eq!(
+            cache.indexed_quads_for(0, &glyphs, &mut quads, &mut indices),
+            Err(CacheReadErr::GlyphNotCached)
+        );
+        assert_eq!((quads.len(), indices.len()), (1, 1));
+
+        for glyph in &glyphs {
+            cache.queue_glyph(0, glyph.clone());
+        }
+        cache.cache_queued(|_, _| {}).unwrap();
+
+        assert_eq!(cache.quads_for(0, &glyphs, &mut quads), Ok(14));
+        let expected: Vec<_> = glyphs
+            .iter()
+            .enumerate()
+            .filter_map(|(index, g)| Some((index, cache.rect_for(0, g).unwrap()?)))
+            .map(|(index, (uv, bb))| {
+                let quad = [
+                    uv.min.x,
+                    uv.min.y,
+                    uv.max.x,
+                    uv.max.y,
+                    bb.min.x as f32,
+                    bb.min.y as f32,
+                    bb.max.x as f32,
+                    bb.max.y as f32,
+                ];
+                (index, quad)
+            })
+            .collect();
+        assert!(quads[1..].iter().eq(expected.iter().map(|(_, quad)| quad)));
+
+        let mut indexed = Vec::new();
+        assert_eq!(
+            cache.indexed_quads_for(0, &glyphs, &mut indexed, &mut indices),
+            Ok(14)
+        );
+        assert_eq!(indexed, quads[1..]);
+        assert!(indices[1..]
+            .iter()
+            .eq(expected.iter().map(|(index, _)| index)));
+        // no quad for the space
+        assert_eq!(indices[1 + 8..1 + 10], [8, 10]);
+    }
+
+    #[test]
+    fn glyph_classes_match_cached_textures() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+        let mut cache = Cache::builder()
+            .dimensions(256, 256)
+            .position_tolerance(0.5)
+            .build();
+        let glyphs: Vec<_> = font
+            .layout(
+                "Lorem ipsum dolor sit amet",
+                Scale::uniform(16.3),
+                point(0.0, 20.0),
+            )
+            .collect();
+
+        let classes = cache.glyph_classes(0, &glyphs);
+        assert_eq!(classes.texture_area, classes.uncached_area);
+        for class in &classes.classes {
+            let info = cache.lossy_info_for(0, &glyphs[class[0]]);
+            assert!(class
+                .iter()
+                .all(|&i| cache.lossy_info_for(0, &glyphs[i]) == info));
+        }
+        let classified: usize = classes.classes.iter().map(Vec::len).sum();
+        assert_eq!(classified, glyphs.len() - 4); // 4 spaces
+
+        for glyph in &glyphs {
+            cache.queue_glyph(0, glyph.clone());
+        }
+        cache.cache_queued(|_, _| {}).unwrap();
+        // one texture per class
+        assert_eq!(cache.all_glyphs.len(), classes.classes.len());
+
+        let cached = cache.glyph_classes(0, &glyphs);
+        assert_eq!(cached.classes, classes.classes);
+        assert_eq!(cached.uncached_area, 0);
+    }
+
+    #[test]
+    fn slot_changes_since_generation() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+        let mut cache = Cache::builder().dimensions(256, 256).build();
+        let cache_text = |cache: &mut Cache<'_>, text| {
+            for glyph in font.layout(text, Scale::uniform(18.0), point(0.0, 0.0)) {
+                cache.queue_glyph(0, glyph);
+            }
+            cache.cache_queued(|_, _| {}).unwrap();
+        };
+
+        let start = cache.generation();
+        cache_text(&mut cache, "ab");
+        let first = cache.generation();
+        assert!(first > start);
+        cache_text(&mut cache, "abc");
+
+        let changes = cache.slot_changes_since(start);
+        assert!(!changes.reset);
+        assert_eq!(changes.slots.len(), 3);
+
+        // only the newly cached 'c'
+        let changes = cache.slot_changes_since(first);
+        assert!(!changes.reset);
+        assert_eq!(changes.slots.len(), 1);
+        assert_eq!(changes.slots[0].glyph.glyph_id, font.glyph('c').id());
+        assert_eq!(changes.slots[0].generation, cache.generation());
+
+        let synced = cache.generation();
+        assert_eq!(cache.slot_changes_since(synced), SlotChanges::default());
+        cache.clear();
+        assert!(cache.slot_changes_since(synced).reset);
+    }
+
+    fn upload_rects(upload_order: UploadOrder) -> Vec<Rect<u32>> {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+
+        let mut cache = Cache::builder()
+            .dimensions(128, 128)
+            .upload_order(upload_order)
+            .build();
+        for glyph in font.layout(
+            "Jackdaws love my big sphinx",
+            Scale::uniform(18.0),
+            point(0.0, 0.0),
+        ) {
+            cache.queue_glyph(0, glyph);
+        }
+        let mut rects = Vec::new();
+        cache.cache_queued(|rect, _| rects.push(rect)).unwrap();
+        rects
+    }
+
+    #[test]
+    fn uploads_never_overlap() {
+        let rects = upload_rects(UploadOrder::Packing);
+        for (i, a) in rects.iter().enumerate() {
+            for b in &rects[i + 1..] {
+                let overlap = a.min.x < b.max.x
+                    && b.min.x < a.max.x
+                    && a.min.y < b.max.y
+                    && b.min.y < a.max.y;
+                assert!(!overlap, "{:?} overlaps {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn uploads_in_row_order() {
+        let mut packing = upload_rects(UploadOrder::Packing);
+        let rows = upload_rects(UploadOrder::Row);
+        assert!(rows
+            .windows(2)
+            .all(|w| (w[0].min.y, w[0].min.x) < (w[1].min.y, w[1].min.x)));
+
+        // same uploads, just ordered differently
+        packing.sort_by_key(|r| (r.min.y, r.min.x));
+        assert_eq!(packing, rows);
+    }
+
     #[test]
     fn align_4x4() {
         // First, test align_4x4 disabled, to confirm non-4x4 alignment
@@ -0,0 +1,247 @@
+//! Simulated texture uploads & cache workloads, for benchmarking a `Cache`
+//! configuration against realistic usage without a GPU. These are the
+//! workloads of rusttype's own cache benchmarks.
+//!
+//! # Example
+//!
+//! ```
+//! use rusttype::gpu_cache::{bench_support::*, Cache};
+//! # use rusttype::Font;
+//! # let font_data: &[u8] = include_bytes!("../../dev/fonts/dejavu/DejaVuSansMono.ttf");
+//! # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+//!
+//! let fonts = [font];
+//! let text = "Lorem ipsum dolor sit amet, consectetur adipiscing elit";
+//! let frames = moving_text_frames(&fonts, text, 20);
+//!
+//! let mut cache = Cache::builder().dimensions(512, 512).build();
+//! let mut uploader = MockUploader::default();
+//! for frame in frames.iter().cycle().take(6) {
+//!     cache_frame(&mut cache, frame, |rect, data| uploader.upload(rect, data)).unwrap();
+//! }
+//! assert!(uploader.uploads > 0);
+//! ```
+use super::{Cache, CacheWriteErr, CachedBy};
+use crate::{point, Font, PositionedGlyph, Rect, Scale};
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+/// Glyphs to draw in a frame, grouped by the `font_id` they are queued with.
+pub type Frame<'font> = Vec<(usize, Vec<PositionedGlyph<'font>>)>;
+
+/// Simulated latency of a texture upload, see `mock_gpu_upload`.
+pub const MOCK_UPLOAD_LATENCY: Duration = Duration::from_micros(2);
+
+/// Largest dimensions `cache_frame_resizing` grows a cache to, a common
+/// GPU texture size limit.
+pub const MAX_RESIZE_DIMENSIONS: (u32, u32) = (16384, 16384);
+
+/// A `Cache::cache_queued` uploader that busy waits `MOCK_UPLOAD_LATENCY`
+/// to simulate a GPU texture upload. Doesn't wait on wasm32, which has no
+/// `Instant`.
+pub fn mock_gpu_upload(_region: Rect<u32>, _bytes: &[u8]) {
+    busy_wait(MOCK_UPLOAD_LATENCY);
+}
+
+/// A simulated uploader that busy waits `latency` per upload & counts
+/// uploads.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MockUploader {
+    /// Simulated latency of each upload, defaults to `MOCK_UPLOAD_LATENCY`.
+    pub latency: Duration,
+    /// Number of uploads made.
+    pub uploads: usize,
+    /// Total bytes uploaded.
+    pub bytes: usize,
+}
+
+impl Default for MockUploader {
+    fn default() -> Self {
+        Self {
+            latency: MOCK_UPLOAD_LATENCY,
+            uploads: 0,
+            bytes: 0,
+        }
+    }
+}
+
+impl MockUploader {
+    /// Simulates uploading `bytes` into `region`.
+    pub fn upload(&mut self, _region: Rect<u32>, bytes: &[u8]) {
+        busy_wait(self.latency);
+        self.uploads += 1;
+        self.bytes += bytes.len();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn busy_wait(duration: Duration) {
+    let now = Instant::now();
+    while now.elapsed() < duration {}
+}
+
+#[cfg(target_arch = "wasm32")]
+fn busy_wait(_duration: Duration) {}
+
+/// Lays out `text` wrapping lines at `width` pixels, honouring `'\n'` &
+/// skipping other control chars.
+pub fn layout_paragraph<'font>(
+    font: &Font<'font>,
+    scale: Scale,
+    width: u32,
+    text: &str,
+) -> Vec<PositionedGlyph<'font>> {
+    let mut result = Vec::new();
+    let v_metrics = font.v_metrics(scale);
+    let advance_height = v_metrics.ascent - v_metrics.descent + v_metrics.line_gap;
+    let mut caret = point(0.0, v_metrics.ascent);
+    let mut last_glyph_id = None;
+    for c in text.chars() {
+        if c.is_control() {
+            if c == '\n' {
+                caret = point(0.0, caret.y + advance_height)
+            }
+            continue;
+        }
+        let base_glyph = font.glyph(c);
+        if let Some(id) = last_glyph_id.take() {
+            caret.x += font.pair_kerning(scale, id, base_glyph.id());
+        }
+        last_glyph_id = Some(base_glyph.id());
+        let mut glyph = base_glyph.scaled(scale).positioned(caret);
+        if let Some(bb) = glyph.pixel_bounding_box() {
+            if bb.max.x > width as i32 {
+                caret = point(0.0, caret.y + advance_height);
+                glyph.set_position(caret);
+                last_glyph_id = None;
+            }
+        }
+        caret.x += glyph.unpositioned().h_metrics().advance_width;
+        result.push(glyph);
+    }
+    result
+}
+
+/// Lays out `text` as 500 pixel wide paragraphs at several close scales
+/// around 25 pixels, which stress the cache's scale & position tolerances.
+pub fn multi_scale_glyphs<'font>(font: &Font<'font>, text: &str) -> Vec<PositionedGlyph<'font>> {
+    // scales found by brute force to reproduce a GlyphNotCached issue, which
+    // occurs when position_tolerance is < 1.0
+    [25_f32, 24.5, 25.01, 24.7, 24.99]
+        .iter()
+        .flat_map(|&scale| layout_paragraph(font, Scale::uniform(scale), 500, text))
+        .collect()
+}
+
+/// Returns three frames of `multi_scale_glyphs` for each font, of windows
+/// of `window_chars` chars from the start, middle & end of `text`, using
+/// the index of each font as its `font_id`.
+///
+/// Cycling through the frames simulates text moving through a view, eg
+/// scrolling. A cache only a little larger than a frame thrashes.
+pub fn moving_text_frames<'font>(
+    fonts: &[Font<'font>],
+    text: &str,
+    window_chars: usize,
+) -> Vec<Frame<'font>> {
+    let chars: Vec<_> = text.chars().collect();
+    let windows: Vec<_> = chars.windows(window_chars.max(1)).collect();
+    if windows.is_empty() {
+        return Vec::new();
+    }
+    [0, windows.len() / 2, windows.len() - 1]
+        .iter()
+        .map(|&index| {
+            let window: String = windows[index].iter().collect();
+            fonts
+                .iter()
+                .enumerate()
+                .map(|(font_id, font)| (font_id, multi_scale_glyphs(font, &window)))
+                .collect()
+        })
+        .collect()
+}
+
+/// Queues & caches the glyphs of a frame, then looks up each glyph as a
+/// renderer would.
+///
+/// # Panics
+///
+/// If a glyph is not cached after caching succeeds.
+pub fn cache_frame<'font, F>(
+    cache: &mut Cache<'font>,
+    frame: &[(usize, Vec<PositionedGlyph<'font>>)],
+    uploader: F,
+) -> Result<CachedBy, CacheWriteErr>
+where
+    F: FnMut(Rect<u32>, &[u8]),
+{
+    for (font_id, glyphs) in frame {
+        for glyph in glyphs {
+            cache.queue_glyph(*font_id, glyph.clone());
+        }
+    }
+    let cached_by = cache.cache_queued(uploader)?;
+    assert_cached(cache, frame);
+    Ok(cached_by)
+}
+
+/// Caches a frame like `cache_frame`, doubling the cache dimensions with
+/// `CacheBuilder::rebuild` until the frame fits, as renderers do when the
+/// texture is too small.
+///
+/// Returns the caching error if the frame doesn't fit within
+/// `MAX_RESIZE_DIMENSIONS`.
+pub fn cache_frame_resizing<'font, F>(
+    cache: &mut Cache<'font>,
+    frame: &[(usize, Vec<PositionedGlyph<'font>>)],
+    mut uploader: F,
+) -> Result<CachedBy, CacheWriteErr>
+where
+    F: FnMut(Rect<u32>, &[u8]),
+{
+    for (font_id, glyphs) in frame {
+        for glyph in glyphs {
+            cache.queue_glyph(*font_id, glyph.clone());
+        }
+    }
+    loop {
+        match cache.cache_queued(&mut uploader) {
+            Ok(cached_by) => {
+                assert_cached(cache, frame);
+                return Ok(cached_by);
+            }
+            Err(err) => {
+                let (width, height) = cache.dimensions();
+                let (max_width, max_height) = MAX_RESIZE_DIMENSIONS;
+                if width >= max_width && height >= max_height {
+                    return Err(err);
+                }
+                cache
+                    .to_builder()
+                    .dimensions(
+                        width.saturating_mul(2).min(max_width).max(width),
+                        height.saturating_mul(2).min(max_height).max(height),
+                    )
+                    .rebuild(cache);
+            }
+        }
+    }
+}
+
+fn assert_cached<'font>(cache: &Cache<'font>, frame: &[(usize, Vec<PositionedGlyph<'font>>)]) {
+    for (font_id, glyphs) in frame {
+        for (index, glyph) in glyphs.iter().enumerate() {
+            let rect = cache.rect_for(*font_id, glyph);
+            assert!(
+                rect.is_ok(),
+                "Gpu cache rect lookup failed ({:?}) for font {} glyph index {}, id {}",
+                rect,
+                font_id,
+                index,
+                glyph.id().0
+            );
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! Rendering the cache layout to an image, for diagnosing packing & thrashing.
+use super::{Cache, PaddingAware};
+use image::{Rgba, RgbaImage};
+
+/// Colour of row boundary lines.
+const ROW_LINE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+/// Colour of glyph rect outlines.
+const GLYPH_OUTLINE: Rgba<u8> = Rgba([255, 220, 0, 255]);
+
+impl Cache<'_> {
+    /// Renders the current cache layout into an image the size of the cache
+    /// texture, to diagnose packing problems & thrashing.
+    ///
+    /// * Rows are filled with their LRU age as a heatmap, from red for the
+    ///   least recently used row, evicted next, to green for the most
+    ///   recently used. Free space is black.
+    /// * Row boundaries are drawn as white lines along each row top.
+    /// * Glyph rects, excluding padding, are outlined in yellow.
+    /// * With `CacheBuilder::retain_texture` the cached glyph coverage is
+    ///   drawn over the heatmap in white.
+    ///
+    /// Requires the `image` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{gpu_cache::Cache, point, Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let mut cache = Cache::builder().retain_texture(true).build();
+    /// for glyph in font.layout("Hello", Scale::uniform(24.0), point(0.0, 24.0)) {
+    ///     cache.queue_glyph(0, glyph);
+    /// }
+    /// cache.cache_queued(|_, _| {}).unwrap();
+    ///
+    /// let image = cache.debug_image();
+    /// assert_eq!(image.dimensions(), cache.dimensions());
+    /// // image.save("cache.png")
+    /// ```
+    pub fn debug_image(&self) -> RgbaImage {
+        let mut image = RgbaImage::from_pixel(self.width, self.height, Rgba([0, 0, 0, 255]));
+
+        // rows are in LRU order, least recently used first
+        let rows = self.rows.len();
+        for (age, (&top, row)) in self.rows.iter().enumerate() {
+            let heat = heat_color(age, rows);
+            let bottom = (top + row.height).min(self.height);
+            for y in top..bottom {
+                for x in 0..self.width {
+                    let coverage = self
+                        .texture
+                        .as_ref()
+                        .map_or(0, |texture| texture[(y as usize, x as usize)]);
+                    image.put_pixel(x, y, blend(heat, coverage));
+                }
+            }
+
+            for glyph in &row.glyphs {
+                let rect = glyph.tex_coords.unpadded(self.glyph_padding);
+                if rect.width() == 0 || rect.height() == 0 {
+                    continue;
+                }
+                for x in rect.min.x..rect.max.x {
+                    image.put_pixel(x, rect.min.y, GLYPH_OUTLINE);
+                    image.put_pixel(x, rect.max.y - 1, GLYPH_OUTLINE);
+                }
+                for y in rect.min.y..rect.max.y {
+                    image.put_pixel(rect.min.x, y, GLYPH_OUTLINE);
+                    image.put_pixel(rect.max.x - 1, y, GLYPH_OUTLINE);
+                }
+            }
+
+            if top < self.height {
+                for x in 0..self.width {
+                    image.put_pixel(x, top, ROW_LINE);
+                }
+            }
+        }
+        image
+    }
+}
+
+/// Dimmed red to green by the row's position in LRU order, `age` 0 being
+/// least recently used.
+fn heat_color(age: usize, rows: usize) -> [u8; 3] {
+    let recency = if rows > 1 {
+        age as f32 / (rows - 1) as f32
+    } else {
+        1.0
+    };
+    let scale = |v: f32| (v * 160.0).round() as u8;
+    [scale(1.0 - recency), scale(recency), 0]
+}
+
+/// Draws white glyph `coverage` over the heatmap colour.
+fn blend(heat: [u8; 3], coverage: u8) -> Rgba<u8> {
+    let c = u16::from(coverage);
+    let mix = |v: u8| ((u16::from(v) * (255 - c) + 255 * c) / 255) as u8;
+    Rgba([mix(heat[0]), mix(heat[1]), mix(heat[2]), 255])
+}
@@ -0,0 +1,65 @@
+//! Signed distance field glyph textures, see `Content::Sdf`.
+use super::ByteArray2d;
+use crate::PositionedGlyph;
+
+/// Rasterizes `glyph` as a signed distance field into a `width` x `height`
+/// texture, the glyph drawn `inset` texels from the top left.
+///
+/// Distances are measured to the nearest texel of the other side of the
+/// outline, coverage `0.5`, within `spread` texels & refined by the texel's
+/// own coverage. `128` is on the outline, `255` is `spread` texels inside &
+/// `0` `spread` texels outside.
+pub(super) fn draw_sdf(
+    glyph: &PositionedGlyph<'_>,
+    width: usize,
+    height: usize,
+    inset: usize,
+    spread: f32,
+) -> ByteArray2d {
+    let mut coverage = vec![0.0_f32; width * height];
+    glyph.draw(|x, y, v| {
+        coverage[(y as usize + inset) * width + x as usize + inset] = v;
+    });
+    let inside = |x: usize, y: usize| coverage[y * width + x] >= 0.5;
+
+    let radius = spread.ceil().max(1.0) as isize;
+    let mut pixels = ByteArray2d::zeros(height, width);
+    for y in 0..height {
+        for x in 0..width {
+            let is_inside = inside(x, y);
+
+            // squared distance to the nearest texel on the other side
+            let mut nearest_sq = None;
+            for dy in -radius..=radius {
+                let ny = y as isize + dy;
+                if ny < 0 || ny >= height as isize {
+                    continue;
+                }
+                for dx in -radius..=radius {
+                    let nx = x as isize + dx;
+                    if nx < 0 || nx >= width as isize {
+                        continue;
+                    }
+                    if inside(nx as usize, ny as usize) != is_inside {
+                        let d_sq = dx * dx + dy * dy;
+                        if nearest_sq.is_none_or(|n| d_sq < n) {
+                            nearest_sq = Some(d_sq);
+                        }
+                    }
+                }
+            }
+
+            let own = coverage[y * width + x] - 0.5;
+            let distance = match nearest_sq {
+                // texels next to the outline are placed by their coverage
+                Some(d_sq) if is_inside => (d_sq as f32).sqrt() - 1.0 + own,
+                Some(d_sq) => own - ((d_sq as f32).sqrt() - 1.0),
+                None if is_inside => spread,
+                None => -spread,
+            };
+            let value = 0.5 + distance / (2.0 * spread);
+            pixels[(y, x)] = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+    }
+    pixels
+}
@@ -0,0 +1,163 @@
+//! Read only views of a cache, see `Cache::snapshot`.
+use super::{
+    original_id, screen_bounds, Cache, CacheKey, CacheReadErr, FontId, FxHashMap, KeySettings,
+    LossyGlyphInfo, TextureCoords,
+};
+use crate::{point, GlyphId, GlyphIdMap, PositionedGlyph, Rect, Vector};
+use std::fmt;
+use std::sync::Arc;
+
+/// A read only view of the glyphs in a `Cache` after a `cache_queued` call,
+/// see `Cache::snapshot`.
+///
+/// Snapshots are `Send + Sync` & cheap to clone, sharing their data, so can
+/// look up glyph texture coordinates on many threads, eg building command
+/// buffers in parallel, while the `Cache` itself is kept by the thread
+/// calling `cache_queued`. Lookups match `Cache::rect_for` at the time the
+/// snapshot was taken, later cache changes are not seen.
+#[derive(Clone)]
+pub struct CacheSnapshot {
+    pub(super) inner: Arc<SnapshotData>,
+}
+
+pub(super) struct SnapshotData {
+    settings: KeySettings,
+    glyph_id_maps: FxHashMap<FontId, GlyphIdMap>,
+    glyphs: FxHashMap<LossyGlyphInfo, SnapshotGlyph>,
+    generation: u64,
+}
+
+struct SnapshotGlyph {
+    uv_rect: Rect<f32>,
+    /// Normalised subpixel offset the glyph was rasterized at
+    offset: Vector<f32>,
+    /// Pixel bounds of the glyph rasterized at `offset`
+    local_bb: Rect<i32>,
+}
+
+impl SnapshotData {
+    /// Approximate bytes owned, see `Cache::approx_memory_usage`.
+    pub(super) fn approx_memory_usage(&self) -> usize {
+        use std::mem::size_of;
+
+        let glyph_id_maps: usize = self
+            .glyph_id_maps
+            .values()
+            .map(|map| map.len() * 3 * size_of::<GlyphId>())
+            .sum();
+        size_of::<Self>()
+            // entries & a control byte per bucket
+            + self.glyphs.capacity() * (size_of::<(LossyGlyphInfo, SnapshotGlyph)>() + 1)
+            + self.glyph_id_maps.capacity() * (size_of::<(FontId, GlyphIdMap)>() + 1)
+            + glyph_id_maps
+    }
+}
+
+impl CacheSnapshot {
+    pub(super) fn new(cache: &Cache<'_>) -> Self {
+        let glyphs = cache
+            .rows
+            .values()
+            .flat_map(|row| &row.glyphs)
+            .map(|g| {
+                let glyph = SnapshotGlyph {
+                    uv_rect: cache.uv_rect(g.tex_coords),
+                    offset: g.offset,
+                    local_bb: g.local_bb,
+                };
+                (g.glyph_info, glyph)
+            })
+            .collect();
+        Self {
+            inner: Arc::new(SnapshotData {
+                settings: cache.key_settings(),
+                glyph_id_maps: cache.glyph_id_maps.clone(),
+                glyphs,
+                generation: cache.generation,
+            }),
+        }
+    }
+
+    /// Retrieves the texture & pixel-space coordinates of a glyph, as
+    /// `Cache::rect_for`.
+    pub fn rect_for(
+        &self,
+        font_id: usize,
+        glyph: &PositionedGlyph<'_>,
+    ) -> Result<Option<TextureCoords>, CacheReadErr> {
+        if glyph.pixel_bounding_box().is_none() {
+            return Ok(None);
+        }
+        let data = &*self.inner;
+        let glyph_id = original_id(&data.glyph_id_maps, font_id, glyph.id());
+        let info = data.settings.lossy_info_for(font_id, glyph_id, glyph);
+        let Some(SnapshotGlyph {
+            uv_rect, offset, ..
+        }) = data.glyphs.get(&info)
+        else {
+            // too small to draw at the cached scale, so never cached
+            if data.settings.raster_glyph(glyph, &info).is_none() {
+                return Ok(None);
+            }
+            return Err(CacheReadErr::GlyphNotCached);
+        };
+
+        let bb = data.settings.screen_bounds_for(glyph, &info, *offset);
+        Ok(Some((*uv_rect, bb)))
+    }
+
+    /// Retrieves the texture coordinates of a glyph queued with
+    /// `queue_glyph_with_data_id`, as `Cache::rect_for_glyph`.
+    pub fn rect_for_glyph(
+        &self,
+        glyph: &PositionedGlyph<'_>,
+    ) -> Result<Option<TextureCoords>, CacheReadErr> {
+        self.rect_for(glyph.font().data_id(), glyph)
+    }
+
+    /// Retrieves the texture coordinates of a cached glyph by key, as
+    /// `Cache::rect_for_key`.
+    pub fn rect_for_key(&self, key: CacheKey) -> Result<TextureCoords, CacheReadErr> {
+        let data = &*self.inner;
+        let offset = data.settings.key_offset(point(0.0, 0.0) + key.offset);
+        let info = data.settings.lossy_info(
+            key.font_id,
+            original_id(&data.glyph_id_maps, key.font_id, key.glyph_id),
+            key.scale,
+            offset,
+            key.rounding,
+        );
+        let SnapshotGlyph {
+            uv_rect,
+            offset: tex_offset,
+            local_bb,
+        } = data.glyphs.get(&info).ok_or(CacheReadErr::GlyphNotCached)?;
+
+        let bb = screen_bounds(*local_bb, *tex_offset, point(0.0, 0.0) + key.offset);
+        Ok((*uv_rect, bb))
+    }
+
+    /// Returns the `Cache::generation` the snapshot was taken at.
+    pub fn generation(&self) -> u64 {
+        self.inner.generation
+    }
+
+    /// Returns the number of cached glyph textures in the snapshot.
+    pub fn len(&self) -> usize {
+        self.inner.glyphs.len()
+    }
+
+    /// Returns `true` if the snapshot has no cached glyphs.
+    pub fn is_empty(&self) -> bool {
+        self.inner.glyphs.is_empty()
+    }
+}
+
+impl fmt::Debug for CacheSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CacheSnapshot")
+            .field("generation", &self.generation())
+            .field("glyphs", &self.len())
+            .finish_non_exhaustive()
+    }
+}
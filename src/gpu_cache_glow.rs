@@ -0,0 +1,268 @@
+//! OpenGL texture management for `gpu_cache::Cache` using
+//! [glow](https://docs.rs/glow).
+//!
+//! Cache uploads are tightly packed single byte rows of any width, whereas
+//! OpenGL defaults to expecting rows aligned to 4 bytes (`UNPACK_ALIGNMENT`).
+//! Uploading without accounting for this corrupts glyphs of most widths.
+//! `CacheTexture::upload` & `CacheTexture::cache_queued` set the alignment,
+//! & with `CacheTextureFormat::Red` a zero `UNPACK_ROW_LENGTH`, for their
+//! uploads, restoring the previous values afterwards.
+//!
+//! Requires the `glow` feature.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use rusttype::{gpu_cache::Cache, gpu_cache_glow::*, point, Font, Scale};
+//! # unsafe fn example(gl: &glow::Context, font: &Font<'static>) -> Result<(), String> {
+//! let mut cache = Cache::builder().dimensions(512, 512).build();
+//! let mut cache_texture = CacheTexture::new(gl, &cache, CacheTextureFormat::Red)?;
+//!
+//! for glyph in font.layout("Hello", Scale::uniform(24.0), point(0.0, 24.0)) {
+//!     cache.queue_glyph(0, glyph);
+//! }
+//! cache_texture.cache_queued(gl, &mut cache).unwrap();
+//!
+//! // bind `cache_texture.texture()` & draw using `cache.rect_for(..)` uvs
+//! # Ok(())
+//! # }
+//! ```
+use crate::gpu_cache::{Cache, CacheWriteErr, CachedBy};
+use crate::Rect;
+use glow::HasContext;
+
+/// Single channel texture formats for cache textures.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CacheTextureFormat {
+    /// `R8` internal format, `RED` data. For OpenGL 3+, GLES 3+ & WebGL 2.
+    #[default]
+    Red,
+    /// `ALPHA`. For GLES 2 & WebGL 1.
+    Alpha,
+    /// `LUMINANCE`. For GLES 2 & WebGL 1.
+    Luminance,
+}
+
+impl CacheTextureFormat {
+    #[inline]
+    fn internal_format(self) -> i32 {
+        (match self {
+            Self::Red => glow::R8,
+            Self::Alpha => glow::ALPHA,
+            Self::Luminance => glow::LUMINANCE,
+        }) as _
+    }
+
+    #[inline]
+    fn format(self) -> u32 {
+        match self {
+            Self::Red => glow::RED,
+            Self::Alpha => glow::ALPHA,
+            Self::Luminance => glow::LUMINANCE,
+        }
+    }
+}
+
+/// A `TEXTURE_2D` texture matching the dimensions of a `gpu_cache::Cache`,
+/// that receives the cache's texture uploads.
+///
+/// The texture is not deleted on drop, use `delete`.
+///
+/// # Safety
+///
+/// All methods taking a `gl` context must be called with the context the
+/// texture was created with, while it is current. Methods leave the cache
+/// texture bound to `TEXTURE_2D`.
+#[derive(Debug)]
+pub struct CacheTexture<T> {
+    texture: T,
+    format: CacheTextureFormat,
+    dimensions: (u32, u32),
+}
+
+impl<T: Copy> CacheTexture<T> {
+    /// Creates a texture matching the `cache` dimensions, with linear
+    /// filtering & clamp to edge wrapping.
+    ///
+    /// # Safety
+    ///
+    /// See `CacheTexture`.
+    pub unsafe fn new<GL: HasContext<Texture = T>>(
+        gl: &GL,
+        cache: &Cache<'_>,
+        format: CacheTextureFormat,
+    ) -> Result<Self, String> {
+        let texture = gl.create_texture()?;
+        let mut cache_texture = Self {
+            texture,
+            format,
+            dimensions: (0, 0),
+        };
+        cache_texture.allocate(gl, cache.dimensions());
+        Ok(cache_texture)
+    }
+
+    unsafe fn allocate<GL: HasContext<Texture = T>>(
+        &mut self,
+        gl: &GL,
+        (width, height): (u32, u32),
+    ) {
+        gl.bind_texture(glow::TEXTURE_2D, Some(self.texture));
+        for (parameter, value) in [
+            (glow::TEXTURE_MIN_FILTER, glow::LINEAR),
+            (glow::TEXTURE_MAG_FILTER, glow::LINEAR),
+            (glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE),
+            (glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE),
+        ] {
+            gl.tex_parameter_i32(glow::TEXTURE_2D, parameter, value as _);
+        }
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            self.format.internal_format(),
+            width as _,
+            height as _,
+            0,
+            self.format.format(),
+            glow::UNSIGNED_BYTE,
+            glow::PixelUnpackData::Slice(None),
+        );
+        self.dimensions = (width, height);
+    }
+
+    /// Returns the texture.
+    #[inline]
+    pub fn texture(&self) -> T {
+        self.texture
+    }
+
+    /// Returns the texture format.
+    #[inline]
+    pub fn format(&self) -> CacheTextureFormat {
+        self.format
+    }
+
+    /// Reallocates the texture storage if it no longer matches the `cache`
+    /// dimensions, eg after `Cache::to_builder().dimensions(..).rebuild(..)`.
+    ///
+    /// Returns `true` if reallocated.
+    ///
+    /// # Safety
+    ///
+    /// See `CacheTexture`.
+    pub unsafe fn resize_to<GL: HasContext<Texture = T>>(
+        &mut self,
+        gl: &GL,
+        cache: &Cache<'_>,
+    ) -> bool {
+        if self.dimensions == cache.dimensions() {
+            return false;
+        }
+        self.allocate(gl, cache.dimensions());
+        true
+    }
+
+    /// Writes cache texture data into the region `rect`, using an
+    /// `UNPACK_ALIGNMENT` of 1. Suitable as a `Cache::cache_queued` uploader.
+    ///
+    /// # Safety
+    ///
+    /// See `CacheTexture`.
+    pub unsafe fn upload<GL: HasContext<Texture = T>>(
+        &self,
+        gl: &GL,
+        rect: Rect<u32>,
+        data: &[u8],
+    ) {
+        let unpack = self.set_unpack_state(gl);
+        self.upload_packed(gl, rect, data);
+        self.restore_unpack_state(gl, unpack);
+    }
+
+    /// Sets the pixel store state for tightly packed uploads, returning the
+    /// previous `UNPACK_ALIGNMENT` & `UNPACK_ROW_LENGTH`.
+    ///
+    /// `UNPACK_ROW_LENGTH` is left alone for the GLES 2 & WebGL 1 formats,
+    /// which lack it.
+    unsafe fn set_unpack_state<GL: HasContext<Texture = T>>(&self, gl: &GL) -> (i32, Option<i32>) {
+        let alignment = gl.get_parameter_i32(glow::UNPACK_ALIGNMENT);
+        gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+        let row_length = match self.format {
+            CacheTextureFormat::Red => {
+                let row_length = gl.get_parameter_i32(glow::UNPACK_ROW_LENGTH);
+                gl.pixel_store_i32(glow::UNPACK_ROW_LENGTH, 0);
+                Some(row_length)
+            }
+            _ => None,
+        };
+        gl.bind_texture(glow::TEXTURE_2D, Some(self.texture));
+        (alignment, row_length)
+    }
+
+    unsafe fn restore_unpack_state<GL: HasContext<Texture = T>>(
+        &self,
+        gl: &GL,
+        (alignment, row_length): (i32, Option<i32>),
+    ) {
+        gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, alignment);
+        if let Some(row_length) = row_length {
+            gl.pixel_store_i32(glow::UNPACK_ROW_LENGTH, row_length);
+        }
+    }
+
+    /// Uploads `data` into `rect` of the bound texture, with the state set by
+    /// `set_unpack_state`.
+    unsafe fn upload_packed<GL: HasContext<Texture = T>>(
+        &self,
+        gl: &GL,
+        rect: Rect<u32>,
+        data: &[u8],
+    ) {
+        gl.tex_sub_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            rect.min.x as _,
+            rect.min.y as _,
+            rect.width() as _,
+            rect.height() as _,
+            self.format.format(),
+            glow::UNSIGNED_BYTE,
+            glow::PixelUnpackData::Slice(Some(data)),
+        );
+    }
+
+    /// Resizes the texture if necessary, see `resize_to`, then caches all
+    /// queued glyphs uploading them into the texture.
+    ///
+    /// If the texture was reallocated `CachedBy::Reordering` is returned, as
+    /// previous texture positions are no longer valid.
+    ///
+    /// # Safety
+    ///
+    /// See `CacheTexture`.
+    pub unsafe fn cache_queued<GL: HasContext<Texture = T>>(
+        &mut self,
+        gl: &GL,
+        cache: &mut Cache<'_>,
+    ) -> Result<CachedBy, CacheWriteErr> {
+        let resized = self.resize_to(gl, cache);
+        let unpack = self.set_unpack_state(gl);
+        let cached_by = cache.cache_queued(|rect, data| self.upload_packed(gl, rect, data));
+        self.restore_unpack_state(gl, unpack);
+        let cached_by = cached_by?;
+        Ok(if resized {
+            CachedBy::Reordering
+        } else {
+            cached_by
+        })
+    }
+
+    /// Deletes the texture.
+    ///
+    /// # Safety
+    ///
+    /// See `CacheTexture`.
+    pub unsafe fn delete<GL: HasContext<Texture = T>>(self, gl: &GL) {
+        gl.delete_texture(self.texture);
+    }
+}
@@ -0,0 +1,163 @@
+//! [wgpu](https://docs.rs/wgpu) texture management for `gpu_cache::Cache`.
+//!
+//! Requires the `wgpu` feature.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use rusttype::{gpu_cache::Cache, gpu_cache_wgpu::CacheTexture, point, Font, Scale};
+//! # fn example(device: &wgpu::Device, queue: &wgpu::Queue, font: &Font<'static>) {
+//! let mut cache = Cache::builder().dimensions(512, 512).build();
+//! let mut cache_texture = CacheTexture::new(device, &cache);
+//!
+//! for glyph in font.layout("Hello", Scale::uniform(24.0), point(0.0, 24.0)) {
+//!     cache.queue_glyph(0, glyph);
+//! }
+//! cache_texture.cache_queued(device, queue, &mut cache).unwrap();
+//!
+//! // bind `cache_texture.view()` & draw using `cache.rect_for(..)` uvs
+//! # }
+//! ```
+use crate::gpu_cache::{Cache, CacheWriteErr, CachedBy};
+use crate::Rect;
+
+/// A `wgpu::Texture` matching the dimensions of a `gpu_cache::Cache`, that
+/// receives the cache's texture uploads.
+///
+/// Glyph coverage is uploaded into a single channel `R8Unorm` texture by
+/// default, sample the red channel as alpha.
+#[derive(Debug)]
+pub struct CacheTexture {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    format: wgpu::TextureFormat,
+}
+
+impl CacheTexture {
+    /// Creates a `R8Unorm` texture matching the `cache` dimensions.
+    pub fn new(device: &wgpu::Device, cache: &Cache<'_>) -> Self {
+        Self::with_format(device, cache, wgpu::TextureFormat::R8Unorm)
+    }
+
+    /// Creates a texture matching the `cache` dimensions with the given
+    /// `format`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `format` does not have single byte texels, as the cache
+    /// provides single byte coverage per texel. For example `R8Unorm` or
+    /// `R8Uint`.
+    pub fn with_format(
+        device: &wgpu::Device,
+        cache: &Cache<'_>,
+        format: wgpu::TextureFormat,
+    ) -> Self {
+        assert_eq!(
+            format.block_copy_size(None),
+            Some(1),
+            "cache texture format must have single byte texels"
+        );
+        let (width, height) = cache.dimensions();
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("rusttype::gpu_cache"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        Self {
+            texture,
+            view,
+            format,
+        }
+    }
+
+    /// Returns the texture.
+    #[inline]
+    pub fn texture(&self) -> &wgpu::Texture {
+        &self.texture
+    }
+
+    /// Returns a default view of the texture.
+    #[inline]
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    /// Returns the texture format.
+    #[inline]
+    pub fn format(&self) -> wgpu::TextureFormat {
+        self.format
+    }
+
+    /// Recreates the texture if it no longer matches the `cache` dimensions,
+    /// eg after `Cache::to_builder().dimensions(..).rebuild(..)`.
+    ///
+    /// Returns `true` if recreated, in which case bind groups using the old
+    /// texture view must be recreated too.
+    pub fn resize_to(&mut self, device: &wgpu::Device, cache: &Cache<'_>) -> bool {
+        let (width, height) = cache.dimensions();
+        let size = self.texture.size();
+        if (size.width, size.height) == (width, height) {
+            return false;
+        }
+        *self = Self::with_format(device, cache, self.format);
+        true
+    }
+
+    /// Writes cache texture data into the region `rect`. Suitable as a
+    /// `Cache::cache_queued` uploader.
+    pub fn upload(&self, queue: &wgpu::Queue, rect: Rect<u32>, data: &[u8]) {
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: rect.min.x,
+                    y: rect.min.y,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            data,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(rect.width()),
+                rows_per_image: Some(rect.height()),
+            },
+            wgpu::Extent3d {
+                width: rect.width(),
+                height: rect.height(),
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    /// Resizes the texture if necessary, see `resize_to`, then caches all
+    /// queued glyphs uploading them into the texture.
+    ///
+    /// If the texture was recreated `CachedBy::Reordering` is returned, as
+    /// previous texture positions are no longer valid.
+    pub fn cache_queued(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        cache: &mut Cache<'_>,
+    ) -> Result<CachedBy, CacheWriteErr> {
+        let resized = self.resize_to(device, cache);
+        let cached_by = cache.cache_queued(|rect, data| self.upload(queue, rect, data))?;
+        Ok(if resized {
+            CachedBy::Reordering
+        } else {
+            cached_by
+        })
+    }
+}
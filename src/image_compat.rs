@@ -0,0 +1,149 @@
+//! Rendering text into [image](https://docs.rs/image) crate buffers.
+use crate::{point, Font, Point, PositionedGlyph, Scale};
+use image::{GrayImage, Luma, Rgba, RgbaImage};
+
+/// Renders a single line of `text` into a new image sized to fit it, with a
+/// transparent background.
+///
+/// The image height is the font's ascent to descent at `scale`, the width
+/// spans the left-most to the right-most pixel drawn. Text is shifted so the
+/// left-most pixel is in the first column, glyphs extending left of the
+/// layout start, eg with a negative left side bearing, aren't clipped.
+///
+/// Requires the `image` feature.
+///
+/// # Example
+///
+/// ```
+/// # use rusttype::{Font, Scale};
+/// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+/// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+/// let image = rusttype::render_text_to_image(
+///     &font,
+///     "Hello",
+///     Scale::uniform(24.0),
+///     image::Rgba([0, 0, 0, 255]),
+/// );
+/// assert_eq!(image.height(), 24);
+/// ```
+pub fn render_text_to_image(
+    font: &Font<'_>,
+    text: &str,
+    scale: Scale,
+    color: Rgba<u8>,
+) -> RgbaImage {
+    let (width, height, origin) = text_image_size(font, text, scale);
+    let mut image = RgbaImage::new(width, height);
+    draw_text_onto_image(&mut image, font, text, scale, origin, color);
+    image
+}
+
+/// Renders a single line of `text` into a new grayscale image of glyph
+/// coverage, sized as `render_text_to_image`.
+///
+/// Requires the `image` feature.
+pub fn render_text_to_gray_image(font: &Font<'_>, text: &str, scale: Scale) -> GrayImage {
+    let (width, height, origin) = text_image_size(font, text, scale);
+    let mut image = GrayImage::new(width, height);
+    for glyph in font.layout(text, scale, origin) {
+        for_each_pixel_in(&glyph, width, height, |x, y, v| {
+            let Luma([c]) = image.get_pixel_mut(x, y);
+            *c = c.saturating_add((v * 255.0).round() as u8);
+        });
+    }
+    image
+}
+
+/// Draws a single line of `text` onto `image`, blending with the existing
+/// pixels. `position` is the baseline origin of the first glyph. Glyphs, or
+/// parts of glyphs, outside the image are clipped.
+///
+/// Requires the `image` feature.
+pub fn draw_text_onto_image(
+    image: &mut RgbaImage,
+    font: &Font<'_>,
+    text: &str,
+    scale: Scale,
+    position: Point<f32>,
+    color: Rgba<u8>,
+) {
+    for glyph in font.layout(text, scale, position) {
+        draw_glyph_onto_image(image, &glyph, color);
+    }
+}
+
+/// Draws `glyph` onto `image` in `color`, blending with the existing pixels.
+/// Parts of the glyph outside the image are clipped.
+///
+/// Requires the `image` feature.
+pub fn draw_glyph_onto_image(image: &mut RgbaImage, glyph: &PositionedGlyph<'_>, color: Rgba<u8>) {
+    let (width, height) = image.dimensions();
+    for_each_pixel_in(glyph, width, height, |x, y, v| {
+        let pixel = image.get_pixel_mut(x, y);
+        *pixel = blend_over(*pixel, color, v);
+    });
+}
+
+/// Alpha composites `color`, with its alpha multiplied by `coverage`, over
+/// `dst`. Exact for opaque & fully transparent pixels.
+fn blend_over(dst: Rgba<u8>, color: Rgba<u8>, coverage: f32) -> Rgba<u8> {
+    let src_a = color[3] as f32 / 255.0 * coverage;
+    let dst_a = dst[3] as f32 / 255.0;
+    let out_a = src_a + dst_a * (1.0 - src_a);
+    if out_a <= 0.0 {
+        return Rgba([0, 0, 0, 0]);
+    }
+    let channel = |s: u8, d: u8| {
+        let c = (s as f32 * src_a + d as f32 * dst_a * (1.0 - src_a)) / out_a;
+        c.round().clamp(0.0, 255.0) as u8
+    };
+    Rgba([
+        channel(color[0], dst[0]),
+        channel(color[1], dst[1]),
+        channel(color[2], dst[2]),
+        (out_a * 255.0).round() as u8,
+    ])
+}
+
+/// Calls `o` for each pixel of `glyph` with coverage in image coordinates,
+/// skipping pixels outside `width` x `height`.
+fn for_each_pixel_in(
+    glyph: &PositionedGlyph<'_>,
+    width: u32,
+    height: u32,
+    mut o: impl FnMut(u32, u32, f32),
+) {
+    let bb = match glyph.pixel_bounding_box() {
+        Some(bb) => bb,
+        None => return,
+    };
+    if bb.max.x <= 0 || bb.max.y <= 0 || bb.min.x >= width as i32 || bb.min.y >= height as i32 {
+        return;
+    }
+    glyph.draw(|x, y, v| {
+        let x = i64::from(bb.min.x) + i64::from(x);
+        let y = i64::from(bb.min.y) + i64::from(y);
+        if v > 0.0 && (0..i64::from(width)).contains(&x) && (0..i64::from(height)).contains(&y) {
+            o(x as u32, y as u32, v);
+        }
+    });
+}
+
+/// Returns the image width, height & baseline origin to fit a line of `text`.
+fn text_image_size(font: &Font<'_>, text: &str, scale: Scale) -> (u32, u32, Point<f32>) {
+    let v_metrics = font.v_metrics(scale);
+    let origin = point(0.0, v_metrics.ascent);
+    let (min_x, max_x) = font
+        .layout(text, scale, origin)
+        .filter_map(|g| g.pixel_bounding_box())
+        .fold(None, |span, bb| match span {
+            Some((min, max)) => Some((bb.min.x.min(min), bb.max.x.max(max))),
+            None => Some((bb.min.x, bb.max.x)),
+        })
+        .unwrap_or((0, 0));
+    // shifting by whole pixels keeps the rasterised glyphs unchanged
+    let origin = point(origin.x - min_x as f32, origin.y);
+    let width = (i64::from(max_x) - i64::from(min_x)) as u32;
+    let height = (v_metrics.ascent - v_metrics.descent).ceil() as u32;
+    (width, height, origin)
+}
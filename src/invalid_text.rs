@@ -0,0 +1,86 @@
+use crate::{Font, Point, PositionedGlyph, Scale};
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// How to handle invalid text, eg unpaired UTF-16 surrogates from OS
+/// strings, when decoding it for layout, see `Font::layout_utf16`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum InvalidCharPolicy {
+    /// Replace each invalid code unit or scalar with `U+FFFD`, `�`.
+    #[default]
+    Replace,
+    /// Replace each invalid code unit or scalar with the char, eg `'?'` for
+    /// fonts without `U+FFFD`.
+    ReplaceWith(char),
+    /// Drop invalid code units & scalars.
+    Skip,
+}
+
+impl InvalidCharPolicy {
+    /// Decodes UTF-16, eg a Windows wide string, applying this policy to
+    /// unpaired surrogates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::InvalidCharPolicy;
+    /// // "a", lone high surrogate, "b"
+    /// let text = [0x61, 0xD800, 0x62];
+    /// assert_eq!(InvalidCharPolicy::Replace.decode_utf16(&text), "a\u{FFFD}b");
+    /// assert_eq!(InvalidCharPolicy::Skip.decode_utf16(&text), "ab");
+    /// ```
+    pub fn decode_utf16(self, text: &[u16]) -> String {
+        char::decode_utf16(text.iter().copied())
+            .filter_map(|c| self.resolve(c.ok()))
+            .collect()
+    }
+
+    /// Decodes Unicode scalar values, eg UTF-32, applying this policy to
+    /// surrogates & values above `U+10FFFF`.
+    pub fn decode_utf32(self, text: &[u32]) -> String {
+        text.iter()
+            .filter_map(|&c| self.resolve(char::from_u32(c)))
+            .collect()
+    }
+
+    #[inline]
+    fn resolve(self, c: Option<char>) -> Option<char> {
+        match (c, self) {
+            (Some(c), _) => Some(c),
+            (None, InvalidCharPolicy::Replace) => Some(char::REPLACEMENT_CHARACTER),
+            (None, InvalidCharPolicy::ReplaceWith(c)) => Some(c),
+            (None, InvalidCharPolicy::Skip) => None,
+        }
+    }
+}
+
+impl<'font> Font<'font> {
+    /// Lays out UTF-16 text, which may be invalid, as `layout`. Unpaired
+    /// surrogates are handled per `policy`.
+    ///
+    /// To use other layout methods on such text, decode it first with
+    /// `InvalidCharPolicy::decode_utf16`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{point, Font, InvalidCharPolicy, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let wide: Vec<u16> = "Hi\u{1F600}".encode_utf16().chain([0xDC00]).collect();
+    /// let glyphs = font.layout_utf16(&wide, Scale::uniform(20.0), point(0.0, 20.0), InvalidCharPolicy::Replace);
+    ///
+    /// assert_eq!(glyphs.len(), 4);
+    /// assert_eq!(glyphs[3].id(), font.glyph('\u{FFFD}').id());
+    /// ```
+    pub fn layout_utf16(
+        &self,
+        text: &[u16],
+        scale: Scale,
+        start: Point<f32>,
+        policy: InvalidCharPolicy,
+    ) -> Vec<PositionedGlyph<'font>> {
+        self.layout(&policy.decode_utf16(text), scale, start)
+            .collect()
+    }
+}
@@ -0,0 +1,234 @@
+mod pairs;
+
+use crate::{Font, GlyphId, IntoGlyphId, Scale, Tag};
+use alloc::collections::{BTreeMap, BTreeSet};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use owned_ttf_parser::kern::Format;
+
+/// Kerning lookups of a font, prepared once for fast repeated use, see
+/// `Font::kern_table`.
+///
+/// Pair lists, `kern` format 0 subtables, are indexed by first glyph so a
+/// lookup is a short search of the pairs starting with that glyph. Class
+/// based subtables are already constant time so are looked up directly.
+#[derive(Clone, Debug)]
+pub struct KernTable<'font> {
+    font: Font<'font>,
+    /// Lookups in `kern` subtable order, the first with a value is used
+    sources: Vec<KernSource>,
+}
+
+#[derive(Clone, Debug)]
+enum KernSource {
+    /// Pairs of a format 0 subtable, `(second, kerning)` sorted by second,
+    /// for each first glyph id in `pairs[rows[id]..rows[id + 1]]`
+    Pairs {
+        rows: Vec<u32>,
+        pairs: Vec<(u16, i16)>,
+    },
+    /// Index of another horizontal subtable
+    Subtable(usize),
+}
+
+impl<'font> KernTable<'font> {
+    fn new(font: &Font<'font>) -> Self {
+        let glyph_count = font.glyph_count();
+        let sources = font
+            .inner()
+            .tables()
+            .kern
+            .into_iter()
+            .flat_map(|kern| kern.subtables.into_iter().enumerate())
+            .filter(|(_, st)| st.horizontal && !st.variable)
+            .map(|(index, st)| match st.format {
+                Format::Format0(subtable) => {
+                    let mut rows = Vec::with_capacity(glyph_count + 1);
+                    let mut pairs = Vec::with_capacity(usize::from(subtable.pairs.len()));
+                    // pairs are sorted by first then second glyph id
+                    for pair in subtable.pairs {
+                        let first = usize::from(pair.left().0);
+                        if first >= glyph_count {
+                            break;
+                        }
+                        while rows.len() <= first {
+                            rows.push(pairs.len() as u32);
+                        }
+                        pairs.push((pair.right().0, pair.value));
+                    }
+                    rows.resize(glyph_count + 1, pairs.len() as u32);
+                    KernSource::Pairs { rows, pairs }
+                }
+                _ => KernSource::Subtable(index),
+            })
+            .collect();
+        Self {
+            font: font.clone(),
+            sources,
+        }
+    }
+
+    /// The font the kerning is of.
+    #[inline]
+    pub fn font(&self) -> &Font<'font> {
+        &self.font
+    }
+
+    /// Returns the kerning between `first` & `second` at `scale`, equal to
+    /// `Font::pair_kerning`.
+    pub fn pair_kerning<A, B>(&self, scale: Scale, first: A, second: B) -> f32
+    where
+        A: IntoGlyphId,
+        B: IntoGlyphId,
+    {
+        let first = first.into_glyph_id(&self.font);
+        let second = second.into_glyph_id(&self.font);
+        self.font.kerning_factor(scale) * f32::from(self.pair_kerning_unscaled(first, second))
+    }
+
+    /// Returns the kerning between `first` & `second` in font units.
+    pub fn pair_kerning_unscaled(&self, first: GlyphId, second: GlyphId) -> i16 {
+        self.sources
+            .iter()
+            .find_map(|source| match source {
+                KernSource::Pairs { rows, pairs } => {
+                    let first = usize::from(first.0);
+                    let row = rows.get(first..first + 2)?;
+                    let row = &pairs[row[0] as usize..row[1] as usize];
+                    let index = row.binary_search_by_key(&second.0, |&(s, _)| s).ok()?;
+                    Some(row[index].1)
+                }
+                KernSource::Subtable(index) => self
+                    .font
+                    .inner()
+                    .tables()
+                    .kern?
+                    .subtables
+                    .into_iter()
+                    .nth(*index)?
+                    .glyphs_kerning(first.into(), second.into()),
+            })
+            .unwrap_or(0)
+    }
+}
+
+impl<'font> Font<'font> {
+    /// Returns the font's kerning prepared for fast repeated lookups, eg
+    /// when laying out long text with `LayoutIter::with_kern_table`.
+    ///
+    /// Building takes time proportional to the number of kerning pairs, so
+    /// keep the table to reuse it. `FontCaches` also keeps one for
+    /// `pair_kerning_cached`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{point, Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/opensans/OpenSans-Italic.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let kern_table = font.kern_table();
+    /// let scale = Scale::uniform(20.0);
+    /// assert_eq!(kern_table.pair_kerning(scale, 'A', 'V'), font.pair_kerning(scale, 'A', 'V'));
+    ///
+    /// let glyphs: Vec<_> = font
+    ///     .layout("AVAVAV", scale, point(0.0, 20.0))
+    ///     .with_kern_table(&kern_table)
+    ///     .collect();
+    /// ```
+    pub fn kern_table(&self) -> KernTable<'font> {
+        KernTable::new(self)
+    }
+
+    /// Returns every glyph pair with non-zero kerning, as `(first, second,
+    /// kerning)` in font units sorted by glyph ids, so kerning can be
+    /// converted into another format once at load time.
+    ///
+    /// Pairs with `kern` table kerning have the unscaled value
+    /// `pair_kerning` applies. Other pairs have the x advance adjustment of
+    /// the font's GPOS `kern` feature pair adjustments, which `pair_kerning`
+    /// doesn't apply, ignoring device & variation adjustments. Class based
+    /// subtables are expanded class pair by class pair, so building takes
+    /// time proportional to the number of pairs returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/opensans/OpenSans-Italic.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let (a, v) = (font.glyph('A').id(), font.glyph('V').id());
+    /// let kerning = font
+    ///     .kerning_pairs()
+    ///     .find(|&(first, second, _)| (first, second) == (a, v))
+    ///     .map(|(_, _, kerning)| kerning);
+    /// assert!(kerning.unwrap() < 0);
+    /// ```
+    pub fn kerning_pairs(&self) -> impl Iterator<Item = (GlyphId, GlyphId, i16)> {
+        let glyph_count = self.glyph_count() as u16;
+
+        // pairs with non-zero kerning in any `kern` subtable, valued as
+        // `pair_kerning` as the first subtable with a value is used
+        let mut candidates = BTreeSet::new();
+        let mut candidate = |first: u16, second: u16| {
+            candidates.insert((first, second));
+        };
+        let raw_subtables = self
+            .raw_table(Tag::from_bytes(b"kern"))
+            .map(pairs::kern_subtables)
+            .unwrap_or_default();
+        let subtables = self
+            .inner()
+            .tables()
+            .kern
+            .into_iter()
+            .flat_map(|kern| kern.subtables)
+            .zip(&raw_subtables);
+        for (subtable, &(_, data, header_len)) in subtables {
+            if !subtable.horizontal || subtable.variable {
+                continue;
+            }
+            match subtable.format {
+                Format::Format0(subtable) => subtable
+                    .pairs
+                    .into_iter()
+                    .filter(|pair| pair.value != 0)
+                    .for_each(|pair| candidate(pair.left().0, pair.right().0)),
+                Format::Format2(_) => {
+                    pairs::kern_format2_pairs(data, header_len, glyph_count, &mut candidate);
+                }
+                Format::Format3(_) => {
+                    pairs::kern_format3_pairs(data, &mut candidate);
+                }
+                _ => {}
+            }
+        }
+        let kern_table = self.kern_table();
+        let mut pairs: BTreeMap<_, _> = candidates
+            .into_iter()
+            .filter(|&(first, second)| first < glyph_count && second < glyph_count)
+            .filter_map(|(first, second)| {
+                let kerning = kern_table.pair_kerning_unscaled(GlyphId(first), GlyphId(second));
+                (kerning != 0).then_some(((first, second), kerning))
+            })
+            .collect();
+
+        if let (Some(gpos), Some(data)) = (
+            self.inner().tables().gpos,
+            self.raw_table(Tag::from_bytes(b"GPOS")),
+        ) {
+            let lookups = gpos
+                .features
+                .into_iter()
+                .filter(|feature| feature.tag == Tag::from_bytes(b"kern"))
+                .flat_map(|feature| feature.lookup_indices)
+                .collect();
+            for (glyphs, kerning) in pairs::gpos_pairs(data, &lookups, glyph_count) {
+                pairs.entry(glyphs).or_insert(kerning);
+            }
+        }
+
+        pairs
+            .into_iter()
+            .map(|((first, second), kerning)| (GlyphId(first), GlyphId(second), kerning))
+    }
+}
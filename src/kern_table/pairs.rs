@@ -0,0 +1,496 @@
+//! Enumeration of kerning pairs from class based `kern` subtables & GPOS
+//! pair adjustments, walking their class tables rather than looking up
+//! every glyph pair, see `Font::kerning_pairs`.
+use alloc::collections::{BTreeMap, BTreeSet};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_i16(data: &[u8], offset: usize) -> Option<i16> {
+    read_u16(data, offset).map(|v| v as i16)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Glyphs of a font grouped by class, glyphs not `listed` in `default`.
+fn group_classes(
+    listed: impl Iterator<Item = (u16, u16)>,
+    glyph_count: u16,
+    default: u16,
+) -> BTreeMap<u16, Vec<u16>> {
+    let mut classes: BTreeMap<u16, Vec<u16>> = BTreeMap::new();
+    let mut is_listed = alloc::vec![false; usize::from(glyph_count)];
+    for (glyph, class) in listed.filter(|&(glyph, _)| glyph < glyph_count) {
+        if !is_listed[usize::from(glyph)] {
+            is_listed[usize::from(glyph)] = true;
+            classes.entry(class).or_default().push(glyph);
+        }
+    }
+    let unlisted = (0..glyph_count).filter(|&glyph| !is_listed[usize::from(glyph)]);
+    classes.entry(default).or_default().extend(unlisted);
+    classes
+}
+
+/// Splits a `kern` table into the data of each subtable after its header, in
+/// the order ttf-parser iterates them, with the format & header length.
+pub(super) fn kern_subtables(kern: &[u8]) -> Vec<(u8, &[u8], usize)> {
+    let mut subtables = Vec::new();
+    let Some(version) = read_u16(kern, 0) else {
+        return subtables;
+    };
+    if version == 0 {
+        // OpenType
+        let count = read_u16(kern, 2).unwrap_or(0);
+        let mut offset = 4;
+        for _ in 0..count {
+            let (Some(len), Some(&format)) = (read_u16(kern, offset + 2), kern.get(offset + 4))
+            else {
+                break;
+            };
+            let start = offset + 6;
+            // a single subtable may exceed its u16 length
+            let end = if count == 1 {
+                kern.len()
+            } else {
+                offset + usize::from(len)
+            };
+            let Some(data) = kern.get(start..end) else {
+                break;
+            };
+            if format != 0 && format != 2 {
+                break;
+            }
+            subtables.push((format, data, 6));
+            offset = end;
+        }
+    } else {
+        // Apple
+        let count = read_u32(kern, 4).unwrap_or(0);
+        let mut offset = 8;
+        for _ in 0..count {
+            let (Some(len), Some(&format)) = (read_u32(kern, offset), kern.get(offset + 5)) else {
+                break;
+            };
+            let (start, end) = (offset + 8, offset.saturating_add(len as usize));
+            let Some(data) = kern.get(start..end) else {
+                break;
+            };
+            if format > 3 {
+                break;
+            }
+            subtables.push((format, data, 8));
+            offset = end;
+        }
+    }
+    subtables
+}
+
+/// Calls `pair` with each glyph pair with non-zero kerning in a format 2,
+/// class offset, `kern` subtable, by class pair rather than glyph pair.
+pub(super) fn kern_format2_pairs(
+    data: &[u8],
+    header_len: usize,
+    glyph_count: u16,
+    mut pair: impl FnMut(u16, u16),
+) -> Option<()> {
+    // offsets are from the subtable start, including the header
+    let offset = |at| usize::from(read_u16(data, at)?).checked_sub(header_len);
+    let (left_table, right_table, array) = (offset(2)?, offset(4)?, offset(6)?);
+    let class_table = |table: usize| {
+        let first = read_u16(data, table)?;
+        let count = read_u16(data, table + 2)?;
+        Some((0..count).filter_map(move |index| {
+            let class = read_u16(data, table + 4 + 2 * usize::from(index))?;
+            Some((first.checked_add(index)?, class))
+        }))
+    };
+    let left = group_classes(class_table(left_table)?, glyph_count, 0);
+    let right = group_classes(class_table(right_table)?, glyph_count, 0);
+
+    // left classes are row offsets, which can't be before the array
+    for (&row, firsts) in left.iter().filter(|(&row, _)| usize::from(row) >= array) {
+        for (&column, seconds) in &right {
+            let value = (usize::from(row) + usize::from(column))
+                .checked_sub(header_len)
+                .and_then(|at| read_i16(data, at));
+            if value.is_some_and(|v| v != 0) {
+                for &first in firsts {
+                    seconds.iter().for_each(|&second| pair(first, second));
+                }
+            }
+        }
+    }
+    Some(())
+}
+
+/// Calls `pair` with each glyph pair with non-zero kerning in a format 3,
+/// class index, `kern` subtable, by class pair rather than glyph pair.
+pub(super) fn kern_format3_pairs(data: &[u8], mut pair: impl FnMut(u16, u16)) -> Option<()> {
+    let count = read_u16(data, 0)?;
+    let values_count = usize::from(*data.get(2)?);
+    let (left_count, right_count) = (*data.get(3)?, *data.get(4)?);
+    let values = 6;
+    let left_classes = values + 2 * values_count;
+    let right_classes = left_classes + usize::from(count);
+    let indices = right_classes + usize::from(count);
+    let classes = |table: usize| {
+        let classes = data.get(table..table + usize::from(count))?;
+        Some((0..count).zip(classes.iter().map(|&c| u16::from(c))))
+    };
+    let left = group_classes(classes(left_classes)?, count, 0);
+    let right = group_classes(classes(right_classes)?, count, 0);
+
+    for (&left_class, firsts) in left.iter().filter(|(&c, _)| c <= u16::from(left_count)) {
+        for (&right_class, seconds) in right.iter().filter(|(&c, _)| c <= u16::from(right_count)) {
+            let index =
+                usize::from(left_class) * usize::from(right_count) + usize::from(right_class);
+            if index >= usize::from(left_count) * usize::from(right_count) {
+                continue;
+            }
+            let value = data
+                .get(indices + index)
+                .filter(|&&value| usize::from(value) < values_count)
+                .and_then(|&value| read_i16(data, values + 2 * usize::from(value)));
+            if value.is_some_and(|v| v != 0) {
+                for &first in firsts {
+                    seconds.iter().for_each(|&second| pair(first, second));
+                }
+            }
+        }
+    }
+    Some(())
+}
+
+/// Returns the glyphs of the next range record of a coverage or class
+/// definition table, clamped to the font's glyphs.
+///
+/// Ranges are sorted & don't overlap, so a range starting before the end of
+/// the last, `next` being the glyph after it, is skipped. This bounds the
+/// glyphs of all ranges by `glyph_count`, however many records there are.
+fn range_glyphs(
+    start: u16,
+    end: u16,
+    next: &mut u32,
+    glyph_count: u16,
+) -> Option<core::ops::RangeInclusive<u16>> {
+    let end = end.min(glyph_count.checked_sub(1)?);
+    if u32::from(start) < *next || start > end {
+        return None;
+    }
+    *next = u32::from(end) + 1;
+    Some(start..=end)
+}
+
+/// Returns the `(glyph, coverage index)` of each glyph of an OpenType
+/// coverage table, ignoring glyphs not in the font.
+fn coverage(data: &[u8], glyph_count: u16) -> Option<Vec<(u16, u16)>> {
+    let count = read_u16(data, 2)?;
+    let mut glyphs = Vec::new();
+    match read_u16(data, 0)? {
+        1 => {
+            for index in 0..count {
+                let glyph = read_u16(data, 4 + 2 * usize::from(index))?;
+                if glyph < glyph_count {
+                    glyphs.push((glyph, index));
+                }
+            }
+        }
+        2 => {
+            let mut next = 0;
+            for range in 0..usize::from(count) {
+                let record = 4 + 6 * range;
+                let (start, end) = (read_u16(data, record)?, read_u16(data, record + 2)?);
+                let start_index = read_u16(data, record + 4)?;
+                let Some(range) = range_glyphs(start, end, &mut next, glyph_count) else {
+                    continue;
+                };
+                for glyph in range {
+                    glyphs.push((glyph, start_index.checked_add(glyph - start)?));
+                }
+            }
+        }
+        _ => return None,
+    }
+    Some(glyphs)
+}
+
+/// Returns the `(glyph, class)` of each glyph listed in an OpenType class
+/// definition table, unlisted glyphs being class 0, ignoring glyphs not in
+/// the font.
+fn class_definition(data: &[u8], glyph_count: u16) -> Option<Vec<(u16, u16)>> {
+    let mut classes = Vec::new();
+    match read_u16(data, 0)? {
+        1 => {
+            let start = read_u16(data, 2)?;
+            let count = read_u16(data, 4)?;
+            for index in 0..count {
+                let class = read_u16(data, 6 + 2 * usize::from(index))?;
+                let glyph = start.checked_add(index)?;
+                if glyph < glyph_count {
+                    classes.push((glyph, class));
+                }
+            }
+        }
+        2 => {
+            let count = read_u16(data, 2)?;
+            let mut next = 0;
+            for range in 0..usize::from(count) {
+                let record = 4 + 6 * range;
+                let (start, end) = (read_u16(data, record)?, read_u16(data, record + 2)?);
+                let class = read_u16(data, record + 4)?;
+                if let Some(range) = range_glyphs(start, end, &mut next, glyph_count) {
+                    classes.extend(range.map(|glyph| (glyph, class)));
+                }
+            }
+        }
+        _ => return None,
+    }
+    Some(classes)
+}
+
+/// Returns the size of a GPOS value record & the offset of its x advance.
+fn value_record(format: u16) -> (usize, Option<usize>) {
+    let size = 2 * format.count_ones() as usize;
+    let x_advance = (format & 0x4 != 0).then(|| 2 * (format & 0x3).count_ones() as usize);
+    (size, x_advance)
+}
+
+/// Returns the GPOS pair adjustment kerning, the x advance adjustment of
+/// the first glyph, of every pair with an adjustment in the lookups of
+/// `lookup_indices`, in font units.
+///
+/// Lookups apply in turn, adding up. Within a lookup the first subtable
+/// with a value for the pair applies, a class based subtable applying to
+/// every pair starting with a glyph it covers.
+pub(super) fn gpos_pairs(
+    gpos: &[u8],
+    lookup_indices: &BTreeSet<u16>,
+    glyph_count: u16,
+) -> BTreeMap<(u16, u16), i16> {
+    let mut pairs = BTreeMap::new();
+    let Some(lookup_list) = read_u16(gpos, 8).map(usize::from) else {
+        return pairs;
+    };
+    for &index in lookup_indices {
+        let lookup = read_u16(gpos, lookup_list + 2 + 2 * usize::from(index))
+            .map(|offset| lookup_list + usize::from(offset));
+        let Some(lookup) = lookup.and_then(|lookup| gpos.get(lookup..)) else {
+            continue;
+        };
+        let mut lookup_pairs = BTreeMap::new();
+        lookup_pair_adjustments(lookup, glyph_count, &mut lookup_pairs);
+        for (glyphs, kerning) in lookup_pairs {
+            let total: &mut i16 = pairs.entry(glyphs).or_default();
+            *total = total.saturating_add(kerning);
+        }
+    }
+    pairs.retain(|_, kerning| *kerning != 0);
+    pairs
+}
+
+/// Adds the pair adjustments of a lookup to `pairs`, if it is one.
+fn lookup_pair_adjustments(
+    lookup: &[u8],
+    glyph_count: u16,
+    pairs: &mut BTreeMap<(u16, u16), i16>,
+) -> Option<()> {
+    const PAIR_ADJUSTMENT: u16 = 2;
+    const EXTENSION: u16 = 9;
+
+    let lookup_type = read_u16(lookup, 0)?;
+    let count = read_u16(lookup, 4)?;
+    // first glyphs a class based subtable already applied to
+    let mut applied = BTreeSet::new();
+    for index in 0..usize::from(count) {
+        let offset = usize::from(read_u16(lookup, 6 + 2 * index)?);
+        let mut subtable = lookup.get(offset..)?;
+        let mut subtable_type = lookup_type;
+        if lookup_type == EXTENSION {
+            subtable_type = read_u16(subtable, 2)?;
+            subtable = subtable.get(read_u32(subtable, 4)? as usize..)?;
+        }
+        if subtable_type == PAIR_ADJUSTMENT {
+            pair_adjustments(subtable, glyph_count, &mut applied, pairs);
+        }
+    }
+    Some(())
+}
+
+fn pair_adjustments(
+    data: &[u8],
+    glyph_count: u16,
+    applied: &mut BTreeSet<u16>,
+    pairs: &mut BTreeMap<(u16, u16), i16>,
+) -> Option<()> {
+    let coverage = coverage(data.get(usize::from(read_u16(data, 2)?)..)?, glyph_count)?;
+    let (format1, format2) = (read_u16(data, 4)?, read_u16(data, 6)?);
+    let (record1_size, x_advance) = value_record(format1);
+    let record2_size = value_record(format2).0;
+    let firsts: Vec<_> = coverage
+        .into_iter()
+        .filter(|(first, _)| !applied.contains(first))
+        .collect();
+
+    match read_u16(data, 0)? {
+        1 => {
+            let set_count = read_u16(data, 8)?;
+            for (first, index) in firsts.into_iter().filter(|&(_, index)| index < set_count) {
+                let set = usize::from(read_u16(data, 10 + 2 * usize::from(index))?);
+                let Some(set) = data.get(set..).filter(|_| set != 0) else {
+                    continue;
+                };
+                let record_size = 2 + record1_size + record2_size;
+                for record in 0..usize::from(read_u16(set, 0)?) {
+                    let record = 2 + record * record_size;
+                    let second = read_u16(set, record)?;
+                    if second >= glyph_count {
+                        continue;
+                    }
+                    let kerning = match x_advance {
+                        Some(at) => read_i16(set, record + 2 + at)?,
+                        None => 0,
+                    };
+                    pairs.entry((first, second)).or_insert(kerning);
+                }
+            }
+        }
+        2 => {
+            let classes_at =
+                |at| class_definition(data.get(usize::from(read_u16(data, at)?)..)?, glyph_count);
+            let first_classes: BTreeMap<u16, u16> = classes_at(8)?.into_iter().collect();
+            let second_classes = group_classes(classes_at(10)?.into_iter(), glyph_count, 0);
+            let (class1_count, class2_count) = (read_u16(data, 12)?, read_u16(data, 14)?);
+            let record_size = record1_size + record2_size;
+            for (first, _) in firsts {
+                applied.insert(first);
+                let class1 = first_classes.get(&first).copied().unwrap_or(0);
+                let Some(x_advance) = x_advance.filter(|_| class1 < class1_count) else {
+                    continue;
+                };
+                for (&class2, seconds) in second_classes.iter().filter(|(&c, _)| c < class2_count) {
+                    let record =
+                        usize::from(class1) * usize::from(class2_count) + usize::from(class2);
+                    let kerning = read_i16(data, 16 + record * record_size + x_advance)?;
+                    if kerning != 0 {
+                        for &second in seconds {
+                            pairs.entry((first, second)).or_insert(kerning);
+                        }
+                    }
+                }
+            }
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use owned_ttf_parser::{
+        kern::{Subtable2, Subtable3},
+        GlyphId,
+    };
+
+    fn be(values: &[u16]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    fn pairs(walk: impl FnOnce(&mut dyn FnMut(u16, u16))) -> BTreeSet<(u16, u16)> {
+        let mut pairs = BTreeSet::new();
+        walk(&mut |first, second| {
+            pairs.insert((first, second));
+        });
+        pairs
+    }
+
+    #[test]
+    fn format2_matches_lookups() {
+        // header length 8, tables after the 8 byte header: left at 16,
+        // right at 26, kerning array at 36 of 2 rows of 3 columns
+        let mut data = be(&[6, 16, 26, 36]);
+        // left classes, glyphs 3..6, row offsets
+        data.extend(be(&[3, 3, 36, 42, 36]));
+        // right classes, glyphs 4..7, column offsets
+        data.extend(be(&[4, 3, 2, 4, 0]));
+        data.extend(be(&[0, (-40_i16) as u16, 25, 10, 0, 0]));
+        assert_eq!(data.len(), 36 - 8 + 12);
+
+        let glyph_count = 10;
+        let expected: BTreeSet<_> = (0..glyph_count)
+            .flat_map(|first| (0..glyph_count).map(move |second| (first, second)))
+            .filter(|&(first, second)| {
+                let table = Subtable2::parse(8, &data).unwrap();
+                table
+                    .glyphs_kerning(GlyphId(first), GlyphId(second))
+                    .is_some_and(|v| v != 0)
+            })
+            .collect();
+        assert!(expected.contains(&(3, 4)) && expected.contains(&(4, 0)));
+        let walked = pairs(|pair| {
+            kern_format2_pairs(&data, 8, glyph_count, pair).unwrap();
+        });
+        assert_eq!(walked, expected);
+    }
+
+    #[test]
+    fn malformed_pair_adjustments_bounded_by_glyph_count() {
+        let glyph_count = 4;
+
+        // format 1 covering glyph 1, with a second glyph not in the font
+        let mut data = be(&[1, 12, 0x4, 0, 1, 18]);
+        data.extend(be(&[1, 1, 1]));
+        data.extend(be(&[2, 2, (-10_i16) as u16, 0xffff, (-20_i16) as u16]));
+        let mut pairs = BTreeMap::new();
+        pair_adjustments(&data, glyph_count, &mut BTreeSet::new(), &mut pairs).unwrap();
+        assert_eq!(pairs.into_iter().collect::<Vec<_>>(), [((1, 2), -10)]);
+
+        // format 2 with coverage & class definitions of many repeated
+        // 0..=0xffff ranges, sharing the same format 2 range records
+        let mut data = be(&[2, 18, 0x4, 0, 18, 18, 1, 1, (-5_i16) as u16]);
+        let ranges = 1000;
+        data.extend(be(&[2, ranges]));
+        for _ in 0..ranges {
+            data.extend(be(&[0, 0xffff, 0]));
+        }
+        assert_eq!(coverage(&data[18..], glyph_count).unwrap().len(), 4);
+        assert_eq!(class_definition(&data[18..], glyph_count).unwrap().len(), 4);
+        let mut pairs = BTreeMap::new();
+        pair_adjustments(&data, glyph_count, &mut BTreeSet::new(), &mut pairs).unwrap();
+        assert_eq!(pairs.len(), 16);
+        assert!(pairs.iter().all(|(&(a, b), &v)| a < 4 && b < 4 && v == -5));
+    }
+
+    #[test]
+    fn format3_matches_lookups() {
+        let glyph_count = 6;
+        // 3 values, 2 left classes, 3 right classes
+        let mut data = vec![0, glyph_count as u8, 3, 2, 3, 0];
+        data.extend(be(&[0, (-30_i16) as u16, 12]));
+        data.extend([0, 1, 1, 0, 2, 1]);
+        data.extend([0, 2, 1, 1, 0, 3]);
+        data.extend([0, 0, 1, 2, 1, 0]);
+
+        let expected: BTreeSet<_> = (0..glyph_count)
+            .flat_map(|first| (0..glyph_count).map(move |second| (first, second)))
+            .filter(|&(first, second)| {
+                let table = Subtable3::parse(&data).unwrap();
+                table
+                    .glyphs_kerning(GlyphId(first), GlyphId(second))
+                    .is_some_and(|v| v != 0)
+            })
+            .collect();
+        assert!(!expected.is_empty());
+        let walked = pairs(|pair| {
+            kern_format3_pairs(&data, pair).unwrap();
+        });
+        assert_eq!(walked, expected);
+    }
+}
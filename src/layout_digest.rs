@@ -0,0 +1,139 @@
+use crate::PositionedGlyph;
+use core::hash::Hasher;
+
+#[cfg(all(feature = "libm-math", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Produces a stable fingerprint of a glyph sequence, for golden tests &
+/// caching renders of whole text runs.
+///
+/// Glyph ids, scales & positions are hashed in sequence. Scales & positions
+/// are quantised, to 1/64th of a pixel by default, so tiny floating point
+/// differences don't change the digest. Font identity is not included, as
+/// `Font::data_id` is not stable between runs.
+///
+/// Unlike `std` hashers the digest is specified, 64-bit FNV-1a over
+/// little-endian values, `usize` & `isize` as 64-bit, so is stable across
+/// platforms, runs & releases.
+///
+/// # Example
+///
+/// ```
+/// # use rusttype::{point, Font, GlyphHasher, Scale};
+/// use std::hash::Hasher;
+/// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+/// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+/// let layout = |x| font.layout("Hello", Scale::uniform(20.0), point(x, 20.0));
+///
+/// let digest = rusttype::layout_digest(&layout(10.0).collect::<Vec<_>>());
+/// assert_eq!(digest, rusttype::layout_digest(&layout(10.0001).collect::<Vec<_>>()));
+/// assert_ne!(digest, rusttype::layout_digest(&layout(10.5).collect::<Vec<_>>()));
+///
+/// // equivalent to
+/// let mut hasher = GlyphHasher::new();
+/// layout(10.0).for_each(|g| hasher.write_glyph(&g));
+/// assert_eq!(hasher.finish(), digest);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GlyphHasher {
+    state: u64,
+    quantization: f32,
+}
+
+impl Default for GlyphHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GlyphHasher {
+    /// Creates a hasher quantising to 1/64th of a pixel.
+    pub fn new() -> Self {
+        Self::with_quantization(64.0)
+    }
+
+    /// Creates a hasher quantising scales & positions to `1 / steps_per_pixel`
+    /// of a pixel.
+    pub fn with_quantization(steps_per_pixel: f32) -> Self {
+        Self {
+            state: FNV_OFFSET_BASIS,
+            quantization: steps_per_pixel,
+        }
+    }
+
+    /// Adds a glyph to the digest.
+    pub fn write_glyph(&mut self, glyph: &PositionedGlyph<'_>) {
+        let (scale, position) = (glyph.scale(), glyph.position());
+        self.write_u16(glyph.id().0);
+        for v in [scale.x, scale.y, position.x, position.y] {
+            self.write_i64(self.quantize(v));
+        }
+    }
+
+    #[inline]
+    fn quantize(&self, v: f32) -> i64 {
+        (v * self.quantization).round() as i64
+    }
+}
+
+macro_rules! write_le {
+    ($($name:ident($ty:ty),)*) => {
+        $(
+            #[inline]
+            fn $name(&mut self, i: $ty) {
+                self.write(&i.to_le_bytes())
+            }
+        )*
+    };
+}
+
+impl Hasher for GlyphHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.state
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.state = (self.state ^ u64::from(byte)).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    // Explicit little-endian, with `usize` & `isize` widened to 64 bits, so
+    // digests of any `Hash` value match across platforms.
+    write_le! {
+        write_u16(u16),
+        write_u32(u32),
+        write_u64(u64),
+        write_u128(u128),
+        write_i16(i16),
+        write_i32(i32),
+        write_i64(i64),
+        write_i128(i128),
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64)
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64)
+    }
+}
+
+/// Returns a stable fingerprint of a glyph sequence. See `GlyphHasher`.
+pub fn layout_digest<'a, 'font: 'a>(
+    glyphs: impl IntoIterator<Item = &'a PositionedGlyph<'font>>,
+) -> u64 {
+    let mut hasher = GlyphHasher::new();
+    for glyph in glyphs {
+        hasher.write_glyph(glyph);
+    }
+    hasher.finish()
+}
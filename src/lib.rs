@@ -97,25 +97,97 @@
 
 extern crate alloc;
 
+#[cfg(feature = "ab_glyph")]
+mod ab_glyph_compat;
+mod arabic;
+mod caches;
+mod cell_renderer;
+mod cmap;
+mod coverage;
+mod coverage_buffer;
+mod darkening;
+mod draw_options;
 mod font;
+mod font_api;
+mod font_error;
 mod geometry;
+mod glyph_id_map;
+mod glyph_ref;
+#[cfg(feature = "image")]
+mod image_compat;
+mod invalid_text;
+mod kern_table;
+mod layout_digest;
+#[cfg(feature = "lyon")]
+mod lyon_compat;
+mod nine_slice;
 mod outliner;
+mod run_cache;
+#[cfg(feature = "rustybuzz")]
+mod rustybuzz_compat;
+mod scanline;
+mod simplify;
+mod synthetic;
+mod text_metrics;
+#[cfg(feature = "tiny_skia")]
+mod tiny_skia_compat;
 
 #[cfg(all(feature = "libm-math", not(feature = "std")))]
 mod nostd_float;
 
+#[cfg(feature = "atlas")]
+pub mod atlas;
+#[cfg(feature = "bidi")]
+pub mod bidi;
+pub mod char_class;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 #[cfg(feature = "gpu_cache")]
 pub mod gpu_cache;
-
+#[cfg(feature = "glow")]
+pub mod gpu_cache_glow;
+#[cfg(feature = "wgpu")]
+pub mod gpu_cache_wgpu;
+#[cfg(feature = "static_cache")]
+pub mod static_cache;
+pub mod tools;
+
+pub use crate::caches::FontCaches;
+pub use crate::cell_renderer::{CellRenderer, CellStyle};
+pub use crate::cmap::CmapSubtable;
+pub use crate::coverage::CoverageReport;
+pub use crate::coverage_buffer::CoverageBuffer;
+pub use crate::darkening::stem_darkening;
+pub use crate::draw_options::{DrawOptions, DrawQuality};
+pub use crate::font_api::FontApi;
+pub use crate::font_error::FontError;
 pub use crate::geometry::{point, vector, Point, Rect, Vector};
+pub use crate::glyph_id_map::GlyphIdMap;
+pub use crate::glyph_ref::{GlyphRef, PositionedGlyphRef, ScaledGlyphRef};
+#[cfg(feature = "image")]
+pub use crate::image_compat::{
+    draw_glyph_onto_image, draw_text_onto_image, render_text_to_gray_image, render_text_to_image,
+};
+pub use crate::invalid_text::InvalidCharPolicy;
+pub use crate::kern_table::KernTable;
+pub use crate::layout_digest::{layout_digest, GlyphHasher};
+pub use crate::nine_slice::{NineSlice, Padding};
+pub use crate::outliner::OutlineRasterizer;
+pub use crate::run_cache::RunCache;
+#[cfg(feature = "rustybuzz")]
+pub use crate::rustybuzz_compat::Shaper;
+pub use crate::synthetic::{Outline, OutlineSegment, SyntheticFont, SyntheticFontError};
+pub use crate::text_metrics::TextMetrics;
 pub use font::*;
 
-use core::fmt;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::{fmt, hash};
 
 #[cfg(all(feature = "libm-math", not(feature = "std")))]
 use crate::nostd_float::FloatExt;
 
-pub use owned_ttf_parser::OutlineBuilder;
+pub use owned_ttf_parser::{FaceTables, OutlineBuilder, Tag};
 
 #[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
 pub struct GlyphId(pub u16);
@@ -153,9 +225,56 @@ impl<'font> Glyph<'font> {
         self.id
     }
 
+    /// The "horizontal metrics" of this glyph in font units, unscaled. Zero
+    /// if the font has no metrics for the glyph.
+    ///
+    /// Multiply by `Font::scale_for_pixel_height` to get `ScaledGlyph`
+    /// metrics, eg in a vertex shader for 3D text.
+    pub fn h_metrics_unscaled(&self) -> HMetrics {
+        self.as_glyph_ref().h_metrics_unscaled()
+    }
+
+    /// The bounding box of the shape of this glyph in font units, unscaled,
+    /// with y increasing upwards from the baseline. `None` if the glyph has
+    /// no outline.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let glyph = font.glyph('A');
+    /// let bb = glyph.bounding_box_unscaled().unwrap();
+    ///
+    /// let scale = Scale::uniform(20.0);
+    /// let factor = font.scale_for_pixel_height(scale.y);
+    /// let scaled = glyph.scaled(scale).exact_bounding_box().unwrap();
+    /// // y-down pixels, so the unscaled top is the scaled min
+    /// assert_eq!(bb.max.y * factor, -scaled.min.y);
+    /// ```
+    pub fn bounding_box_unscaled(&self) -> Option<Rect<f32>> {
+        self.as_glyph_ref().bounding_box_unscaled()
+    }
+
     /// Augments this glyph with scaling information, making methods that depend
     /// on the scale of the glyph available.
     pub fn scaled(self, scale: Scale) -> ScaledGlyph<'font> {
+        self.scaled_impl(scale)
+    }
+
+    /// Augments this glyph with scaling information, `size` interpreted
+    /// according to `semantics`. See `Font::resolve_scale`.
+    pub fn scaled_with_semantics(
+        self,
+        size: Scale,
+        semantics: SizeSemantics,
+    ) -> ScaledGlyph<'font> {
+        let scale = self.font.resolve_scale(size, semantics);
+        self.scaled_impl(scale)
+    }
+
+    fn scaled_impl(self, scale: Scale) -> ScaledGlyph<'font> {
         let scale_y = self.font.scale_for_pixel_height(scale.y);
         let scale_x = scale_y * scale.x / scale.y;
         ScaledGlyph {
@@ -166,6 +285,23 @@ impl<'font> Glyph<'font> {
     }
 }
 
+/// Glyphs are equal if they have the same id in the same font, see
+/// `Font::data_id`.
+impl PartialEq for Glyph<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.font == other.font
+    }
+}
+
+impl Eq for Glyph<'_> {}
+
+impl hash::Hash for Glyph<'_> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.font.data_id().hash(state);
+        self.id.hash(state);
+    }
+}
+
 impl fmt::Debug for Glyph<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Glyph").field("id", &self.id().0).finish()
@@ -185,6 +321,19 @@ pub struct HMetrics {
     pub left_side_bearing: f32,
 }
 
+/// The unscaled "horizontal metrics" of all glyphs in a font, in font units,
+/// as packed arrays indexed by glyph id. See `Font::h_metrics_unscaled`.
+///
+/// Suited to bulk layout, eg with SIMD, where per-glyph lookups are too slow.
+/// Multiply by `Font::scale_for_pixel_height` & `Scale::x` to get pixels.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct UnscaledHMetrics {
+    /// Advance widths of each glyph.
+    pub advance_widths: Vec<u16>,
+    /// Left side bearings of each glyph.
+    pub left_side_bearings: Vec<i16>,
+}
+
 /// The "vertical metrics" of a font at a particular scale. This is useful for
 /// calculating the amount of vertical space to give a line of text, and for
 /// computing the vertical offset between successive lines.
@@ -201,6 +350,40 @@ pub struct VMetrics {
     pub line_gap: f32,
 }
 
+/// The scale & offset for superscript or subscript glyphs of running text,
+/// from a font's OS/2 metrics. See `Font::superscript_metrics` &
+/// `Font::subscript_metrics`.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct ScriptMetrics {
+    /// Scale to lay out the script glyphs at.
+    pub scale: Scale,
+    /// Offset of the script baseline from the running text caret, in pixels
+    /// with y increasing downwards.
+    pub offset: Vector<f32>,
+}
+
+/// The slope of text carets, from a font's `hhea` table in font units, see
+/// `Font::caret_slope`. Upright fonts have a vertical slope, `run` zero.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CaretSlope {
+    /// Vertical component of the slope, positive.
+    pub rise: i16,
+    /// Horizontal component of the slope, positive leaning right.
+    pub run: i16,
+    /// Horizontal shift of the caret to best position it over slanted
+    /// glyphs.
+    pub offset: i16,
+}
+
+impl CaretSlope {
+    /// Returns the horizontal caret shift per unit of height, eg about `0.21`
+    /// for a 12° italic & `0.0` for upright text.
+    #[inline]
+    pub fn slant(&self) -> f32 {
+        f32::from(self.run) / f32::from(self.rise)
+    }
+}
+
 impl core::ops::Mul<f32> for VMetrics {
     type Output = VMetrics;
 
@@ -247,22 +430,48 @@ impl<'font> ScaledGlyph<'font> {
     /// Builds the outline of the glyph with the builder specified. Returns
     /// `false` when the outline is either malformed or empty.
     pub fn build_outline(&self, builder: &mut impl OutlineBuilder) -> bool {
-        let mut outliner =
-            crate::outliner::OutlineScaler::new(builder, vector(self.scale.x, -self.scale.y));
+        self.as_glyph_ref().build_outline(builder)
+    }
 
-        self.font()
-            .inner()
-            .outline_glyph(self.id().into(), &mut outliner)
-            .is_some()
+    /// Builds the outline as `build_outline`, replaying the glyph's parsed
+    /// outline from `caches` instead of walking the font's `glyf` or `CFF `
+    /// data each time. Faster when the same glyphs are outlined or drawn
+    /// repeatedly, eg at many sizes & positions.
+    ///
+    /// Outlines are kept per glyph id, unscaled, up to
+    /// `FontCaches::outline_capacity`, evicting the least recently used.
+    /// With a capacity of `0` this is equivalent to `build_outline`.
+    ///
+    /// `caches` should be dedicated to this font, if it was last used with a
+    /// different font it is cleared first.
+    pub fn build_outline_cached(
+        &self,
+        caches: &mut FontCaches<'font>,
+        builder: &mut impl OutlineBuilder,
+    ) -> bool {
+        self.as_glyph_ref().build_outline_cached(caches, builder)
     }
 
     /// Augments this glyph with positioning information, making methods that
     /// depend on the position of the glyph available.
     pub fn positioned(self, p: Point<f32>) -> PositionedGlyph<'font> {
-        let bb = self.pixel_bounds_at(p);
+        self.positioned_with_rounding(p, PixelRounding::default())
+    }
+
+    /// Augments this glyph with positioning information, using `rounding` to
+    /// convert the exact glyph bounds into the pixel bounding box.
+    ///
+    /// See `PixelRounding` for the available modes.
+    pub fn positioned_with_rounding(
+        self,
+        p: Point<f32>,
+        rounding: PixelRounding,
+    ) -> PositionedGlyph<'font> {
+        let bb = self.as_glyph_ref().pixel_bounds_at(p, rounding);
         PositionedGlyph {
             sg: self,
             position: p,
+            rounding,
             bb,
         }
     }
@@ -271,75 +480,74 @@ impl<'font> ScaledGlyph<'font> {
         self.api_scale
     }
 
+    /// Returns a lightweight hashable key identifying this scaled glyph. See
+    /// `GlyphKey` for the equality semantics.
+    pub fn key(&self) -> GlyphKey {
+        GlyphKey {
+            font: self.font().data_id(),
+            id: self.id(),
+            scale: (float_key(self.api_scale.x), float_key(self.api_scale.y)),
+            position: None,
+        }
+    }
+
     /// Retrieves the "horizontal metrics" of this glyph. See `HMetrics` for
     /// more detail.
+    ///
+    /// Glyphs without metrics, eg of malformed fonts missing `hmtx` entries,
+    /// have zero advance & side bearing, see `try_h_metrics`.
     pub fn h_metrics(&self) -> HMetrics {
-        let inner = self.font().inner();
-        let id = self.id().into();
-
-        let advance = inner.glyph_hor_advance(id).unwrap();
-        let left_side_bearing = inner.glyph_hor_side_bearing(id).unwrap();
+        self.as_glyph_ref().h_metrics()
+    }
 
-        HMetrics {
-            advance_width: advance as f32 * self.scale.x,
-            left_side_bearing: left_side_bearing as f32 * self.scale.x,
-        }
+    /// Retrieves the "horizontal metrics" of this glyph, or `None` if the
+    /// font has no advance width for it, eg a malformed font missing the
+    /// `hmtx` table. A missing left side bearing alone defaults to `0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let glyph = font.glyph('a').scaled(Scale::uniform(20.0));
+    /// assert_eq!(glyph.try_h_metrics(), Some(glyph.h_metrics()));
+    /// ```
+    pub fn try_h_metrics(&self) -> Option<HMetrics> {
+        self.as_glyph_ref().try_h_metrics()
     }
 
     /// The bounding box of the shape of this glyph, not to be confused with
     /// `pixel_bounding_box`, the conservative pixel-boundary bounding box. The
     /// coordinates are relative to the glyph's origin.
     pub fn exact_bounding_box(&self) -> Option<Rect<f32>> {
-        let owned_ttf_parser::Rect {
-            x_min,
-            y_min,
-            x_max,
-            y_max,
-        } = self.font().inner().glyph_bounding_box(self.id().into())?;
-
-        Some(Rect {
-            min: point(x_min as f32 * self.scale.x, -y_max as f32 * self.scale.y),
-            max: point(x_max as f32 * self.scale.x, -y_min as f32 * self.scale.y),
-        })
+        self.as_glyph_ref().exact_bounding_box()
     }
 
-    fn glyph_bitmap_box_subpixel(
-        &self,
-        font: &Font<'font>,
-        shift_x: f32,
-        shift_y: f32,
-    ) -> Option<Rect<i32>> {
-        let owned_ttf_parser::Rect {
-            x_min,
-            y_min,
-            x_max,
-            y_max,
-        } = font.inner().glyph_bounding_box(self.id().into())?;
-
-        Some(Rect {
-            min: point(
-                (x_min as f32 * self.scale.x + shift_x).floor() as i32,
-                (-y_max as f32 * self.scale.y + shift_y).floor() as i32,
-            ),
-            max: point(
-                (x_max as f32 * self.scale.x + shift_x).ceil() as i32,
-                (-y_min as f32 * self.scale.y + shift_y).ceil() as i32,
-            ),
-        })
+    /// The maximum conservative pixel bounding box dimensions (width, height)
+    /// of this glyph over all subpixel positions. Returns `None` for glyphs
+    /// with no shape.
+    ///
+    /// This allows reasoning about pixel sizes, e.g. for texture atlas
+    /// planning, before choosing glyph positions. `pixel_bounding_box`
+    /// dimensions of the positioned glyph never exceed these extents.
+    pub fn pixel_extents(&self) -> Option<Vector<u32>> {
+        self.as_glyph_ref().pixel_extents()
     }
+}
 
-    #[inline]
-    fn pixel_bounds_at(&self, p: Point<f32>) -> Option<Rect<i32>> {
-        // Use subpixel fraction in floor/ceil rounding to eliminate rounding error
-        // from identical subpixel positions
-        let (x_trunc, x_fract) = (p.x.trunc() as i32, p.x.fract());
-        let (y_trunc, y_fract) = (p.y.trunc() as i32, p.y.fract());
-
-        let Rect { min, max } = self.glyph_bitmap_box_subpixel(self.font(), x_fract, y_fract)?;
-        Some(Rect {
-            min: point(x_trunc + min.x, y_trunc + min.y),
-            max: point(x_trunc + max.x, y_trunc + max.y),
-        })
+/// Equality by logical identity, see `GlyphKey`.
+impl PartialEq for ScaledGlyph<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for ScaledGlyph<'_> {}
+
+impl hash::Hash for ScaledGlyph<'_> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
     }
 }
 
@@ -359,6 +567,7 @@ impl fmt::Debug for ScaledGlyph<'_> {
 pub struct PositionedGlyph<'font> {
     sg: ScaledGlyph<'font>,
     position: Point<f32>,
+    rounding: PixelRounding,
     bb: Option<Rect<i32>>,
 }
 
@@ -400,20 +609,28 @@ impl<'font> PositionedGlyph<'font> {
         self.position
     }
 
+    /// The rounding mode used to calculate the pixel bounding box.
+    pub fn rounding(&self) -> PixelRounding {
+        self.rounding
+    }
+
+    /// Returns a lightweight hashable key identifying this positioned glyph.
+    /// See `GlyphKey` for the equality semantics.
+    pub fn key(&self) -> GlyphKey {
+        GlyphKey {
+            position: Some((
+                float_key(self.position.x),
+                float_key(self.position.y),
+                self.rounding,
+            )),
+            ..self.sg.key()
+        }
+    }
+
     /// Builds the outline of the glyph with the builder specified. Returns
     /// `false` when the outline is either malformed or empty.
     pub fn build_outline(&self, builder: &mut impl OutlineBuilder) -> bool {
-        let bb = if let Some(bb) = self.bb.as_ref() {
-            bb
-        } else {
-            return false;
-        };
-
-        let offset = vector(bb.min.x as f32, bb.min.y as f32);
-
-        let mut outliner = crate::outliner::OutlineTranslator::new(builder, self.position - offset);
-
-        self.sg.build_outline(&mut outliner)
+        self.as_glyph_ref().build_outline(builder)
     }
 
     /// Rasterises this glyph. For each pixel in the rect given by
@@ -437,38 +654,77 @@ impl<'font> PositionedGlyph<'font> {
     /// }
     /// ```
     pub fn draw<O: FnMut(u32, u32, f32)>(&self, o: O) {
-        let bb = if let Some(bb) = self.bb.as_ref() {
-            bb
-        } else {
-            return;
-        };
-
-        let width = (bb.max.x - bb.min.x) as u32;
-        let height = (bb.max.y - bb.min.y) as u32;
-
-        let mut outliner = crate::outliner::OutlineRasterizer::new(width as _, height as _);
+        self.as_glyph_ref().draw(o)
+    }
 
-        self.build_outline(&mut outliner);
+    /// Builds the outline as `build_outline`, using outlines cached in
+    /// `caches`, see `ScaledGlyph::build_outline_cached`.
+    pub fn build_outline_cached(
+        &self,
+        caches: &mut FontCaches<'font>,
+        builder: &mut impl OutlineBuilder,
+    ) -> bool {
+        self.as_glyph_ref().build_outline_cached(caches, builder)
+    }
 
-        outliner.rasterizer.for_each_pixel_2d(o);
+    /// Rasterises this glyph as `draw`, using outlines cached in `caches`,
+    /// see `ScaledGlyph::build_outline_cached`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{point, Font, FontCaches, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let mut caches = FontCaches::with_outline_capacity(128);
+    /// for size in [12.0, 14.0, 16.0] {
+    ///     for glyph in font.layout("Hello", Scale::uniform(size), point(0.0, size)) {
+    ///         // "l" is parsed once then replayed
+    ///         glyph.draw_cached(&mut caches, |x, y, v| {
+    ///             // draw pixel at (bb.min.x + x, bb.min.y + y)
+    ///         });
+    ///     }
+    /// }
+    /// ```
+    pub fn draw_cached<O: FnMut(u32, u32, f32)>(&self, caches: &mut FontCaches<'font>, o: O) {
+        self.as_glyph_ref().draw_cached(caches, o)
     }
 
     /// Resets positioning information and recalculates the pixel bounding box
     pub fn set_position(&mut self, p: Point<f32>) {
         let p_diff = p - self.position;
-        if p_diff.x.fract().is_near_zero() && p_diff.y.fract().is_near_zero() {
+        // truncation isn't translation invariant across the origin
+        if self.rounding != PixelRounding::Truncate
+            && p_diff.x.fract().is_near_zero()
+            && p_diff.y.fract().is_near_zero()
+        {
             if let Some(bb) = self.bb.as_mut() {
                 let rounded_diff = vector(p_diff.x.round() as i32, p_diff.y.round() as i32);
-                bb.min = bb.min + rounded_diff;
-                bb.max = bb.max + rounded_diff;
+                *bb = bb.saturating_translate(rounded_diff);
             }
         } else {
-            self.bb = self.sg.pixel_bounds_at(p);
+            self.bb = self.sg.as_glyph_ref().pixel_bounds_at(p, self.rounding);
         }
         self.position = p;
     }
 }
 
+/// Equality by logical identity, see `GlyphKey`. Positions are compared
+/// bitwise, without any epsilon.
+impl PartialEq for PositionedGlyph<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for PositionedGlyph<'_> {}
+
+impl hash::Hash for PositionedGlyph<'_> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
+
 impl fmt::Debug for PositionedGlyph<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("PositionedGlyph")
@@ -479,6 +735,221 @@ impl fmt::Debug for PositionedGlyph<'_> {
     }
 }
 
+/// A cheap, hashable identity for a `ScaledGlyph` or `PositionedGlyph`,
+/// obtained with `ScaledGlyph::key` & `PositionedGlyph::key`.
+///
+/// Two keys are equal when they refer to the same font data (ie the same
+/// `Font` or clones of it), the same glyph id, bitwise identical scale and
+/// position values, and the same `PixelRounding`. `-0.0` and `0.0` are
+/// treated as equal.
+///
+/// Keys of scaled glyphs never equal keys of positioned glyphs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GlyphKey {
+    font: usize,
+    id: GlyphId,
+    scale: (u32, u32),
+    position: Option<(u32, u32, PixelRounding)>,
+}
+
+impl GlyphKey {
+    /// The glyph identifier for the keyed glyph.
+    pub fn id(&self) -> GlyphId {
+        self.id
+    }
+
+    /// The scale of the keyed glyph.
+    pub fn scale(&self) -> Scale {
+        Scale {
+            x: f32::from_bits(self.scale.0),
+            y: f32::from_bits(self.scale.1),
+        }
+    }
+
+    /// The position of the keyed glyph, if it was positioned.
+    pub fn position(&self) -> Option<Point<f32>> {
+        self.position
+            .map(|(x, y, _)| point(f32::from_bits(x), f32::from_bits(y)))
+    }
+}
+
+/// Returns the bits of `f` for use in hashable keys, normalising `-0.0`.
+#[inline]
+fn float_key(f: f32) -> u32 {
+    if f == 0.0 {
+        0
+    } else {
+        f.to_bits()
+    }
+}
+
+/// Spacing & handling of tabs, newlines & other control chars by
+/// `Font::layout`, see `LayoutIter::with_options`.
+///
+/// The default matches plain `Font::layout`, drawing every char's glyph,
+/// which for control chars is usually `.notdef`.
+///
+/// # Example
+///
+/// ```
+/// # use rusttype::*;
+/// let options = LayoutOptions::new()
+///     .with_tab_width(Some(TabWidth::Spaces(4.0)))
+///     .with_newline(NewlineMode::Break)
+///     .with_skip_control(true)
+///     .with_letter_spacing(1.5)
+///     .with_word_spacing(4.0);
+/// assert_eq!(options.newline, NewlineMode::Break);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct LayoutOptions {
+    /// Distance between tab stops. Tabs advance the caret to the next stop,
+    /// measured from the layout start, & produce no glyph. `None` draws tabs
+    /// as any other char.
+    pub tab_width: Option<TabWidth>,
+    /// Handling of `'\n'`.
+    pub newline: NewlineMode,
+    /// Skip other control chars, eg `'\r'`, & default ignorable format chars,
+    /// eg zero width joiners, producing no glyph & not advancing the caret.
+    /// See `char_class::is_default_ignorable`.
+    pub skip_control: bool,
+    /// Extra space, tracking, in pixels added after every glyph that advances
+    /// the caret, in addition to kerning. May be negative.
+    pub letter_spacing: f32,
+    /// Extra space in pixels added after every space glyph, see
+    /// `char_class::is_word_separator`, eg for justified text, in addition to
+    /// `letter_spacing`. May be negative.
+    pub word_spacing: f32,
+}
+
+impl LayoutOptions {
+    /// Returns the default options, matching plain `Font::layout`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets `tab_width`.
+    pub fn with_tab_width(mut self, tab_width: Option<TabWidth>) -> Self {
+        self.tab_width = tab_width;
+        self
+    }
+
+    /// Sets `newline`.
+    pub fn with_newline(mut self, newline: NewlineMode) -> Self {
+        self.newline = newline;
+        self
+    }
+
+    /// Sets `skip_control`.
+    pub fn with_skip_control(mut self, skip_control: bool) -> Self {
+        self.skip_control = skip_control;
+        self
+    }
+
+    /// Sets `letter_spacing`.
+    pub fn with_letter_spacing(mut self, letter_spacing: f32) -> Self {
+        self.letter_spacing = letter_spacing;
+        self
+    }
+
+    /// Sets `word_spacing`.
+    pub fn with_word_spacing(mut self, word_spacing: f32) -> Self {
+        self.word_spacing = word_spacing;
+        self
+    }
+}
+
+/// Distance between tab stops, see `LayoutOptions`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TabWidth {
+    /// A multiple of the advance width of the space glyph.
+    Spaces(f32),
+    /// A width in pixels.
+    Pixels(f32),
+}
+
+/// Handling of `'\n'` in layout, see `LayoutOptions`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum NewlineMode {
+    /// Draw the font's glyph for `'\n'`, usually `.notdef`.
+    #[default]
+    Glyph,
+    /// Skip newlines, producing no glyph.
+    Ignore,
+    /// Start a new line, moving the caret back to the start & down by the
+    /// font's line height, ascent - descent + line gap. Produces no glyph.
+    Break,
+    /// Reject newlines, for single line text. Layout ends before the first
+    /// newline & `LayoutIter::stopped_at_newline` returns `true`.
+    Error,
+}
+
+/// The metric used to measure the width of a string, see
+/// `Font::measure_width`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum WidthMetric {
+    /// The sum of glyph advance widths & kerning, ie the caret position after
+    /// the last glyph. Use this for cursor placement and for positioning
+    /// subsequent text.
+    #[default]
+    Advance,
+    /// The horizontal extent of the glyph shapes, from the leftmost to the
+    /// rightmost exact glyph bounds. Unlike `Advance` this includes any
+    /// overhang of the last glyph past its advance (a negative right side
+    /// bearing), and excludes side bearings. Use this for tight box fitting.
+    Ink,
+}
+
+/// Rounding policy used to convert exact glyph bounds, in fractional pixels,
+/// into a `PositionedGlyph`'s pixel bounding box.
+///
+/// Rounding is applied to the glyph bounds relative to the integer part of the
+/// glyph position, so glyphs at the same subpixel offset produce identical
+/// bounds.
+///
+/// Modes other than `Conservative` can produce bounds smaller than the exact
+/// shape, in which case coverage outside the bounds is clipped when drawing.
+/// They are useful to match the pixel bounds of other renderers.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PixelRounding {
+    /// Floors the minimum and ceils the maximum bounds, producing the
+    /// smallest pixel aligned rectangle that encloses the exact shape.
+    #[default]
+    Conservative,
+    /// Floors both the minimum and maximum bounds.
+    Floor,
+    /// Ceils both the minimum and maximum bounds.
+    Ceil,
+    /// Rounds both the minimum and maximum bounds to the nearest pixel, with
+    /// halfway cases rounded away from zero.
+    Round,
+    /// Truncates both the minimum and maximum bounds toward zero.
+    Truncate,
+}
+
+impl PixelRounding {
+    #[inline]
+    pub(crate) fn round_min(self, v: f32) -> i32 {
+        match self {
+            Self::Conservative | Self::Floor => v.floor() as i32,
+            Self::Ceil => v.ceil() as i32,
+            Self::Round => v.round() as i32,
+            Self::Truncate => v.trunc() as i32,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn round_max(self, v: f32) -> i32 {
+        match self {
+            Self::Conservative | Self::Ceil => v.ceil() as i32,
+            Self::Floor => v.floor() as i32,
+            Self::Round => v.round() as i32,
+            Self::Truncate => v.trunc() as i32,
+        }
+    }
+}
+
 /// Defines the size of a rendered face of a font, in pixels, horizontally and
 /// vertically. A vertical scale of `y` pixels means that the distance between
 /// the ascent and descent lines (see `VMetrics`) of the face will be `y`
@@ -499,7 +970,30 @@ impl Scale {
     pub fn uniform(s: f32) -> Scale {
         Scale { x: s, y: s }
     }
+
+    /// Uniform scaling of `pt` points at `dpi` pixels per inch, ie
+    /// `pt * dpi / 72` pixels.
+    ///
+    /// Note point sizes are conventionally em sizes, as in CSS or word
+    /// processors, where `Scale` is the ascent to descent height. To match
+    /// those use `Font::scale_for_em_height` with `Scale::from_pt(..).y`.
+    #[inline]
+    pub fn from_pt(pt: f32, dpi: f32) -> Scale {
+        Scale::uniform(pt * dpi / 72.0)
+    }
 }
+/// What a text size in pixels measures, see `Font::resolve_scale`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SizeSemantics {
+    /// The distance from the font's ascent to descent line, rusttype's
+    /// `Scale` convention.
+    #[default]
+    AscentDescent,
+    /// The font's em square, as CSS `font-size` & point sizes in other
+    /// software, so text sized `16.0` matches browser rendering at `16px`.
+    EmSquare,
+}
+
 /// A trait for types that can be converted into a `GlyphId`, in the context of
 /// a specific font.
 ///
@@ -514,10 +1008,7 @@ pub trait IntoGlyphId {
 impl IntoGlyphId for char {
     #[inline]
     fn into_glyph_id(self, font: &Font<'_>) -> GlyphId {
-        font.inner()
-            .glyph_index(self)
-            .unwrap_or(owned_ttf_parser::GlyphId(0))
-            .into()
+        font.char_glyph_id(self)
     }
 }
 impl<G: Into<GlyphId>> IntoGlyphId for G {
@@ -556,25 +1047,585 @@ pub struct LayoutIter<'a, 'font, 's> {
     scale: Scale,
     start: Point<f32>,
     last_glyph: Option<GlyphId>,
+    /// Caret before the advance of the last glyph produced, after kerning
+    last_caret: f32,
+    /// Caret movement around the last glyph produced
+    last_advance: GlyphAdvance,
+    options: LayoutOptions,
+    kern_table: Option<&'a KernTable<'font>>,
+    #[cfg(feature = "unicode-segmentation")]
+    graphemes: Option<GraphemeState>,
+}
+
+/// The caret movement a layout applied around a glyph, see
+/// `LayoutIter::advances`.
+///
+/// The caret moves by `kerning` before the glyph is placed, then by
+/// `advance` after it.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct GlyphAdvance {
+    /// Kerning with the previous glyph, in pixels.
+    pub kerning: f32,
+    /// The glyph's advance width plus any `LayoutOptions` letter & word
+    /// spacing, in pixels. Zero for glyphs that don't advance the caret, eg
+    /// attached marks with `LayoutIter::graphemes`.
+    pub advance: f32,
+}
+
+/// Grapheme cluster progress of a `LayoutIter`.
+#[cfg(feature = "unicode-segmentation")]
+#[derive(Clone, Copy, Debug)]
+struct GraphemeState {
+    /// Bytes left of the current cluster.
+    remaining: usize,
+    /// Position & advance width of the current cluster's base glyph.
+    base_x: f32,
+    base_advance: f32,
+    base_id: GlyphId,
+    /// The last attached mark of the current cluster & its position.
+    mark: Option<(GlyphId, Point<f32>)>,
+}
+
+#[cfg(feature = "unicode-segmentation")]
+impl Default for GraphemeState {
+    fn default() -> Self {
+        Self {
+            remaining: 0,
+            base_x: 0.0,
+            base_advance: 0.0,
+            base_id: GlyphId(0),
+            mark: None,
+        }
+    }
 }
 
 impl<'a, 'font, 's> Iterator for LayoutIter<'a, 'font, 's> {
     type Item = PositionedGlyph<'font>;
 
     fn next(&mut self) -> Option<PositionedGlyph<'font>> {
+        let (id, position) = self.next_placed()?;
+        Some(self.font.glyph(id).scaled(self.scale).positioned(position))
+    }
+}
+
+impl<'a, 'font, 's> LayoutIter<'a, 'font, 's> {
+    /// Lays out the next glyph, without cloning the font into it.
+    fn next_placed(&mut self) -> Option<(GlyphId, Point<f32>)> {
+        while self.skip_option_char() {}
+        if self.stopped_at_newline() {
+            return None;
+        }
+
+        #[cfg(feature = "unicode-segmentation")]
+        if let Some(state) = self.graphemes.as_mut() {
+            use unicode_segmentation::UnicodeSegmentation;
+
+            if state.remaining == 0 {
+                let rest = self.chars.as_str();
+                state.remaining = rest.graphemes(true).next().map_or(0, str::len);
+            } else {
+                // position an attached char, eg combining mark, over its base
+                let c = self.chars.next()?;
+                state.remaining = state.remaining.saturating_sub(c.len_utf8());
+                let g = self.font.glyph_ref(c).scaled(self.scale);
+                let base = point(state.base_x, self.start.y);
+                let anchored = state
+                    .mark
+                    .and_then(|(id, p)| {
+                        Some(p + self.font.mark_attachment(self.scale, id, g.id())?)
+                    })
+                    .or_else(|| {
+                        Some(
+                            base + self
+                                .font
+                                .mark_attachment(self.scale, state.base_id, g.id())?,
+                        )
+                    });
+                let position = anchored.unwrap_or_else(|| {
+                    let advance = g.h_metrics().advance_width;
+                    // zero advance marks are designed to be drawn at the caret
+                    let x = if advance == 0.0 {
+                        self.start.x + self.caret
+                    } else {
+                        state.base_x + (state.base_advance - advance) / 2.0
+                    };
+                    point(x, self.start.y)
+                });
+                state.mark = Some((g.id(), position));
+                self.last_caret = self.caret;
+                self.last_advance = GlyphAdvance::default();
+                return Some((g.id(), position));
+            }
+        }
+
         self.chars.next().map(|c| {
-            let g = self.font.glyph(c).scaled(self.scale);
-            if let Some(last) = self.last_glyph {
-                self.caret += self.font.pair_kerning(self.scale, last, g.id());
+            let g = self.font.glyph_ref(c).scaled(self.scale);
+            let kerning = self.last_glyph.map_or(0.0, |last| match self.kern_table {
+                Some(table) => table.pair_kerning(self.scale, last, g.id()),
+                None => self.font.pair_kerning(self.scale, last, g.id()),
+            });
+            self.caret += kerning;
+            let position = point(self.start.x + self.caret, self.start.y);
+            let advance = g.h_metrics().advance_width;
+            #[cfg(feature = "unicode-segmentation")]
+            if let Some(state) = self.graphemes.as_mut() {
+                state.remaining = state.remaining.saturating_sub(c.len_utf8());
+                state.base_x = self.start.x + self.caret;
+                state.base_advance = advance;
+                state.base_id = g.id();
+                state.mark = None;
             }
-            let g = g.positioned(point(self.start.x + self.caret, self.start.y));
-            self.caret += g.sg.h_metrics().advance_width;
+            // spacing only follows glyphs that advance the caret, not eg
+            // combining marks
+            let mut advance = advance;
+            if !advance.is_near_zero() {
+                advance += self.options.letter_spacing;
+                if char_class::is_word_separator(c) {
+                    advance += self.options.word_spacing;
+                }
+            }
+            self.last_caret = self.caret;
+            self.caret += advance;
             self.last_glyph = Some(g.id());
-            g
+            self.last_advance = GlyphAdvance { kerning, advance };
+            (g.id(), position)
+        })
+    }
+
+    /// Returns an iterator laying out tabs, newlines & other control chars
+    /// according to `options`. See `LayoutOptions`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::*;
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let options = LayoutOptions::new()
+    ///     .with_tab_width(Some(TabWidth::Pixels(40.0)))
+    ///     .with_newline(NewlineMode::Break);
+    /// let glyphs: Vec<_> = font
+    ///     .layout("a\tb\nc", Scale::uniform(20.0), point(0.0, 20.0))
+    ///     .with_options(options)
+    ///     .collect();
+    ///
+    /// assert_eq!(glyphs.len(), 3);
+    /// assert_eq!(glyphs[1].position().x, 40.0);
+    /// assert_eq!(glyphs[2].position().x, 0.0);
+    /// assert!(glyphs[2].position().y > 20.0);
+    /// ```
+    pub fn with_options(mut self, options: LayoutOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Returns `true` if layout ended early at a newline, rejected by
+    /// `NewlineMode::Error`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::*;
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let options = LayoutOptions::new().with_newline(NewlineMode::Error);
+    /// let mut layout = font
+    ///     .layout("ab\nc", Scale::uniform(20.0), point(0.0, 20.0))
+    ///     .with_options(options);
+    ///
+    /// assert_eq!(layout.by_ref().count(), 2);
+    /// assert!(layout.stopped_at_newline());
+    /// ```
+    pub fn stopped_at_newline(&self) -> bool {
+        self.options.newline == NewlineMode::Error && self.chars.as_str().starts_with('\n')
+    }
+
+    /// Returns an iterator looking up kerning in `kern_table`, faster for
+    /// long text than the default per pair lookup. See `Font::kern_table`.
+    ///
+    /// `kern_table` should be of the laid out font, otherwise the default
+    /// lookup is used.
+    pub fn with_kern_table(mut self, kern_table: &'a KernTable<'font>) -> Self {
+        self.kern_table = (kern_table.font() == self.font).then_some(kern_table);
+        self
+    }
+
+    /// Consumes the next char if the layout options skip it, applying its
+    /// effect on the caret. Returns `true` if a char was skipped.
+    fn skip_option_char(&mut self) -> bool {
+        let Some(c) = self.chars.clone().next() else {
+            return false;
+        };
+        let options = self.options;
+        match (c, options.tab_width) {
+            ('\t', Some(tab_width)) => {
+                let tab_width = match tab_width {
+                    TabWidth::Spaces(spaces) => {
+                        let space = self.font.glyph_ref(' ').scaled(self.scale);
+                        spaces * space.h_metrics().advance_width
+                    }
+                    TabWidth::Pixels(pixels) => pixels,
+                };
+                if tab_width > 0.0 {
+                    self.caret = ((self.caret / tab_width).floor() + 1.0) * tab_width;
+                }
+                self.last_glyph = None;
+            }
+            ('\n', _) if options.newline == NewlineMode::Ignore => {}
+            ('\n', _) if options.newline == NewlineMode::Break => {
+                let v_metrics = self.font.v_metrics(self.scale);
+                self.start.y += v_metrics.ascent - v_metrics.descent + v_metrics.line_gap;
+                self.caret = 0.0;
+                self.last_glyph = None;
+            }
+            ('\t' | '\n', _) => return false,
+            (c, _) if options.skip_control && char_class::is_default_ignorable(c) => {}
+            _ => return false,
+        }
+        self.chars.next();
+        #[cfg(feature = "unicode-segmentation")]
+        if let Some(state) = self.graphemes.as_mut() {
+            state.remaining = state.remaining.saturating_sub(c.len_utf8());
+        }
+        true
+    }
+
+    /// Lays out extended grapheme clusters as units, so combining sequences,
+    /// like "e" + U+0301 for "é", render as one character.
+    ///
+    /// The first char of each cluster is laid out as normal. Following chars,
+    /// eg combining marks, don't advance the caret and aren't kerned. They
+    /// are positioned on the font's GPOS anchors, see `Font::mark_attachment`,
+    /// attaching to the previous mark of the cluster or else the base glyph.
+    /// Without anchors, marks with a zero advance width are drawn at the
+    /// caret, as fonts design such marks to overlap the preceding glyph,
+    /// others are centred over the base glyph.
+    ///
+    /// Requires the `unicode-segmentation` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::*;
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let scale = Scale::uniform(20.0);
+    /// let glyphs: Vec<_> = font
+    ///     .layout("e\u{301}x", scale, point(0.0, 0.0))
+    ///     .graphemes()
+    ///     .collect();
+    ///
+    /// // the mark doesn't take up space, "x" follows "e"
+    /// let e_advance = font.glyph('e').scaled(scale).h_metrics().advance_width;
+    /// assert_eq!(glyphs[2].position().x, e_advance);
+    /// ```
+    #[cfg(feature = "unicode-segmentation")]
+    pub fn graphemes(mut self) -> Self {
+        self.graphemes = Some(GraphemeState::default());
+        self
+    }
+
+    /// Returns an iterator producing each positioned glyph along with a
+    /// `GlyphCluster` mapping it back to the source text.
+    ///
+    /// Glyph indices & text byte offsets are relative to the remaining text
+    /// of this iterator, so for a new layout they refer to the whole input.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::*;
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let text = "añb";
+    /// let clusters: Vec<_> = font
+    ///     .layout(text, Scale::uniform(20.0), point(0.0, 0.0))
+    ///     .clusters()
+    ///     .map(|(cluster, _glyph)| cluster)
+    ///     .collect();
+    ///
+    /// assert_eq!(clusters[1].glyph_index, 1);
+    /// assert_eq!(&text[clusters[1].text_range()], "ñ");
+    /// ```
+    pub fn clusters(self) -> LayoutClusters<'a, 'font, 's> {
+        LayoutClusters {
+            text: self.chars.as_str(),
+            glyph_index: 0,
+            char_index: 0,
+            layout: self,
+        }
+    }
+
+    /// Returns an iterator producing the same glyphs as plain `PlacedGlyph`
+    /// values, without a font handle. See `Font::layout_ids`.
+    pub fn ids(self) -> LayoutIds<'a, 'font, 's> {
+        LayoutIds { layout: self }
+    }
+
+    /// Returns an iterator producing each positioned glyph along with the
+    /// `GlyphAdvance` the layout applied around it.
+    ///
+    /// Allows post-passes, eg justification or per character animation, to
+    /// adjust spacing without re-deriving advances & kerning from the font.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::*;
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/opensans/OpenSans-Italic.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let scale = Scale::uniform(24.0);
+    /// let glyphs: Vec<_> = font.layout("AV", scale, point(0.0, 0.0)).advances().collect();
+    ///
+    /// let (a, a_advance) = &glyphs[0];
+    /// let (v, v_advance) = &glyphs[1];
+    /// assert_eq!(v_advance.kerning, font.pair_kerning(scale, 'A', 'V'));
+    /// assert_eq!(v.position().x, a.position().x + a_advance.advance + v_advance.kerning);
+    /// ```
+    pub fn advances(self) -> LayoutAdvances<'a, 'font, 's> {
+        LayoutAdvances { layout: self }
+    }
+
+    /// Returns an iterator producing the same glyphs, that first calls `f`
+    /// with each glyph's `LayoutGlyphInfo`.
+    ///
+    /// This allows computing decorations, eg backgrounds or inline widget
+    /// placeholders, in the same pass as the layout without repeating the
+    /// caret math.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::*;
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let text = "a b";
+    /// let mut spaces = Vec::new();
+    /// let glyphs: Vec<_> = font
+    ///     .layout(text, Scale::uniform(20.0), point(10.0, 20.0))
+    ///     .on_glyph(|info| {
+    ///         if &text[info.cluster.text_range()] == " " {
+    ///             spaces.push(info.caret_start..info.caret_end);
+    ///         }
+    ///     })
+    ///     .collect();
+    ///
+    /// assert_eq!(spaces, [glyphs[1].position().x..glyphs[2].position().x]);
+    /// ```
+    pub fn on_glyph<F>(self, f: F) -> LayoutHook<'a, 'font, 's, F>
+    where
+        F: FnMut(&LayoutGlyphInfo<'_, 'font>),
+    {
+        LayoutHook {
+            clusters: self.clusters(),
+            f,
+        }
+    }
+}
+
+/// Maps a glyph of a layout back to the range of source text it was produced
+/// from. See `LayoutIter::clusters`.
+///
+/// Fields are plain integers so clusters can be easily serialised for use by
+/// accessibility & text extraction layers, with the `serde` feature clusters
+/// are serializable.
+///
+/// Clusters depend only on the text up to & including their own, so when
+/// text grows, eg a streamed chat message, glyphs of the unchanged prefix
+/// keep equal clusters across layouts. Use them, eg `char_index`, as keys of
+/// per character animation state.
+///
+/// # Example
+///
+/// ```
+/// # use rusttype::*;
+/// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+/// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+/// let clusters = |text| {
+///     font.layout(text, Scale::uniform(20.0), point(0.0, 0.0))
+///         .clusters()
+///         .map(|(cluster, _)| cluster)
+///         .collect::<Vec<_>>()
+/// };
+/// let before = clusters("Héllo");
+/// let after = clusters("Héllo wörld");
+///
+/// assert_eq!(after[..before.len()], before);
+/// // new chars to fade in
+/// assert_eq!(after[before.len()].char_index, 5);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct GlyphCluster {
+    /// Index of the glyph in the layout output.
+    pub glyph_index: usize,
+    /// Index, in chars, of the start of the source text.
+    pub char_index: usize,
+    /// Byte offset of the start of the source text.
+    pub text_start: usize,
+    /// Byte offset of the end (exclusive) of the source text.
+    pub text_end: usize,
+}
+
+impl GlyphCluster {
+    /// Creates a cluster of the glyph at `glyph_index` for the source text at
+    /// byte range `text_range`, starting at char `char_index`.
+    pub fn new(glyph_index: usize, char_index: usize, text_range: core::ops::Range<usize>) -> Self {
+        Self {
+            glyph_index,
+            char_index,
+            text_start: text_range.start,
+            text_end: text_range.end,
+        }
+    }
+
+    /// The byte range of the source text.
+    #[inline]
+    pub fn text_range(&self) -> core::ops::Range<usize> {
+        self.text_start..self.text_end
+    }
+}
+
+/// Iterator of positioned glyphs with their `GlyphCluster`s, created by
+/// `LayoutIter::clusters`.
+///
+/// With the `serde` feature, clusters collected into a `Vec<GlyphCluster>`
+/// serialize as a glyph to source text mapping table.
+#[derive(Clone)]
+pub struct LayoutClusters<'a, 'font, 's> {
+    layout: LayoutIter<'a, 'font, 's>,
+    text: &'s str,
+    glyph_index: usize,
+    char_index: usize,
+}
+
+impl<'font> Iterator for LayoutClusters<'_, 'font, '_> {
+    type Item = (GlyphCluster, PositionedGlyph<'font>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let text_len = self.text.len();
+        let skipped_start = text_len - self.layout.chars.as_str().len();
+        // chars skipped by the layout options belong to no glyph's cluster
+        while self.layout.skip_option_char() {}
+        let text_start = text_len - self.layout.chars.as_str().len();
+        self.char_index += self.text[skipped_start..text_start].chars().count();
+        let glyph = self.layout.next()?;
+        let cluster = GlyphCluster {
+            glyph_index: self.glyph_index,
+            char_index: self.char_index,
+            text_start,
+            text_end: text_len - self.layout.chars.as_str().len(),
+        };
+        self.glyph_index += 1;
+        self.char_index += self.text[cluster.text_range()].chars().count();
+        Some((cluster, glyph))
+    }
+}
+
+/// Iterator of positioned glyphs with their `GlyphAdvance`s, created by
+/// `LayoutIter::advances`.
+#[derive(Clone)]
+pub struct LayoutAdvances<'a, 'font, 's> {
+    layout: LayoutIter<'a, 'font, 's>,
+}
+
+impl<'font> Iterator for LayoutAdvances<'_, 'font, '_> {
+    type Item = (PositionedGlyph<'font>, GlyphAdvance);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let glyph = self.layout.next()?;
+        Some((glyph, self.layout.last_advance))
+    }
+}
+
+/// A laid out glyph without a font handle, see `Font::layout_ids`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PlacedGlyph {
+    /// The glyph's id in the laid out font.
+    pub id: GlyphId,
+    /// Position of the glyph origin, as `PositionedGlyph::position`.
+    pub position: Point<f32>,
+    /// The layout scale.
+    pub scale: Scale,
+}
+
+/// Iterator producing laid out glyphs as `PlacedGlyph` values, see
+/// `Font::layout_ids`.
+#[derive(Clone)]
+pub struct LayoutIds<'a, 'font, 's> {
+    layout: LayoutIter<'a, 'font, 's>,
+}
+
+impl Iterator for LayoutIds<'_, '_, '_> {
+    type Item = PlacedGlyph;
+
+    #[inline]
+    fn next(&mut self) -> Option<PlacedGlyph> {
+        let (id, position) = self.layout.next_placed()?;
+        Some(PlacedGlyph {
+            id,
+            position,
+            scale: self.layout.scale,
         })
     }
 }
 
+/// A laid out glyph with its metrics & cluster, passed to the
+/// `LayoutIter::on_glyph` callback.
+///
+/// Fields may be added in minor releases, so this can't be constructed or
+/// exhaustively destructured outside of rusttype.
+#[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
+pub struct LayoutGlyphInfo<'g, 'font> {
+    /// The positioned glyph, as produced by the layout.
+    pub glyph: &'g PositionedGlyph<'font>,
+    /// Maps the glyph back to the source text.
+    pub cluster: GlyphCluster,
+    /// The scaled horizontal metrics of the glyph.
+    pub h_metrics: HMetrics,
+    /// Horizontal position of the caret before the glyph, after kerning.
+    pub caret_start: f32,
+    /// Horizontal position of the caret after the glyph. Equals `caret_start`
+    /// for glyphs that don't advance the caret, eg attached marks with
+    /// `LayoutIter::graphemes`.
+    pub caret_end: f32,
+    /// The kerning & advance applied around the glyph, see
+    /// `LayoutIter::advances`.
+    pub advance: GlyphAdvance,
+}
+
+/// Iterator of positioned glyphs calling a user callback per glyph, created
+/// by `LayoutIter::on_glyph`.
+#[derive(Clone)]
+pub struct LayoutHook<'a, 'font, 's, F> {
+    clusters: LayoutClusters<'a, 'font, 's>,
+    f: F,
+}
+
+impl<'font, F> Iterator for LayoutHook<'_, 'font, '_, F>
+where
+    F: FnMut(&LayoutGlyphInfo<'_, 'font>),
+{
+    type Item = PositionedGlyph<'font>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (cluster, glyph) = self.clusters.next()?;
+        let layout = &self.clusters.layout;
+        (self.f)(&LayoutGlyphInfo {
+            glyph: &glyph,
+            cluster,
+            h_metrics: glyph.unpositioned().h_metrics(),
+            caret_start: layout.start.x + layout.last_caret,
+            caret_end: layout.start.x + layout.caret,
+            advance: layout.last_advance,
+        });
+        Some(glyph)
+    }
+}
+
 pub(crate) trait NearZero {
     /// Returns if this number is kinda pretty much zero.
     fn is_near_zero(&self) -> bool;
@@ -582,6 +1633,6 @@ pub(crate) trait NearZero {
 impl NearZero for f32 {
     #[inline]
     fn is_near_zero(&self) -> bool {
-        self.abs() <= core::f32::EPSILON
+        self.abs() <= f32::EPSILON
     }
 }
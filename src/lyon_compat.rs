@@ -0,0 +1,118 @@
+//! Building [lyon](https://docs.rs/lyon) paths from glyph outlines.
+use crate::{OutlineBuilder, PositionedGlyph, ScaledGlyph};
+use lyon_path::{math::point, Path, PathEvent};
+
+/// `OutlineBuilder` adapter tracking open sub-paths, which lyon requires to
+/// be explicitly ended.
+struct LyonBuilder {
+    builder: lyon_path::path::Builder,
+    open: bool,
+}
+
+impl LyonBuilder {
+    fn new() -> Self {
+        Self {
+            builder: Path::builder(),
+            open: false,
+        }
+    }
+
+    fn build(mut self) -> Path {
+        if self.open {
+            self.builder.end(false);
+        }
+        self.builder.build()
+    }
+}
+
+impl OutlineBuilder for LyonBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        if self.open {
+            self.builder.end(false);
+        }
+        self.builder.begin(point(x, y));
+        self.open = true;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.builder.line_to(point(x, y));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.builder.quadratic_bezier_to(point(x1, y1), point(x, y));
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.builder
+            .cubic_bezier_to(point(x1, y1), point(x2, y2), point(x, y));
+    }
+
+    fn close(&mut self) {
+        if self.open {
+            self.builder.end(true);
+            self.open = false;
+        }
+    }
+}
+
+impl ScaledGlyph<'_> {
+    /// Builds the outline of the glyph into a lyon `Path`, relative to the
+    /// glyph origin with y increasing downwards as with `build_outline`.
+    ///
+    /// Returns `None` when the outline is either malformed or empty.
+    ///
+    /// Requires the `lyon` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let glyph = font.glyph('o').scaled(Scale::uniform(24.0));
+    /// let path = glyph.lyon_path().unwrap();
+    ///
+    /// // 'o' has an outer & inner contour
+    /// let contours = path
+    ///     .iter()
+    ///     .filter(|e| matches!(e, lyon_path::PathEvent::Begin { .. }))
+    ///     .count();
+    /// assert_eq!(contours, 2);
+    /// ```
+    pub fn lyon_path(&self) -> Option<Path> {
+        let mut builder = LyonBuilder::new();
+        self.build_outline(&mut builder).then(|| builder.build())
+    }
+
+    /// Returns the outline of the glyph as lyon `PathEvent`s. See `lyon_path`.
+    ///
+    /// Empty for malformed or empty outlines.
+    ///
+    /// Requires the `lyon` feature.
+    pub fn path_events(&self) -> impl Iterator<Item = PathEvent> {
+        self.lyon_path()
+            .map(|path| path.iter().collect::<alloc::vec::Vec<_>>())
+            .unwrap_or_default()
+            .into_iter()
+    }
+}
+
+impl PositionedGlyph<'_> {
+    /// Builds the outline of the glyph into a lyon `Path` at its position, so
+    /// the paths of a laid out string may be tessellated together.
+    ///
+    /// Note: Unlike `build_outline`, coordinates are not relative to the pixel
+    /// bounding box.
+    ///
+    /// Returns `None` when the outline is either malformed or empty.
+    ///
+    /// Requires the `lyon` feature.
+    pub fn lyon_path(&self) -> Option<Path> {
+        self.unpositioned().lyon_path().map(|path| {
+            path.transformed(&lyon_path::math::Translation::new(
+                self.position().x,
+                self.position().y,
+            ))
+        })
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{point, Font, Point, Rect, Scale, WidthMetric};
+
+/// Space around text, in pixels, see `Font::nine_slice`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Padding {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl Padding {
+    /// Equal padding on all sides.
+    #[inline]
+    pub fn uniform(padding: f32) -> Self {
+        Self::symmetric(padding, padding)
+    }
+
+    /// `horizontal` padding left & right, `vertical` padding top & bottom.
+    #[inline]
+    pub fn symmetric(horizontal: f32, vertical: f32) -> Self {
+        Self {
+            left: horizontal,
+            top: vertical,
+            right: horizontal,
+            bottom: vertical,
+        }
+    }
+}
+
+/// 9-slice geometry of a background behind a line of text, eg a pill or
+/// badge, see `Font::nine_slice`.
+///
+/// The background is split by two vertical & two horizontal lines, `insets`
+/// from its edges, into fixed size corners, edges stretched along one axis &
+/// a centre stretched along both.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NineSlice {
+    /// The outer bounds of the background in pixels, y increasing downwards.
+    pub rect: Rect<f32>,
+    /// Distances of the slice lines from the left, top, right & bottom edges
+    /// of `rect`, ie the corner sizes.
+    pub insets: Padding,
+    /// The y coordinate of the text baseline.
+    pub baseline: f32,
+}
+
+impl NineSlice {
+    /// Returns the nine slices in row major order, top-left first. Slices
+    /// are empty along an axis where the insets meet.
+    pub fn slices(&self) -> [Rect<f32>; 9] {
+        let Rect { min, max } = self.rect;
+        let xs = [
+            min.x,
+            min.x + self.insets.left,
+            max.x - self.insets.right,
+            max.x,
+        ];
+        let ys = [
+            min.y,
+            min.y + self.insets.top,
+            max.y - self.insets.bottom,
+            max.y,
+        ];
+        core::array::from_fn(|i| {
+            let (column, row) = (i % 3, i / 3);
+            Rect {
+                min: point(xs[column], ys[row]),
+                max: point(xs[column + 1], ys[row + 1]),
+            }
+        })
+    }
+}
+
+impl Font<'_> {
+    /// Returns 9-slice geometry of a background behind `text` laid out at
+    /// `scale` with its baseline starting at `start`, eg for UI badges &
+    /// pills.
+    ///
+    /// The background spans the advance width of the text horizontally &
+    /// the font's ascent to descent vertically, so backgrounds of different
+    /// text on the same baseline line up, plus `padding`. Corners are
+    /// `corner_radius` square, limited to half the background size, so a
+    /// radius of at least half the height gives a pill.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{point, Font, Padding, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let start = point(10.0, 30.0);
+    /// let badge = font.nine_slice("New", Scale::uniform(16.0), start, Padding::symmetric(8.0, 2.0), 100.0);
+    ///
+    /// assert_eq!(badge.baseline, start.y);
+    /// // pill shaped, fully rounded ends
+    /// assert_eq!(badge.insets.left, badge.rect.height() / 2.0);
+    /// for slice in badge.slices() {
+    ///     // draw the matching part of the background image over `slice`
+    /// }
+    /// ```
+    pub fn nine_slice(
+        &self,
+        text: &str,
+        scale: Scale,
+        start: Point<f32>,
+        padding: Padding,
+        corner_radius: f32,
+    ) -> NineSlice {
+        let width = self.measure_width(text, scale, WidthMetric::Advance);
+        let v_metrics = self.v_metrics(scale);
+        let rect = Rect {
+            min: point(
+                start.x - padding.left,
+                start.y - v_metrics.ascent - padding.top,
+            ),
+            max: point(
+                start.x + width + padding.right,
+                start.y - v_metrics.descent + padding.bottom,
+            ),
+        };
+        let corner = corner_radius
+            .min(rect.width() / 2.0)
+            .min(rect.height() / 2.0)
+            .max(0.0);
+        NineSlice {
+            rect,
+            insets: Padding::uniform(corner),
+            baseline: start.y,
+        }
+    }
+}
@@ -5,6 +5,10 @@ pub(crate) trait FloatExt {
     fn trunc(self) -> Self;
     fn round(self) -> Self;
     fn abs(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn sin_cos(self) -> (Self, Self)
+    where
+        Self: Sized;
 }
 
 impl FloatExt for f32 {
@@ -32,4 +36,12 @@ impl FloatExt for f32 {
     fn abs(self) -> Self {
         libm::fabsf(self)
     }
+    #[inline]
+    fn sqrt(self) -> Self {
+        libm::sqrtf(self)
+    }
+    #[inline]
+    fn sin_cos(self) -> (Self, Self) {
+        (libm::sinf(self), libm::cosf(self))
+    }
 }
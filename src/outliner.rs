@@ -1,5 +1,10 @@
-use crate::{Point, Vector};
+#[cfg(all(feature = "libm-math", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+use crate::{point, Point, Vector};
 use ab_glyph_rasterizer::{point as ab_point, Point as AbPoint, Rasterizer};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt;
 use owned_ttf_parser::OutlineBuilder;
 
 pub(crate) struct OutlineScaler<'b, T: ?Sized> {
@@ -94,24 +99,77 @@ impl<T: OutlineBuilder + ?Sized> OutlineBuilder for OutlineTranslator<'_, T> {
     }
 }
 
-pub(crate) struct OutlineRasterizer {
+/// Rasterises outlines built with `OutlineBuilder` commands, eg icons
+/// converted from SVG, with the same analytic coverage as glyphs.
+///
+/// Coordinates are pixels, with `(0, 0)` the top-left of the `width` x
+/// `height` canvas & y increasing downwards, as `PositionedGlyph::build_outline`
+/// produces relative to the glyph's pixel bounding box. Contours are filled
+/// with the non-zero winding rule & an unclosed contour is closed by the next
+/// `move_to`, so call `close` to end the last.
+///
+/// # Example
+///
+/// ```
+/// use rusttype::{OutlineBuilder, OutlineRasterizer};
+///
+/// let mut rasterizer = OutlineRasterizer::new(4, 4);
+/// rasterizer.move_to(1.0, 1.0);
+/// rasterizer.line_to(3.0, 1.0);
+/// rasterizer.line_to(3.0, 3.0);
+/// rasterizer.line_to(1.0, 3.0);
+/// rasterizer.close();
+///
+/// rasterizer.for_each_pixel(|x, y, v| {
+///     let inside = (1..3).contains(&x) && (1..3).contains(&y);
+///     assert_eq!(v, if inside { 1.0 } else { 0.0 });
+/// });
+/// ```
+pub struct OutlineRasterizer {
     pub(crate) rasterizer: Rasterizer,
     last: AbPoint,
     last_move: Option<AbPoint>,
+    open: bool,
 }
 
 impl OutlineRasterizer {
-    pub(crate) fn new(width: usize, height: usize) -> Self {
+    /// Creates a rasteriser with an empty `width` x `height` pixel canvas.
+    pub fn new(width: usize, height: usize) -> Self {
         Self {
             rasterizer: Rasterizer::new(width, height),
             last: ab_point(0.0, 0.0),
             last_move: None,
+            open: false,
         }
     }
+
+    /// Returns the canvas `(width, height)` in pixels.
+    #[inline]
+    pub fn dimensions(&self) -> (usize, usize) {
+        self.rasterizer.dimensions()
+    }
+
+    /// Calls `o` with the x, y & coverage, `0.0..=1.0`, of every canvas pixel,
+    /// as `PositionedGlyph::draw`.
+    #[inline]
+    pub fn for_each_pixel<O: FnMut(u32, u32, f32)>(&self, mut o: O) {
+        // overlapping contours accumulate coverage beyond 1.0
+        self.rasterizer
+            .for_each_pixel_2d(|x, y, v| o(x, y, v.min(1.0)));
+    }
+}
+
+impl fmt::Debug for OutlineRasterizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OutlineRasterizer")
+            .field("dimensions", &self.dimensions())
+            .finish()
+    }
 }
 
 impl OutlineBuilder for OutlineRasterizer {
     fn move_to(&mut self, x: f32, y: f32) {
+        self.close();
         self.last = AbPoint { x, y };
         self.last_move = Some(self.last);
     }
@@ -121,6 +179,7 @@ impl OutlineBuilder for OutlineRasterizer {
 
         self.rasterizer.draw_line(self.last, p1);
         self.last = p1;
+        self.open = true;
     }
 
     fn quad_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32) {
@@ -129,6 +188,7 @@ impl OutlineBuilder for OutlineRasterizer {
 
         self.rasterizer.draw_quad(self.last, p1, p2);
         self.last = p2;
+        self.open = true;
     }
 
     fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x3: f32, y3: f32) {
@@ -138,11 +198,103 @@ impl OutlineBuilder for OutlineRasterizer {
 
         self.rasterizer.draw_cubic(self.last, p1, p2, p3);
         self.last = p3;
+        self.open = true;
     }
 
     fn close(&mut self) {
-        if let Some(m) = self.last_move {
+        if let Some(m) = self.last_move.filter(|_| self.open) {
             self.rasterizer.draw_line(self.last, m);
+            self.last = m;
+        }
+        self.open = false;
+    }
+}
+
+/// Flattens outlines into closed polygons of line segments, within
+/// `tolerance` of the curves.
+pub(crate) struct OutlineFlattener {
+    pub(crate) segments: Vec<(Point<f32>, Point<f32>)>,
+    /// Index of the first segment of each contour
+    pub(crate) contour_starts: Vec<usize>,
+    tolerance: f32,
+    last: Point<f32>,
+    last_move: Option<Point<f32>>,
+}
+
+impl OutlineFlattener {
+    pub(crate) fn new(tolerance: f32) -> Self {
+        Self {
+            segments: Vec::new(),
+            contour_starts: Vec::new(),
+            tolerance,
+            last: point(0.0, 0.0),
+            last_move: None,
+        }
+    }
+
+    fn push_line(&mut self, to: Point<f32>) {
+        if to != self.last {
+            self.segments.push((self.last, to));
+        }
+        self.last = to;
+    }
+
+    /// Number of line segments to flatten a curve with second differences of
+    /// length `dd` & degree factor `k`.
+    fn subdivisions(&self, dd: Vector<f32>, k: f32) -> u32 {
+        let dd = (dd.x * dd.x + dd.y * dd.y).sqrt();
+        ((dd * k / self.tolerance).sqrt().ceil() as u32).clamp(1, 100)
+    }
+}
+
+impl OutlineBuilder for OutlineFlattener {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.close();
+        self.contour_starts.push(self.segments.len());
+        self.last = point(x, y);
+        self.last_move = Some(self.last);
+    }
+
+    fn line_to(&mut self, x1: f32, y1: f32) {
+        self.push_line(point(x1, y1));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32) {
+        let (p0, p1, p2) = (self.last, point(x1, y1), point(x2, y2));
+        let n = self.subdivisions((p0 - p1) - (p1 - p2), 0.25);
+        for i in 1..=n {
+            let t = i as f32 / n as f32;
+            let mt = 1.0 - t;
+            self.push_line(point(
+                mt * mt * p0.x + 2.0 * mt * t * p1.x + t * t * p2.x,
+                mt * mt * p0.y + 2.0 * mt * t * p1.y + t * t * p2.y,
+            ));
+        }
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x3: f32, y3: f32) {
+        let (p0, p1, p2, p3) = (self.last, point(x1, y1), point(x2, y2), point(x3, y3));
+        let (dd1, dd2) = ((p0 - p1) - (p1 - p2), (p1 - p2) - (p2 - p3));
+        let dd = if dd1.x * dd1.x + dd1.y * dd1.y > dd2.x * dd2.x + dd2.y * dd2.y {
+            dd1
+        } else {
+            dd2
+        };
+        let n = self.subdivisions(dd, 0.75);
+        for i in 1..=n {
+            let t = i as f32 / n as f32;
+            let mt = 1.0 - t;
+            let (a, b, c, d) = (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+            self.push_line(point(
+                a * p0.x + b * p1.x + c * p2.x + d * p3.x,
+                a * p0.y + b * p1.y + c * p2.y + d * p3.y,
+            ));
+        }
+    }
+
+    fn close(&mut self) {
+        if let Some(m) = self.last_move.take() {
+            self.push_line(m);
         }
     }
 }
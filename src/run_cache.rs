@@ -0,0 +1,137 @@
+use crate::{float_key, point, Font, Point, PositionedGlyph, Scale};
+use alloc::collections::BTreeMap;
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// Font data id & scale.
+type FontScaleKey = (usize, (u32, u32));
+
+#[derive(Debug, Clone)]
+struct Run<'font> {
+    /// Keeps the font data, used for identity, from being reused.
+    _font: Font<'font>,
+    /// Glyphs laid out at the origin.
+    glyphs: Vec<PositionedGlyph<'font>>,
+    last_used: u64,
+}
+
+/// Memoises `Font::layout` results for repeatedly laid out strings, eg UI
+/// labels drawn every frame, avoiding repeated character to glyph mapping &
+/// kerning lookups.
+///
+/// Runs are keyed by font, text & scale. Laying out a cached run at another
+/// position only translates the cached glyphs. Once `capacity` runs are
+/// cached the least recently used run is evicted.
+///
+/// # Example
+///
+/// ```
+/// # use rusttype::{point, Font, RunCache, Scale};
+/// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+/// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+/// let mut runs = RunCache::new(64);
+///
+/// let scale = Scale::uniform(20.0);
+/// for frame in 0..3 {
+///     let start = point(10.0 + frame as f32, 20.0);
+///     let glyphs: Vec<_> = runs.layout(&font, "Score", scale, start).collect();
+///     assert!(glyphs.into_iter().eq(font.layout("Score", scale, start)));
+/// }
+/// assert_eq!(runs.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RunCache<'font> {
+    capacity: usize,
+    runs: BTreeMap<FontScaleKey, BTreeMap<String, Run<'font>>>,
+    /// Run keys by last use, for LRU eviction.
+    lru: BTreeMap<u64, (FontScaleKey, String)>,
+    tick: u64,
+}
+
+impl<'font> RunCache<'font> {
+    /// Creates a cache holding up to `capacity` runs, minimum 1.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            runs: BTreeMap::new(),
+            lru: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Returns the maximum number of cached runs.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of cached runs.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lru.len()
+    }
+
+    /// Returns `true` if no runs are cached.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.lru.is_empty()
+    }
+
+    /// Removes all cached runs.
+    pub fn clear(&mut self) {
+        self.runs.clear();
+        self.lru.clear();
+    }
+
+    /// Returns the same glyphs as `font.layout(text, scale, start)`, using &
+    /// updating the cache.
+    pub fn layout<'a>(
+        &'a mut self,
+        font: &Font<'font>,
+        text: &str,
+        scale: Scale,
+        start: Point<f32>,
+    ) -> impl Iterator<Item = PositionedGlyph<'font>> + 'a {
+        let key = (font.data_id(), (float_key(scale.x), float_key(scale.y)));
+        self.tick += 1;
+        let tick = self.tick;
+
+        let cached = self.runs.get_mut(&key).and_then(|runs| runs.get_mut(text));
+        if let Some(run) = cached {
+            let last_used = core::mem::replace(&mut run.last_used, tick);
+            let lru_key = self.lru.remove(&last_used).unwrap();
+            self.lru.insert(tick, lru_key);
+        } else {
+            while self.len() >= self.capacity.max(1) {
+                self.evict_lru();
+            }
+            let run = Run {
+                _font: font.clone(),
+                glyphs: font.layout(text, scale, point(0.0, 0.0)).collect(),
+                last_used: tick,
+            };
+            self.lru.insert(tick, (key, text.into()));
+            self.runs.entry(key).or_default().insert(text.into(), run);
+        }
+
+        let glyphs = &self.runs[&key][text].glyphs;
+        glyphs.iter().map(move |g| {
+            let mut g = g.clone();
+            let p = g.position();
+            g.set_position(point(p.x + start.x, p.y + start.y));
+            g
+        })
+    }
+
+    fn evict_lru(&mut self) {
+        let Some((_, (key, text))) = self.lru.pop_first() else {
+            return;
+        };
+        if let Some(runs) = self.runs.get_mut(&key) {
+            runs.remove(&text);
+            if runs.is_empty() {
+                self.runs.remove(&key);
+            }
+        }
+    }
+}
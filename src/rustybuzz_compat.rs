@@ -0,0 +1,143 @@
+use crate::{point, Font, GlyphId, Point, PositionedGlyph, Scale};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt;
+use rustybuzz::{Direction, Feature, Script, UnicodeBuffer};
+
+impl<'font> Font<'font> {
+    /// Returns a `Shaper` shaping text with this font, parsing the tables
+    /// rustybuzz needs once for any number of `Shaper::shape` calls.
+    ///
+    /// Returns `None` if rustybuzz can't parse the font data. The rustybuzz
+    /// face borrows this font's data, it is not copied.
+    ///
+    /// Requires the `rustybuzz` feature.
+    pub fn shaper(&self) -> Option<Shaper<'_, 'font>> {
+        let raw = self.inner().raw_face();
+        let face = rustybuzz::Face::from_slice(raw.data, self.collection_index())?;
+        Some(Shaper { font: self, face })
+    }
+
+    /// Shapes `text` with [rustybuzz](https://docs.rs/rustybuzz), a port of
+    /// HarfBuzz, returning glyphs positioned along a line from `start`, ready
+    /// to draw or queue into a `gpu_cache::Cache`.
+    ///
+    /// Unlike `Font::layout` this applies the font's OpenType features, eg
+    /// ligatures, contextual forms & mark positioning, as required by complex
+    /// scripts. `features` are applied in addition to the defaults. A `None`
+    /// `direction` or `script` is guessed from the text.
+    ///
+    /// Glyphs are returned in visual order, left to right. Returns no glyphs
+    /// if rustybuzz can't parse the font data. This parses the tables
+    /// rustybuzz needs for each call, use `Font::shaper` to shape many runs.
+    ///
+    /// Requires the `rustybuzz` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{point, Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let scale = Scale::uniform(24.0);
+    /// let glyphs = font.shape("Hello", &[], None, None, scale, point(0.0, 24.0));
+    ///
+    /// // simple latin text matches `Font::layout`
+    /// assert!(glyphs.into_iter().eq(font.layout("Hello", scale, point(0.0, 24.0))));
+    /// ```
+    pub fn shape(
+        &self,
+        text: &str,
+        features: &[Feature],
+        direction: Option<Direction>,
+        script: Option<Script>,
+        scale: Scale,
+        start: Point<f32>,
+    ) -> Vec<PositionedGlyph<'font>> {
+        self.shaper().map_or_else(Vec::new, |shaper| {
+            shaper.shape(text, features, direction, script, scale, start)
+        })
+    }
+}
+
+/// Shapes text with [rustybuzz](https://docs.rs/rustybuzz) over a font's
+/// tables parsed once, created by `Font::shaper`.
+///
+/// Requires the `rustybuzz` feature.
+///
+/// # Example
+///
+/// ```
+/// # use rusttype::{point, Font, Scale};
+/// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+/// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+/// let shaper = font.shaper().unwrap();
+/// let scale = Scale::uniform(24.0);
+/// for (i, line) in ["Hello", "World"].into_iter().enumerate() {
+///     let start = point(0.0, 24.0 * (i + 1) as f32);
+///     let glyphs = shaper.shape(line, &[], None, None, scale, start);
+///     assert_eq!(glyphs, font.shape(line, &[], None, None, scale, start));
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Shaper<'a, 'font> {
+    font: &'a Font<'font>,
+    face: rustybuzz::Face<'a>,
+}
+
+impl<'font> Shaper<'_, 'font> {
+    /// Shapes `text`, positioning glyphs along a line from `start`, see
+    /// `Font::shape`.
+    pub fn shape(
+        &self,
+        text: &str,
+        features: &[Feature],
+        direction: Option<Direction>,
+        script: Option<Script>,
+        scale: Scale,
+        start: Point<f32>,
+    ) -> Vec<PositionedGlyph<'font>> {
+        let font = self.font;
+        let mut buffer = UnicodeBuffer::new();
+        buffer.push_str(text);
+        if let Some(direction) = direction {
+            buffer.set_direction(direction);
+        }
+        if let Some(script) = script {
+            buffer.set_script(script);
+        }
+        buffer.guess_segment_properties();
+        let shaped = rustybuzz::shape(&self.face, features, buffer);
+
+        let scale_x = font.kerning_factor(scale);
+        let scale_y = font.scale_for_pixel_height(scale.y);
+        let mut caret = start;
+        shaped
+            .glyph_infos()
+            .iter()
+            .zip(shaped.glyph_positions())
+            .map(|(info, pos)| {
+                // font units are y-up
+                let position = point(
+                    caret.x + pos.x_offset as f32 * scale_x,
+                    caret.y - pos.y_offset as f32 * scale_y,
+                );
+                caret.x += pos.x_advance as f32 * scale_x;
+                caret.y -= pos.y_advance as f32 * scale_y;
+                // shaping malformed fonts may produce invalid ids
+                font.try_glyph(GlyphId(info.glyph_id as u16))
+                    .unwrap_or_else(|| font.glyph(GlyphId(0)))
+                    .scaled(scale)
+                    .positioned(position)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for Shaper<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shaper")
+            .field("font", &self.font)
+            .finish_non_exhaustive()
+    }
+}
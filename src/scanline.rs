@@ -0,0 +1,171 @@
+use crate::outliner::OutlineFlattener;
+use crate::{point, Point, ScaledGlyph};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+#[cfg(all(feature = "libm-math", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+
+/// Maximum distance, in pixels, of flattened outlines from the true curves.
+const FLATTEN_TOLERANCE: f32 = 0.02;
+
+/// Maximum number of hatch lines per glyph, bounding work & allocation for
+/// tiny `spacing` values.
+const MAX_HATCH_LINES: usize = 1 << 16;
+
+/// A glyph outline flattened into line segments, for computing inside spans
+/// along lines through the glyph.
+pub(crate) struct FlatOutline {
+    segments: Vec<(Point<f32>, Point<f32>)>,
+}
+
+impl FlatOutline {
+    /// Flattens the outline of `glyph`, relative to its origin with y
+    /// increasing downwards. Returns `None` for malformed or empty outlines.
+    pub(crate) fn new(glyph: &ScaledGlyph<'_>) -> Option<Self> {
+        let mut flattener = OutlineFlattener::new(FLATTEN_TOLERANCE);
+        glyph.build_outline(&mut flattener).then_some(Self {
+            segments: flattener.segments,
+        })
+    }
+
+    /// Rotates the outline by `angle` radians, clockwise in y-down coordinates.
+    pub(crate) fn rotate(&mut self, angle: f32) {
+        let (sin, cos) = angle.sin_cos();
+        let rotate = |p: Point<f32>| point(p.x * cos - p.y * sin, p.x * sin + p.y * cos);
+        for (a, b) in &mut self.segments {
+            *a = rotate(*a);
+            *b = rotate(*b);
+        }
+    }
+
+    /// Returns the minimum & maximum y of the outline.
+    pub(crate) fn y_range(&self) -> (f32, f32) {
+        self.segments
+            .iter()
+            .flat_map(|(a, b)| [a.y, b.y])
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), y| {
+                (min.min(y), max.max(y))
+            })
+    }
+
+    /// Returns the sorted, disjoint `(start_x, end_x)` intervals of the
+    /// horizontal line at `y` inside the outline, using the non-zero winding
+    /// rule.
+    pub(crate) fn crossings(&self, y: f32) -> Vec<(f32, f32)> {
+        let mut hits: Vec<(f32, i32)> = self
+            .segments
+            .iter()
+            .filter_map(|&(a, b)| {
+                let (top, bottom, winding) = if a.y < b.y { (a, b, 1) } else { (b, a, -1) };
+                // half-open so shared vertices are counted once
+                if y < top.y || y >= bottom.y {
+                    return None;
+                }
+                let t = (y - top.y) / (bottom.y - top.y);
+                Some((top.x + t * (bottom.x - top.x), winding))
+            })
+            .collect();
+        hits.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut intervals: Vec<(f32, f32)> = Vec::new();
+        let mut winding = 0;
+        let mut start = 0.0;
+        for (x, w) in hits {
+            let was_inside = winding != 0;
+            winding += w;
+            match (was_inside, winding != 0) {
+                (false, true) => start = x,
+                (true, false) if x > start => match intervals.last_mut() {
+                    Some(last) if last.1 >= start => last.1 = x,
+                    _ => intervals.push((start, x)),
+                },
+                _ => {}
+            }
+        }
+        intervals
+    }
+}
+
+impl ScaledGlyph<'_> {
+    /// Returns the intervals, `(start_x, end_x)`, of the horizontal line at
+    /// `y` that are inside the glyph, using the non-zero winding rule.
+    ///
+    /// Coordinates are relative to the glyph origin with y increasing
+    /// downwards, as with `build_outline`. Intervals are sorted & disjoint.
+    /// Curves are flattened to within 0.02 pixels.
+    ///
+    /// Useful for hatching, wrapping text around glyph ink, eg drop caps, &
+    /// custom rasterizers. Returns no intervals for empty or malformed
+    /// outlines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let glyph = font.glyph('O').scaled(Scale::uniform(40.0));
+    /// let middle = glyph.exact_bounding_box().map(|bb| (bb.min.y + bb.max.y) / 2.0).unwrap();
+    ///
+    /// // left & right sides of the 'O' ring
+    /// let crossings = glyph.scanline_crossings(middle);
+    /// assert_eq!(crossings.len(), 2);
+    /// assert!(crossings[0].1 < crossings[1].0);
+    /// ```
+    pub fn scanline_crossings(&self, y: f32) -> Vec<(f32, f32)> {
+        FlatOutline::new(self)
+            .map(|outline| outline.crossings(y))
+            .unwrap_or_default()
+    }
+
+    /// Returns hatch line segments filling the inside of the glyph, for
+    /// plotter or engraver output and stylistic fills.
+    ///
+    /// Lines are `spacing` pixels apart at `angle` radians, clockwise from
+    /// horizontal. Coordinates are relative to the glyph origin with y
+    /// increasing downwards, as with `build_outline`. Call again with another
+    /// angle for cross-hatching.
+    ///
+    /// Returns no segments for empty or malformed outlines, a non-positive or
+    /// non-finite `spacing`, or a non-finite `angle`. At most 65536 lines are
+    /// drawn, so a `spacing` tiny relative to the glyph leaves its far side
+    /// unhatched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let glyph = font.glyph('O').scaled(Scale::uniform(40.0));
+    /// let mut lines = glyph.hatch_lines(2.0, 45_f32.to_radians());
+    /// lines.extend(glyph.hatch_lines(2.0, -45_f32.to_radians()));
+    /// assert!(!lines.is_empty());
+    /// ```
+    pub fn hatch_lines(&self, spacing: f32, angle: f32) -> Vec<(Point<f32>, Point<f32>)> {
+        let mut outline = match FlatOutline::new(self) {
+            Some(outline) if spacing > 0.0 && spacing.is_finite() && angle.is_finite() => outline,
+            _ => return Vec::new(),
+        };
+        // rotate so hatch lines are horizontal, then rotate them back
+        outline.rotate(-angle);
+        let (sin, cos) = angle.sin_cos();
+        let unrotate = |x: f32, y: f32| point(x * cos - y * sin, x * sin + y * cos);
+
+        let (min_y, max_y) = outline.y_range();
+        let mut lines = Vec::new();
+        // step by line index, as repeatedly adding a spacing tiny relative
+        // to y may not advance it
+        let first = (min_y / spacing).ceil();
+        let count = ((max_y / spacing).ceil() - first).max(0.0);
+        let count = (count as usize).min(MAX_HATCH_LINES);
+        for i in 0..count {
+            let y = (first + i as f32) * spacing;
+            for (start, end) in outline.crossings(y) {
+                lines.push((unrotate(start, y), unrotate(end, y)));
+            }
+        }
+        lines
+    }
+}
@@ -0,0 +1,169 @@
+use crate::outliner::{OutlineFlattener, OutlineRasterizer};
+use crate::{point, Point, PositionedGlyph, ScaledGlyph};
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use owned_ttf_parser::OutlineBuilder;
+
+#[cfg(all(feature = "libm-math", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+
+impl ScaledGlyph<'_> {
+    /// Returns the glyph outline as closed polygons, curves flattened &
+    /// vertices removed, Douglas-Peucker style, while the polygons stay within
+    /// `tolerance` pixels of the true outline.
+    ///
+    /// Coordinates are relative to the glyph origin with y increasing
+    /// downwards, as with `build_outline`. Larger tolerances give fewer
+    /// vertices, eg for meshes of distant 3D text labels. Contours
+    /// simplified to fewer than 3 vertices are dropped. Returns no polygons
+    /// for empty or malformed outlines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let glyph = font.glyph('O').scaled(Scale::uniform(40.0));
+    /// let near = glyph.simplified_contours(0.05);
+    /// let far = glyph.simplified_contours(1.0);
+    ///
+    /// let vertices = |contours: &[Vec<_>]| contours.iter().map(Vec::len).sum::<usize>();
+    /// assert!(vertices(&far) < vertices(&near));
+    /// ```
+    pub fn simplified_contours(&self, tolerance: f32) -> Vec<Vec<Point<f32>>> {
+        // split the tolerance between flattening & vertex removal
+        let tolerance = tolerance.max(0.0) / 2.0;
+        let mut flattener = OutlineFlattener::new(tolerance.max(0.001));
+        if !self.build_outline(&mut flattener) {
+            return Vec::new();
+        }
+        let OutlineFlattener {
+            segments,
+            mut contour_starts,
+            ..
+        } = flattener;
+        contour_starts.push(segments.len());
+        contour_starts
+            .windows(2)
+            .filter_map(|range| {
+                let contour: Vec<_> = segments[range[0]..range[1]].iter().map(|s| s.0).collect();
+                let simplified = simplify_polygon(&contour, tolerance);
+                (simplified.len() >= 3).then_some(simplified)
+            })
+            .collect()
+    }
+
+    /// Builds the outline simplified as `simplified_contours` with
+    /// `builder`, as line segments. Returns `false` when the simplified
+    /// outline is empty.
+    pub fn build_simplified_outline(
+        &self,
+        tolerance: f32,
+        builder: &mut impl OutlineBuilder,
+    ) -> bool {
+        build_contours(
+            &self.simplified_contours(tolerance),
+            point(0.0, 0.0),
+            builder,
+        )
+    }
+}
+
+impl PositionedGlyph<'_> {
+    /// Rasterises this glyph as `draw`, with the outline simplified to
+    /// within `tolerance` pixels, see `ScaledGlyph::simplified_contours`.
+    ///
+    /// Simplification costs more than it saves for typical text, but reduces
+    /// raster cost of complex glyphs drawn small, eg distant 3D labels.
+    pub fn draw_simplified<O: FnMut(u32, u32, f32)>(&self, tolerance: f32, o: O) {
+        let Some(bb) = self.pixel_bounding_box() else {
+            return;
+        };
+        let width = bb.saturating_width() as usize;
+        let height = bb.saturating_height() as usize;
+        // vertices are points of the outline so within the bounding box
+        let offset = point(
+            self.position().x - bb.min.x as f32,
+            self.position().y - bb.min.y as f32,
+        );
+        let contours = self.unpositioned().simplified_contours(tolerance);
+        let mut rasterizer = OutlineRasterizer::new(width, height);
+        build_contours(&contours, offset, &mut rasterizer);
+        rasterizer.for_each_pixel(o);
+    }
+}
+
+fn build_contours(
+    contours: &[Vec<Point<f32>>],
+    offset: Point<f32>,
+    builder: &mut impl OutlineBuilder,
+) -> bool {
+    for contour in contours {
+        let mut points = contour
+            .iter()
+            .map(|p| point(p.x + offset.x, p.y + offset.y));
+        if let Some(first) = points.next() {
+            builder.move_to(first.x, first.y);
+            points.for_each(|p| builder.line_to(p.x, p.y));
+            builder.close();
+        }
+    }
+    !contours.is_empty()
+}
+
+/// Returns the vertices of the closed polygon `points` kept by
+/// Douglas-Peucker simplification with `tolerance`.
+fn simplify_polygon(points: &[Point<f32>], tolerance: f32) -> Vec<Point<f32>> {
+    if points.len() <= 3 {
+        return points.to_vec();
+    }
+    // split the ring at the first point & the point farthest from it
+    let distance_sq = |a: Point<f32>, b: Point<f32>| {
+        let d = b - a;
+        d.x * d.x + d.y * d.y
+    };
+    let far = (1..points.len())
+        .max_by(|&a, &b| {
+            distance_sq(points[0], points[a]).total_cmp(&distance_sq(points[0], points[b]))
+        })
+        .unwrap_or(0);
+
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[far] = true;
+    let mut stack = vec![(0, far), (far, points.len())];
+    while let Some((start, end)) = stack.pop() {
+        // `end` may be `points.len()`, wrapping to the first point
+        let (a, b) = (points[start], points[end % points.len()]);
+        let farthest = (start + 1..end)
+            .map(|i| (i, segment_distance(points[i], a, b)))
+            .max_by(|x, y| x.1.total_cmp(&y.1));
+        if let Some((i, distance)) = farthest {
+            if distance > tolerance {
+                keep[i] = true;
+                stack.push((start, i));
+                stack.push((i, end));
+            }
+        }
+    }
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(&p, keep)| keep.then_some(p))
+        .collect()
+}
+
+/// Returns the distance from `p` to the line segment `a` to `b`.
+fn segment_distance(p: Point<f32>, a: Point<f32>, b: Point<f32>) -> f32 {
+    let ab = b - a;
+    let ap = p - a;
+    let length_sq = ab.x * ab.x + ab.y * ab.y;
+    let t = if length_sq > 0.0 {
+        ((ap.x * ab.x + ap.y * ab.y) / length_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (dx, dy) = (ap.x - t * ab.x, ap.y - t * ab.y);
+    (dx * dx + dy * dy).sqrt()
+}
@@ -0,0 +1,378 @@
+//! A fixed capacity glyph texture cache that performs no heap allocation of
+//! its own, intended for embedded targets rendering a bounded set of glyphs.
+//!
+//! This module is optional, and not compiled by default. To use it enable the
+//! `static_cache` feature in your Cargo.toml. Unlike `gpu_cache` it does not
+//! require `std`.
+//!
+//! `StaticCache` divides a `W`x`H` texture into a grid of `MAX_GLYPHS` equally
+//! sized cells, each holding at most one glyph. All bookkeeping is stored in a
+//! fixed size array, so a cache can live in a `static` or on the stack. When
+//! all cells are in use the least recently used glyph is replaced.
+//!
+//! Compared with the dynamic `gpu_cache` packing is simple and space is wasted
+//! for glyphs smaller than a cell, but memory use is fixed and predictable.
+//! Note that glyph rasterization itself still uses a temporary heap buffer.
+//!
+//! # Example
+//!
+//! ```
+//! # use rusttype::{Font, point, Scale, static_cache::StaticCache};
+//! # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+//! # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+//! # let mut texture = [0u8; 128 * 128];
+//! // 128x128 texture holding up to 16 glyphs in 32x32 cells
+//! let mut cache: StaticCache<128, 128, 16> = StaticCache::new();
+//!
+//! let glyph = font.glyph('a').scaled(Scale::uniform(20.0)).positioned(point(4.0, 20.0));
+//! if let Ok(Some((uv_rect, screen_rect))) = cache.cache_glyph(0, &glyph, |x, y, v| {
+//!     texture[(y * 128 + x) as usize] = v;
+//! }) {
+//!     // Generate vertex data, etc
+//! }
+//! ```
+use crate::{point, vector, GlyphId, Point, PositionedGlyph, Rect, Vector};
+use core::fmt;
+
+#[cfg(all(feature = "libm-math", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+
+/// Texture coordinates (floating point) of the quad for a glyph in the cache,
+/// as well as the pixel-space (integer) coordinates that this region should be
+/// drawn at.
+pub type TextureCoords = (Rect<f32>, Rect<i32>);
+
+/// Number of cached subpixel offset steps per pixel in each dimension.
+const SUBPIXEL_STEPS: f32 = 4.0;
+
+/// Returned from `StaticCache::cache_glyph`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum StaticCacheErr {
+    /// The glyph, including a single pixel of padding, does not fit in a cell.
+    GlyphTooLarge,
+}
+
+impl fmt::Display for StaticCacheErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StaticCacheErr::GlyphTooLarge => "Glyph too large",
+        }
+        .fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StaticCacheErr {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct SlotKey {
+    font_id: usize,
+    glyph_id: GlyphId,
+    scale_bits: (u32, u32),
+    /// Subpixel offset in `[0, 1)` multiplied by `SUBPIXEL_STEPS`
+    offset_steps: (u8, u8),
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Slot {
+    key: SlotKey,
+    /// Pixel bounds of the glyph rasterized at the quantised offset
+    local_bb: Rect<i32>,
+    last_used: u32,
+}
+
+/// A fixed capacity glyph cache for a `W`x`H` texture holding up to
+/// `MAX_GLYPHS` glyphs. See the module documentation for more information.
+///
+/// `MAX_GLYPHS` must be at least `1`, a zero capacity cache fails to compile.
+///
+/// ```compile_fail
+/// # use rusttype::static_cache::StaticCache;
+/// let cache = StaticCache::<256, 256, 0>::new();
+/// ```
+#[derive(Clone)]
+pub struct StaticCache<const W: u32, const H: u32, const MAX_GLYPHS: usize> {
+    slots: [Option<Slot>; MAX_GLYPHS],
+    tick: u32,
+}
+
+impl<const W: u32, const H: u32, const MAX_GLYPHS: usize> Default
+    for StaticCache<W, H, MAX_GLYPHS>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: u32, const H: u32, const MAX_GLYPHS: usize> StaticCache<W, H, MAX_GLYPHS> {
+    /// Returns a new empty cache.
+    pub const fn new() -> Self {
+        const { assert!(MAX_GLYPHS > 0, "StaticCache needs MAX_GLYPHS > 0") };
+        Self {
+            slots: [None; MAX_GLYPHS],
+            tick: 0,
+        }
+    }
+
+    /// The number of cell columns & rows the texture is divided into.
+    pub const fn grid() -> (u32, u32) {
+        const { assert!(MAX_GLYPHS > 0, "StaticCache needs MAX_GLYPHS > 0") };
+        let mut columns = 1;
+        while (columns * columns) < MAX_GLYPHS as u32 {
+            columns += 1;
+        }
+        let rows = (MAX_GLYPHS as u32).div_ceil(columns);
+        (columns, rows)
+    }
+
+    /// The pixel dimensions of each cell. Glyphs, with a single pixel of
+    /// padding on all sides, must fit within a cell.
+    pub const fn cell_size() -> (u32, u32) {
+        let (columns, rows) = Self::grid();
+        (W / columns, H / rows)
+    }
+
+    /// Removes all glyphs from the cache.
+    pub fn clear(&mut self) {
+        self.slots = [None; MAX_GLYPHS];
+        self.tick = 0;
+    }
+
+    /// Ensures `glyph` is present in the cache, rasterizing it if necessary,
+    /// and returns its texture coordinates. `font_id` is used to disambiguate
+    /// glyphs from different fonts.
+    ///
+    /// When the glyph is not already cached, `writer` is called with texture
+    /// pixel coordinates & values to write the glyph, and its padding, into
+    /// the texture. If all cells are in use the least recently used glyph is
+    /// replaced, invalidating its texture coordinates.
+    ///
+    /// Returns `Ok(None)` for empty glyphs (no shape).
+    pub fn cache_glyph<F: FnMut(u32, u32, u8)>(
+        &mut self,
+        font_id: usize,
+        glyph: &PositionedGlyph<'_>,
+        mut writer: F,
+    ) -> Result<Option<TextureCoords>, StaticCacheErr> {
+        if glyph.pixel_bounding_box().is_none() {
+            return Ok(None);
+        }
+        self.tick = self.tick.wrapping_add(1);
+
+        let (key, offset) = Self::key_for(font_id, glyph);
+        if let Some(index) = self.position(&key) {
+            let slot = self.slots[index].as_mut().unwrap();
+            slot.last_used = self.tick;
+            let slot = *slot;
+            return Ok(Some(self.coords(index, &slot, glyph.position())));
+        }
+
+        let quantised = glyph
+            .unpositioned()
+            .clone()
+            .positioned_with_rounding(point(0.0, 0.0) + offset, glyph.rounding());
+        let local_bb = quantised.pixel_bounding_box().unwrap();
+        let (cell_width, cell_height) = Self::cell_size();
+        let (width, height) = (
+            (local_bb.saturating_width() as u32).saturating_add(2),
+            (local_bb.saturating_height() as u32).saturating_add(2),
+        );
+        if width > cell_width || height > cell_height {
+            return Err(StaticCacheErr::GlyphTooLarge);
+        }
+
+        // first empty, otherwise least recently used, slot
+        let index = match self.slots.iter().position(Option::is_none) {
+            Some(index) => index,
+            None => self
+                .slots
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, s)| self.tick.wrapping_sub(s.unwrap().last_used))
+                .map(|(index, _)| index)
+                .unwrap(),
+        };
+
+        let cell = Self::cell_min(index);
+        for y in 0..height {
+            for x in 0..width {
+                if x == 0 || y == 0 || x == width - 1 || y == height - 1 {
+                    writer(cell.x + x, cell.y + y, 0);
+                }
+            }
+        }
+        quantised.draw(|x, y, v| writer(cell.x + x + 1, cell.y + y + 1, (v * 255.0).round() as u8));
+
+        let slot = Slot {
+            key,
+            local_bb,
+            last_used: self.tick,
+        };
+        self.slots[index] = Some(slot);
+        Ok(Some(self.coords(index, &slot, glyph.position())))
+    }
+
+    /// Retrieves the texture coordinates of a cached glyph, if present. Does
+    /// not affect which glyph is least recently used.
+    ///
+    /// Returns `None` for uncached & empty glyphs.
+    pub fn rect_for(&self, font_id: usize, glyph: &PositionedGlyph<'_>) -> Option<TextureCoords> {
+        glyph.pixel_bounding_box()?;
+        let (key, _) = Self::key_for(font_id, glyph);
+        let index = self.position(&key)?;
+        Some(self.coords(index, self.slots[index].as_ref()?, glyph.position()))
+    }
+
+    /// Returns the number of cached glyphs.
+    pub fn len(&self) -> usize {
+        self.slots.iter().filter(|s| s.is_some()).count()
+    }
+
+    /// Returns `true` if no glyphs are cached.
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(Option::is_none)
+    }
+
+    fn position(&self, key: &SlotKey) -> Option<usize> {
+        self.slots
+            .iter()
+            .position(|s| matches!(s, Some(s) if s.key == *key))
+    }
+
+    fn key_for(font_id: usize, glyph: &PositionedGlyph<'_>) -> (SlotKey, Vector<f32>) {
+        let position = glyph.position();
+        let step = |p: f32| {
+            let fract = p - p.floor();
+            ((fract * SUBPIXEL_STEPS).round() as u8) % SUBPIXEL_STEPS as u8
+        };
+        let offset_steps = (step(position.x), step(position.y));
+        let scale = glyph.scale();
+        let key = SlotKey {
+            font_id,
+            glyph_id: glyph.id(),
+            scale_bits: (scale.x.to_bits(), scale.y.to_bits()),
+            offset_steps,
+        };
+        let offset = vector(
+            offset_steps.0 as f32 / SUBPIXEL_STEPS,
+            offset_steps.1 as f32 / SUBPIXEL_STEPS,
+        );
+        (key, offset)
+    }
+
+    fn cell_min(index: usize) -> Point<u32> {
+        let (columns, _) = Self::grid();
+        let (cell_width, cell_height) = Self::cell_size();
+        let index = index as u32;
+        point(
+            (index % columns) * cell_width,
+            (index / columns) * cell_height,
+        )
+    }
+
+    fn coords(&self, index: usize, slot: &Slot, position: Point<f32>) -> TextureCoords {
+        let cell = Self::cell_min(index);
+        let (width, height) = (slot.local_bb.width() as u32, slot.local_bb.height() as u32);
+        let uv_rect = Rect {
+            min: point(
+                (cell.x + 1) as f32 / W as f32,
+                (cell.y + 1) as f32 / H as f32,
+            ),
+            max: point(
+                (cell.x + 1 + width) as f32 / W as f32,
+                (cell.y + 1 + height) as f32 / H as f32,
+            ),
+        };
+
+        let offset = vector(
+            slot.key.offset_steps.0 as f32 / SUBPIXEL_STEPS,
+            slot.key.offset_steps.1 as f32 / SUBPIXEL_STEPS,
+        );
+        let origin = point(position.x.floor(), position.y.floor());
+        let ideal_min = point(
+            slot.local_bb.min.x as f32 - offset.x,
+            slot.local_bb.min.y as f32 - offset.y,
+        ) + (position - origin);
+        let min = point(
+            origin.x as i32 + ideal_min.x.round() as i32,
+            origin.y as i32 + ideal_min.y.round() as i32,
+        );
+        let screen_rect = Rect {
+            min,
+            max: point(min.x + width as i32, min.y + height as i32),
+        };
+        (uv_rect, screen_rect)
+    }
+}
+
+impl<const W: u32, const H: u32, const MAX_GLYPHS: usize> fmt::Debug
+    for StaticCache<W, H, MAX_GLYPHS>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticCache")
+            .field("dimensions", &(W, H))
+            .field("max_glyphs", &MAX_GLYPHS)
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Font, Scale};
+
+    #[test]
+    fn grid_cells() {
+        assert_eq!(StaticCache::<128, 128, 16>::grid(), (4, 4));
+        assert_eq!(StaticCache::<128, 128, 16>::cell_size(), (32, 32));
+        assert_eq!(StaticCache::<100, 60, 5>::grid(), (3, 2));
+        assert_eq!(StaticCache::<100, 60, 5>::cell_size(), (33, 30));
+    }
+
+    #[test]
+    fn cache_and_replace_lru() {
+        let font_data = include_bytes!("../dev/fonts/wqy-microhei/WenQuanYiMicroHei.ttf");
+        let font = Font::try_from_bytes(font_data as &[u8]).unwrap();
+        let glyph = |c| {
+            font.glyph(c)
+                .scaled(Scale::uniform(16.0))
+                .positioned(point(10.0, 20.0))
+        };
+
+        let mut cache: StaticCache<64, 32, 2> = StaticCache::new();
+        let mut writes = 0;
+        let a = cache
+            .cache_glyph(0, &glyph('a'), |_, _, _| writes += 1)
+            .unwrap()
+            .unwrap();
+        assert!(writes > 0);
+        assert_eq!(a.1, glyph('a').pixel_bounding_box().unwrap());
+
+        // already cached
+        writes = 0;
+        cache
+            .cache_glyph(0, &glyph('a'), |_, _, _| writes += 1)
+            .unwrap();
+        assert_eq!(writes, 0);
+
+        cache.cache_glyph(0, &glyph('b'), |_, _, _| {}).unwrap();
+        cache.cache_glyph(0, &glyph('a'), |_, _, _| {}).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        // 'b' is least recently used
+        cache.cache_glyph(0, &glyph('c'), |_, _, _| {}).unwrap();
+        assert!(cache.rect_for(0, &glyph('a')).is_some());
+        assert!(cache.rect_for(0, &glyph('b')).is_none());
+        assert!(cache.rect_for(1, &glyph('c')).is_none());
+
+        let large = font
+            .glyph('W')
+            .scaled(Scale::uniform(60.0))
+            .positioned(point(0.0, 0.0));
+        assert_eq!(
+            cache.cache_glyph(0, &large, |_, _, _| {}),
+            Err(StaticCacheErr::GlyphTooLarge)
+        );
+    }
+}
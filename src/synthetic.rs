@@ -0,0 +1,274 @@
+use crate::{point, Font, FontApi, GlyphId, HMetrics, Point, Rect, VMetrics};
+use alloc::collections::BTreeMap;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt;
+use owned_ttf_parser::OutlineBuilder;
+
+/// A segment of an `Outline`, in font units with y-up.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum OutlineSegment {
+    MoveTo(Point<f32>),
+    LineTo(Point<f32>),
+    QuadTo(Point<f32>, Point<f32>),
+    CurveTo(Point<f32>, Point<f32>, Point<f32>),
+    Close,
+}
+
+/// A glyph outline, recorded from `OutlineBuilder` calls, for the glyphs of
+/// a `SyntheticFont`.
+///
+/// An outline of a font glyph can be recorded with `ScaledGlyph::build_outline`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Outline {
+    segments: Vec<OutlineSegment>,
+}
+
+impl Outline {
+    /// Creates an empty outline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a closed rectangle outline, in font units with y-up.
+    pub fn rect(min: Point<f32>, max: Point<f32>) -> Self {
+        let mut outline = Self::new();
+        outline.move_to(min.x, min.y);
+        outline.line_to(max.x, min.y);
+        outline.line_to(max.x, max.y);
+        outline.line_to(min.x, max.y);
+        outline.close();
+        outline
+    }
+
+    #[inline]
+    pub fn segments(&self) -> &[OutlineSegment] {
+        &self.segments
+    }
+
+    /// Replays the recorded segments into `builder`.
+    pub fn build(&self, builder: &mut dyn OutlineBuilder) {
+        for segment in &self.segments {
+            match *segment {
+                OutlineSegment::MoveTo(p) => builder.move_to(p.x, p.y),
+                OutlineSegment::LineTo(p) => builder.line_to(p.x, p.y),
+                OutlineSegment::QuadTo(c, p) => builder.quad_to(c.x, c.y, p.x, p.y),
+                OutlineSegment::CurveTo(c1, c2, p) => {
+                    builder.curve_to(c1.x, c1.y, c2.x, c2.y, p.x, p.y)
+                }
+                OutlineSegment::Close => builder.close(),
+            }
+        }
+    }
+
+    /// Returns the bounds of all points, including control points, `None` if
+    /// the outline is empty.
+    pub fn bounding_box(&self) -> Option<Rect<f32>> {
+        self.segments
+            .iter()
+            .flat_map(|segment| match *segment {
+                OutlineSegment::MoveTo(p) | OutlineSegment::LineTo(p) => [Some(p), None, None],
+                OutlineSegment::QuadTo(c, p) => [Some(c), Some(p), None],
+                OutlineSegment::CurveTo(c1, c2, p) => [Some(c1), Some(c2), Some(p)],
+                OutlineSegment::Close => [None; 3],
+            })
+            .flatten()
+            .fold(None, |bb: Option<Rect<f32>>, p| {
+                Some(bb.map_or(Rect { min: p, max: p }, |bb| Rect {
+                    min: point(bb.min.x.min(p.x), bb.min.y.min(p.y)),
+                    max: point(bb.max.x.max(p.x), bb.max.y.max(p.y)),
+                }))
+            })
+    }
+}
+
+impl OutlineBuilder for Outline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.segments.push(OutlineSegment::MoveTo(point(x, y)));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.segments.push(OutlineSegment::LineTo(point(x, y)));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.segments
+            .push(OutlineSegment::QuadTo(point(x1, y1), point(x, y)));
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.segments.push(OutlineSegment::CurveTo(
+            point(x1, y1),
+            point(x2, y2),
+            point(x, y),
+        ));
+    }
+
+    fn close(&mut self) {
+        self.segments.push(OutlineSegment::Close);
+    }
+}
+
+/// An in-memory font built from outlines & metrics, implementing `FontApi`,
+/// for tests of layout logic without binary font fixtures. See
+/// `Font::synthetic`.
+///
+/// Glyph `0` is an empty ".notdef" glyph that unmapped chars map to, the
+/// given glyphs follow in order from glyph `1`. The font defaults to 1000
+/// units per em, an ascent of 800, a descent of -200 & no kerning.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyntheticFont {
+    units_per_em: u16,
+    v_metrics: VMetrics,
+    glyphs: Vec<(Outline, HMetrics)>,
+    cmap: BTreeMap<char, GlyphId>,
+    kerning: BTreeMap<(GlyphId, GlyphId), f32>,
+}
+
+impl SyntheticFont {
+    /// Returns the outline & metrics of a glyph, ".notdef" for out of range
+    /// ids.
+    fn glyph(&self, id: GlyphId) -> &(Outline, HMetrics) {
+        self.glyphs
+            .get(usize::from(id.0))
+            .unwrap_or(&self.glyphs[0])
+    }
+
+    /// Sets the units per em.
+    pub fn with_units_per_em(mut self, units_per_em: u16) -> Self {
+        self.units_per_em = units_per_em;
+        self
+    }
+
+    /// Sets the unscaled vertical metrics.
+    pub fn with_v_metrics(mut self, v_metrics: VMetrics) -> Self {
+        self.v_metrics = v_metrics;
+        self
+    }
+
+    /// Sets the unscaled kerning between the glyphs of a pair of chars. Pairs
+    /// with unmapped chars are ignored.
+    pub fn with_kerning(mut self, first: char, second: char, kerning: f32) -> Self {
+        if let (Some(&first), Some(&second)) = (self.cmap.get(&first), self.cmap.get(&second)) {
+            self.kerning.insert((first, second), kerning);
+        }
+        self
+    }
+}
+
+/// Error building a `SyntheticFont`, see `Font::synthetic`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SyntheticFontError {
+    /// More glyphs than fit in a `GlyphId`, 65535 besides ".notdef".
+    TooManyGlyphs,
+}
+
+impl fmt::Display for SyntheticFontError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SyntheticFontError::TooManyGlyphs => "Too many glyphs for a synthetic font",
+        }
+        .fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SyntheticFontError {}
+
+impl Font<'_> {
+    /// Builds a `SyntheticFont` from `(char, outline, metrics)` glyphs, with
+    /// outlines & horizontal metrics in font units. Later glyphs for the same
+    /// char replace the mapping of earlier ones.
+    ///
+    /// Fails with more than 65535 glyphs, which can't all be given ids.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rusttype::{point, Font, FontApi, HMetrics, Outline, Scale};
+    ///
+    /// let metrics = HMetrics {
+    ///     advance_width: 600.0,
+    ///     left_side_bearing: 100.0,
+    /// };
+    /// let square = Outline::rect(point(100.0, 0.0), point(500.0, 400.0));
+    /// let font = Font::synthetic([('a', square.clone(), metrics), ('b', square, metrics)])
+    ///     .unwrap()
+    ///     .with_kerning('a', 'b', -100.0);
+    ///
+    /// let scale = Scale::uniform(10.0);
+    /// let (a, b) = (font.glyph_id('a'), font.glyph_id('b'));
+    /// assert_eq!(font.glyph_h_metrics(a, scale).advance_width, 6.0);
+    /// assert_eq!(font.pair_kerning(scale, a, b), -1.0);
+    /// ```
+    pub fn synthetic<I>(glyphs: I) -> Result<SyntheticFont, SyntheticFontError>
+    where
+        I: IntoIterator<Item = (char, Outline, HMetrics)>,
+    {
+        let notdef = HMetrics {
+            advance_width: 0.0,
+            left_side_bearing: 0.0,
+        };
+        let mut font = SyntheticFont {
+            units_per_em: 1000,
+            v_metrics: VMetrics {
+                ascent: 800.0,
+                descent: -200.0,
+                line_gap: 0.0,
+            },
+            glyphs: alloc::vec![(Outline::new(), notdef)],
+            cmap: BTreeMap::new(),
+            kerning: BTreeMap::new(),
+        };
+        for (c, outline, h_metrics) in glyphs {
+            let id =
+                u16::try_from(font.glyphs.len()).map_err(|_| SyntheticFontError::TooManyGlyphs)?;
+            font.glyphs.push((outline, h_metrics));
+            font.cmap.insert(c, GlyphId(id));
+        }
+        Ok(font)
+    }
+}
+
+impl FontApi for SyntheticFont {
+    #[inline]
+    fn units_per_em(&self) -> u16 {
+        self.units_per_em
+    }
+
+    #[inline]
+    fn glyph_count(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    #[inline]
+    fn glyph_id(&self, c: char) -> GlyphId {
+        self.cmap.get(&c).copied().unwrap_or(GlyphId(0))
+    }
+
+    #[inline]
+    fn v_metrics_unscaled(&self) -> VMetrics {
+        self.v_metrics
+    }
+
+    #[inline]
+    fn glyph_h_metrics_unscaled(&self, id: GlyphId) -> HMetrics {
+        self.glyph(id).1
+    }
+
+    #[inline]
+    fn pair_kerning_unscaled(&self, first: GlyphId, second: GlyphId) -> f32 {
+        self.kerning.get(&(first, second)).copied().unwrap_or(0.0)
+    }
+
+    #[inline]
+    fn glyph_bounding_box_unscaled(&self, id: GlyphId) -> Option<Rect<f32>> {
+        self.glyph(id).0.bounding_box()
+    }
+
+    fn build_outline_unscaled(&self, id: GlyphId, builder: &mut dyn OutlineBuilder) -> bool {
+        let outline = &self.glyph(id).0;
+        outline.build(builder);
+        !outline.segments.is_empty()
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{point, Font, Scale, WidthMetric};
+
+/// Text measurements matching the HTML canvas `measureText` `TextMetrics`,
+/// see `Font::measure_text`.
+///
+/// Distances are in pixels from the start of the text on the alphabetic
+/// baseline, as canvas `textAlign = "left"` & `textBaseline = "alphabetic"`,
+/// positive in the direction of the field name.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TextMetrics {
+    /// Advance width of the text, `width`.
+    pub width: f32,
+    /// Distance the ink extends left of the start, `actualBoundingBoxLeft`.
+    pub actual_bounding_box_left: f32,
+    /// Distance the ink extends right of the start, `actualBoundingBoxRight`.
+    pub actual_bounding_box_right: f32,
+    /// Distance the ink extends above the baseline,
+    /// `actualBoundingBoxAscent`.
+    pub actual_bounding_box_ascent: f32,
+    /// Distance the ink extends below the baseline,
+    /// `actualBoundingBoxDescent`.
+    pub actual_bounding_box_descent: f32,
+    /// The font's ascent, `fontBoundingBoxAscent`.
+    pub font_bounding_box_ascent: f32,
+    /// The font's descent, `fontBoundingBoxDescent`.
+    pub font_bounding_box_descent: f32,
+    /// Distance of the top of the em square above the baseline,
+    /// `emHeightAscent`.
+    pub em_height_ascent: f32,
+    /// Distance of the bottom of the em square below the baseline,
+    /// `emHeightDescent`.
+    pub em_height_descent: f32,
+}
+
+impl Font<'_> {
+    /// Measures `text` laid out with `layout` as the HTML canvas
+    /// `measureText`, for apps mirroring canvas layout.
+    ///
+    /// Canvas font sizes are em sizes, so use `scale_for_em_height` to match
+    /// a CSS font size. The em square is split above & below the baseline in
+    /// proportion to the font's ascent & descent, as browsers do. Ink bounds
+    /// are zero for text without outlines, eg spaces.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::Font;
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// // ctx.font = "16px DejaVu Sans Mono"; ctx.measureText("Hello")
+    /// let metrics = font.measure_text("Hello", font.scale_for_em_height(16.0));
+    ///
+    /// assert_eq!(metrics.em_height_ascent + metrics.em_height_descent, 16.0);
+    /// assert!(metrics.actual_bounding_box_ascent > 0.0);
+    /// ```
+    pub fn measure_text(&self, text: &str, scale: Scale) -> TextMetrics {
+        let v_metrics = self.v_metrics(scale);
+        let em = self.px_per_em(scale);
+        let font_height = v_metrics.ascent - v_metrics.descent;
+        let em_height_ascent = if font_height > 0.0 {
+            em * v_metrics.ascent / font_height
+        } else {
+            em
+        };
+
+        let ink = self
+            .layout(text, scale, point(0.0, 0.0))
+            .filter_map(|g| {
+                let bb = g.unpositioned().exact_bounding_box()?;
+                let p = g.position();
+                Some((
+                    point(bb.min.x + p.x, bb.min.y + p.y),
+                    point(bb.max.x + p.x, bb.max.y + p.y),
+                ))
+            })
+            .reduce(|(min_a, max_a), (min_b, max_b)| {
+                (
+                    point(min_a.x.min(min_b.x), min_a.y.min(min_b.y)),
+                    point(max_a.x.max(max_b.x), max_a.y.max(max_b.y)),
+                )
+            });
+        let (min, max) = ink.unwrap_or((point(0.0, 0.0), point(0.0, 0.0)));
+
+        TextMetrics {
+            width: self.measure_width(text, scale, WidthMetric::Advance),
+            actual_bounding_box_left: -min.x,
+            actual_bounding_box_right: max.x,
+            actual_bounding_box_ascent: -min.y,
+            actual_bounding_box_descent: max.y,
+            font_bounding_box_ascent: v_metrics.ascent,
+            font_bounding_box_descent: -v_metrics.descent,
+            em_height_ascent,
+            em_height_descent: em - em_height_ascent,
+        }
+    }
+}
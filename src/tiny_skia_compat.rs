@@ -0,0 +1,84 @@
+//! Filling glyphs into [tiny-skia](https://docs.rs/tiny-skia) pixmaps.
+use crate::{OutlineBuilder, PositionedGlyph};
+use tiny_skia::{FillRule, Mask, Paint, Path, PathBuilder, PixmapMut, Transform};
+
+struct SkiaBuilder(PathBuilder);
+
+impl OutlineBuilder for SkiaBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.0.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.0.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.0.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.0.cubic_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.0.close();
+    }
+}
+
+impl PositionedGlyph<'_> {
+    /// Builds the outline of the glyph into a tiny-skia `Path` at its
+    /// position.
+    ///
+    /// Note: Unlike `build_outline`, coordinates are not relative to the pixel
+    /// bounding box.
+    ///
+    /// Returns `None` when the outline is either malformed or empty.
+    ///
+    /// Requires the `tiny_skia` feature.
+    pub fn tiny_skia_path(&self) -> Option<Path> {
+        let mut builder = SkiaBuilder(PathBuilder::new());
+        if !self.unpositioned().build_outline(&mut builder) {
+            return None;
+        }
+        let position = self.position();
+        builder
+            .0
+            .finish()?
+            .transform(Transform::from_translate(position.x, position.y))
+    }
+
+    /// Fills the glyph into `pixmap` at its position with `paint`.
+    ///
+    /// Blending follows `paint.blend_mode` and drawing is clipped to the
+    /// pixmap, and optionally a `mask`. Anti-aliasing is done by tiny-skia so
+    /// coverage may differ very slightly from `draw`.
+    ///
+    /// Requires the `tiny_skia` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rusttype::{point, Font, Scale};
+    /// # let font_data: &[u8] = include_bytes!("../dev/fonts/dejavu/DejaVuSansMono.ttf");
+    /// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+    /// let mut pixmap = tiny_skia::Pixmap::new(100, 40).unwrap();
+    /// let mut paint = tiny_skia::Paint::default();
+    /// paint.set_color_rgba8(200, 30, 30, 255);
+    ///
+    /// for glyph in font.layout("Hello", Scale::uniform(32.0), point(4.0, 30.0)) {
+    ///     glyph.fill_tiny_skia(&mut pixmap.as_mut(), &paint, None);
+    /// }
+    /// # assert!(pixmap.pixels().iter().any(|p| p.alpha() == 255));
+    /// ```
+    pub fn fill_tiny_skia(
+        &self,
+        pixmap: &mut PixmapMut<'_>,
+        paint: &Paint<'_>,
+        mask: Option<&Mask>,
+    ) {
+        if let Some(path) = self.tiny_skia_path() {
+            pixmap.fill_path(&path, paint, FillRule::Winding, Transform::identity(), mask);
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Font inspection tools, for debugging fonts & reporting rendering issues.
+use crate::{point, Font, IntoGlyphId, PositionedGlyph, Scale};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+#[cfg(all(feature = "libm-math", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+
+/// A raster comparing a glyph pair drawn without & with kerning, see
+/// `kerning_preview`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KerningPreview {
+    /// The kerning adjustment between the pair in pixels.
+    pub kerning: f32,
+    /// Width of the raster in pixels.
+    pub width: u32,
+    /// Height of each of the two rows of the raster in pixels.
+    pub row_height: u32,
+    /// 8-bit coverage, row major. The top `row_height` rows show the pair
+    /// without kerning, the bottom rows with kerning.
+    pub pixels: Vec<u8>,
+}
+
+impl KerningPreview {
+    /// Returns the coverage of the pair drawn without kerning.
+    #[inline]
+    pub fn unkerned(&self) -> &[u8] {
+        &self.pixels[..self.pixels.len() / 2]
+    }
+
+    /// Returns the coverage of the pair drawn with kerning.
+    #[inline]
+    pub fn kerned(&self) -> &[u8] {
+        &self.pixels[self.pixels.len() / 2..]
+    }
+}
+
+/// Draws a glyph pair at `scale` without & with kerning, one above the
+/// other, for visual inspection of kerning, eg in font inspection tools or
+/// kerning bug reports.
+///
+/// The first glyph is drawn at the same position in both rows so the
+/// adjustment shows as a horizontal shift of the second glyph. The raster
+/// fits the ink of all glyphs & the font's ascent to descent.
+///
+/// Returns `None` if either glyph id is not in the font.
+///
+/// # Example
+///
+/// ```
+/// # use rusttype::{Font, Scale};
+/// # let font_data: &[u8] = include_bytes!("../dev/fonts/opensans/OpenSans-Italic.ttf");
+/// # let font: Font<'static> = Font::try_from_bytes(font_data).unwrap();
+/// let preview = rusttype::tools::kerning_preview(&font, 'A', 'V', Scale::uniform(32.0)).unwrap();
+///
+/// assert!(preview.kerning < 0.0);
+/// assert_ne!(preview.unkerned(), preview.kerned());
+/// ```
+pub fn kerning_preview<A, B>(
+    font: &Font<'_>,
+    first: A,
+    second: B,
+    scale: Scale,
+) -> Option<KerningPreview>
+where
+    A: IntoGlyphId,
+    B: IntoGlyphId,
+{
+    let first = font.try_glyph(first)?.scaled(scale);
+    let second = font.try_glyph(second)?.scaled(scale);
+    let kerning = font.pair_kerning(scale, first.id(), second.id());
+
+    // lay out on a baseline at the origin, then shift to fit the raster
+    let advance = first.h_metrics().advance_width;
+    let rows = [
+        [
+            first.clone().positioned(point(0.0, 0.0)),
+            second.clone().positioned(point(advance, 0.0)),
+        ],
+        [
+            first.positioned(point(0.0, 0.0)),
+            second.positioned(point(advance + kerning, 0.0)),
+        ],
+    ];
+
+    let v_metrics = font.v_metrics(scale);
+    let (mut min_x, mut max_x) = (0, 0);
+    let (mut min_y, mut max_y) = (
+        -v_metrics.ascent.ceil() as i32,
+        -v_metrics.descent.floor() as i32,
+    );
+    for glyph in rows.iter().flatten() {
+        let caret = glyph.position().x + glyph.unpositioned().h_metrics().advance_width;
+        min_x = min_x.min(glyph.position().x.floor() as i32);
+        max_x = max_x.max(caret.ceil() as i32);
+        if let Some(bb) = glyph.pixel_bounding_box() {
+            min_x = min_x.min(bb.min.x);
+            min_y = min_y.min(bb.min.y);
+            max_x = max_x.max(bb.max.x);
+            max_y = max_y.max(bb.max.y);
+        }
+    }
+    let width = (max_x - min_x).max(0) as u32;
+    let row_height = (max_y - min_y).max(0) as u32;
+
+    // shifting by whole pixels keeps the rasterised glyphs unchanged
+    let shift = point(-min_x as f32, -min_y as f32);
+    let row_len = width as usize * row_height as usize;
+    let mut pixels = alloc::vec![0; 2 * row_len];
+    for (row, glyphs) in rows.iter().enumerate() {
+        let offset = row * row_len;
+        for glyph in glyphs {
+            let position = glyph.position();
+            let glyph = glyph
+                .clone()
+                .into_unpositioned()
+                .positioned(point(position.x + shift.x, position.y + shift.y));
+            draw_max(
+                &glyph,
+                &mut pixels[offset..offset + row_len],
+                width,
+                row_height,
+            );
+        }
+    }
+
+    Some(KerningPreview {
+        kerning,
+        width,
+        row_height,
+        pixels,
+    })
+}
+
+/// Draws into a coverage buffer keeping the max coverage of overlapping
+/// glyphs, clipping at the buffer edges.
+fn draw_max(glyph: &PositionedGlyph<'_>, pixels: &mut [u8], width: u32, height: u32) {
+    let Some(bb) = glyph.pixel_bounding_box() else {
+        return;
+    };
+    glyph.draw(|x, y, v| {
+        let x = bb.min.x + x as i32;
+        let y = bb.min.y + y as i32;
+        if x >= 0 && y >= 0 && (x as u32) < width && (y as u32) < height {
+            let pixel = &mut pixels[y as usize * width as usize + x as usize];
+            *pixel = (*pixel).max((v * 255.0).round() as u8);
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GlyphId;
+
+    fn open_sans_italic() -> Font<'static> {
+        let font_data = include_bytes!("../dev/fonts/opensans/OpenSans-Italic.ttf");
+        Font::try_from_bytes(font_data as &[u8]).unwrap()
+    }
+
+    /// Returns the total coverage of each row.
+    fn ink(preview: &KerningPreview) -> (u32, u32) {
+        let sum = |pixels: &[u8]| pixels.iter().map(|&v| u32::from(v)).sum();
+        (sum(preview.unkerned()), sum(preview.kerned()))
+    }
+
+    #[test]
+    fn unclipped_overhangs() {
+        let font = open_sans_italic();
+        let scale = Scale::uniform(48.0);
+        // italic "j" overhangs left & descends, "f" overhangs its advance
+        for (first, second) in [('j', 'f'), ('f', 'j'), ('A', 'V')] {
+            let preview = kerning_preview(&font, first, second, scale).unwrap();
+            assert_eq!(
+                preview.pixels.len(),
+                2 * (preview.width * preview.row_height) as usize
+            );
+
+            // rows hold as much ink as drawing onto a roomy canvas
+            let first = font.glyph(first).scaled(scale);
+            let second = font.glyph(second).scaled(scale);
+            let advance = first.h_metrics().advance_width;
+            let canvas_ink = |kerning: f32| {
+                let mut canvas = alloc::vec![0; 200 * 200];
+                let at = |x: f32| point(50.0 + x, 100.0);
+                draw_max(&first.clone().positioned(at(0.0)), &mut canvas, 200, 200);
+                draw_max(
+                    &second.clone().positioned(at(advance + kerning)),
+                    &mut canvas,
+                    200,
+                    200,
+                );
+                canvas.iter().map(|&v| u32::from(v)).sum::<u32>()
+            };
+            assert_eq!(
+                ink(&preview),
+                (canvas_ink(0.0), canvas_ink(preview.kerning))
+            );
+        }
+    }
+
+    #[test]
+    fn first_glyph_shared_by_rows() {
+        let font = open_sans_italic();
+        let preview = kerning_preview(&font, 'A', 'V', Scale::uniform(32.0)).unwrap();
+        assert!(preview.kerning < 0.0);
+
+        // the left columns only show the first glyph
+        let width = preview.width as usize;
+        let column = |pixels: &[u8], x: usize| -> Vec<u8> {
+            pixels.chunks(width).map(|row| row[x]).collect()
+        };
+        assert_eq!(column(preview.unkerned(), 1), column(preview.kerned(), 1));
+    }
+
+    #[test]
+    fn invalid_glyph_ids() {
+        let font = open_sans_italic();
+        let count = font.glyph_count() as u16;
+        let scale = Scale::uniform(20.0);
+        assert!(kerning_preview(&font, GlyphId(count), 'a', scale).is_none());
+        assert!(kerning_preview(&font, 'a', GlyphId(u16::MAX), scale).is_none());
+        assert!(kerning_preview(&font, GlyphId(count - 1), 'a', scale).is_some());
+    }
+}